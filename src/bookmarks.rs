@@ -0,0 +1,141 @@
+// Bookmarks kept across runs, each with tags to filter the bookmarks view by
+// Stored as JSON in the data directory. Unknown fields are ignored and missing
+// ones take defaults, so files written by other versions of bbow still load.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::path::{Path, PathBuf};
+
+const FILE_NAME: &str = "bookmarks.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Bookmark {
+    pub url: String,
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default = "Utc::now")]
+    pub added_at: DateTime<Utc>,
+}
+
+/// Layout of `bookmarks.json`; an object rather than a bare list so fields
+/// can be added next to the bookmarks later
+#[derive(Default, Serialize, Deserialize)]
+struct BookmarkFile {
+    #[serde(default)]
+    bookmarks: Vec<Bookmark>,
+}
+
+#[derive(Default)]
+pub struct Bookmarks {
+    /// Where the bookmarks are saved, `None` when there is no data directory
+    /// or the file could not be read (so it is never overwritten)
+    path: Option<PathBuf>,
+    items: Vec<Bookmark>,
+}
+
+impl Bookmarks {
+    /// Bookmarks saved in `dir`; none yet when the file does not exist
+    pub fn load(dir: Option<&Path>) -> Result<Self> {
+        let Some(path) = dir.map(|dir| dir.join(FILE_NAME)) else {
+            return Ok(Self::default());
+        };
+        let items = match std::fs::read_to_string(&path) {
+            Ok(contents) => {
+                serde_json::from_str::<BookmarkFile>(&contents)
+                    .with_context(|| format!("Invalid bookmarks file {}", path.display()))?
+                    .bookmarks
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => {
+                return Err(e).with_context(|| format!("Failed to read {}", path.display()));
+            }
+        };
+        Ok(Self {
+            path: Some(path),
+            items,
+        })
+    }
+
+    pub fn save(&self) -> Result<PathBuf> {
+        let path = self
+            .path
+            .clone()
+            .context("Bookmarks can't be saved: no usable data directory")?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        let file = BookmarkFile {
+            bookmarks: self.items.clone(),
+        };
+        std::fs::write(&path, serde_json::to_string_pretty(&file)?)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(path)
+    }
+
+    pub fn get(&self, url: &str) -> Option<&Bookmark> {
+        self.items.iter().find(|bookmark| bookmark.url == url)
+    }
+
+    /// Bookmark `url`, or give an existing bookmark a new title and tags
+    pub fn set(&mut self, url: &str, title: &str, tags: Vec<String>) {
+        match self.items.iter_mut().find(|bookmark| bookmark.url == url) {
+            Some(bookmark) => {
+                bookmark.title = title.to_string();
+                bookmark.tags = tags;
+            }
+            None => self.items.push(Bookmark {
+                url: url.to_string(),
+                title: title.to_string(),
+                tags,
+                added_at: Utc::now(),
+            }),
+        }
+    }
+
+    /// Remove the bookmark of `url`; `false` when there was none
+    pub fn remove(&mut self, url: &str) -> bool {
+        let before = self.items.len();
+        self.items.retain(|bookmark| bookmark.url != url);
+        self.items.len() != before
+    }
+
+    /// Bookmarks with a tag starting with each word of `filter` (ignoring
+    /// case and a leading `#`), newest first. An empty filter matches all.
+    pub fn matching(&self, filter: &str) -> Vec<&Bookmark> {
+        let words: Vec<String> = filter
+            .split_whitespace()
+            .map(|word| word.trim_start_matches('#').to_lowercase())
+            .collect();
+        let mut matches: Vec<&Bookmark> = self
+            .items
+            .iter()
+            .filter(|bookmark| {
+                words.iter().all(|word| {
+                    bookmark
+                        .tags
+                        .iter()
+                        .any(|tag| tag.to_lowercase().starts_with(word.as_str()))
+                })
+            })
+            .collect();
+        matches.sort_by_key(|bookmark| Reverse(bookmark.added_at));
+        matches
+    }
+}
+
+/// Tags typed as words, e.g. "rust #async", without `#` and duplicates
+pub fn parse_tags(text: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in text.split_whitespace() {
+        let tag = tag.trim_start_matches('#');
+        if !tag.is_empty() && !tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+            tags.push(tag.to_string());
+        }
+    }
+    tags
+}
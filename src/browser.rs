@@ -1,110 +1,649 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+use futures::stream::{self, StreamExt};
+use std::borrow::Cow;
+use std::cmp::Reverse;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
 use url::Url;
 
 use crate::{
-    client::WebClient,
-    extractor::TextExtractor,
-    history::History,
-    links::{Link, LinkExtractor},
-    openai::OpenAIClient,
-    ui::{BrowserState, HistoryEntry, UIInterface, UserAction},
+    bookmarks::{self, Bookmarks},
+    cache::LruCache,
+    client::{FetchError, FetchedPage, WebClient},
+    common::{
+        clipboard, editor,
+        graphics::{ImageOverlay, Protocol},
+        opener,
+        text::{format_bytes, truncate_chars_at_word_boundary},
+        ui::{section_at, section_line, shown_summary},
+    },
+    config::Config,
+    crawl::{CrawlEvent, CrawledPage, Crawler, RunningCrawl},
+    domains::DomainFilter,
+    export,
+    extractor::{self, ContentSource, ExtractedText, TextExtractor},
+    history::{shorten_title, History},
+    http::Http,
+    links::{self, Link, LinkExtractor},
+    openai::{Completion, OpenAIClient, SessionUsage, SummaryHints, SummaryOptions, TokenUsage},
+    paths,
+    record::Recorder,
+    settings::Setting,
+    sitemap,
+    ui::{
+        BookmarkEntry, BrowserState, ComparePane, ConfirmAction, CursorMove, HistoryEntry,
+        LinkFind, LinkRow, LoadingStage, PageMeta, PromptAction, SettingsItem, TextInput,
+        UIInterface, UserAction,
+    },
 };
 
+/// How long the optional page image may take to download
+const IMAGE_FETCH_TIMEOUT: Duration = Duration::from_secs(5);
+/// Pause before the one retry of a page image after a transient failure
+const IMAGE_RETRY_DELAY: Duration = Duration::from_millis(300);
+/// Upper bound on the time spent verifying URL suggestions
+const PROBE_TOTAL_TIMEOUT: Duration = Duration::from_secs(6);
+const PROBE_CONCURRENCY: usize = 4;
+/// Page URL shown for HTML read from stdin without `--base-url`
+const STDIN_URL: &str = "about:stdin";
+/// Page URL shown for plain text summarized with `--text-file`
+const TEXT_URL: &str = "about:text";
+/// Frames of the "thinking" indicator shown while the LLM works
+const SPINNER_FRAMES: &[char] = &['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+const SPINNER_INTERVAL: Duration = Duration::from_millis(100);
+/// How long a notice stays up when no key is pressed
+const NOTICE_TIMEOUT: Duration = Duration::from_secs(8);
+/// How often a crawl started from the UI is checked for pages it found
+const CRAWL_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Title, summary, links and response details of a loaded page
+type ProcessedPage = (String, String, Vec<Link>, PageMeta);
+
+/// Counts for the session stats screen; bytes and tokens are counted by the clients
+#[derive(Default)]
+struct SessionStats {
+    pages_visited: u32,
+    links_followed: u32,
+}
+
+/// A page shown earlier, with what it takes to show it again without refetching
+#[derive(Clone)]
+struct CachedPage {
+    page: ProcessedPage,
+    text: Option<String>,
+    image: Option<Vec<u8>>,
+}
+
+/// A crawl started from a page with `W` and what it has found so far
+struct SiteCrawl {
+    /// `None` once the crawl has finished or been stopped
+    running: Option<RunningCrawl>,
+    host: String,
+    /// Pages fetched, in the order they were
+    fetched: Vec<String>,
+    queued: usize,
+    summarized: Vec<CrawledPage>,
+    /// How the crawl ended, once it has
+    outcome: Option<String>,
+    /// Report last put on the page, to tell whether it is out of date
+    report: String,
+}
+
+impl SiteCrawl {
+    fn record(&mut self, event: CrawlEvent) {
+        match event {
+            CrawlEvent::Fetched { url, queued } => {
+                self.fetched.push(url);
+                self.queued = queued;
+            }
+            CrawlEvent::Summarized(page) => self.summarized.push(page),
+        }
+    }
+
+    /// Progress, then each summary in the order they came in, as markdown
+    fn markdown(&self) -> String {
+        let status = match &self.outcome {
+            Some(outcome) => outcome.clone(),
+            None => format!(
+                "**{}** fetched · **{}** queued · **{}** summarized · `Esc` stops the crawl",
+                self.fetched.len(),
+                self.queued,
+                self.summarized.len()
+            ),
+        };
+        let mut report = format!(
+            "## Crawl of {}\n\n{}\n\nPick a page from the links panel to open it.",
+            self.host, status
+        );
+        for page in &self.summarized {
+            report.push_str(&format!(
+                "\n\n### {}\n\n{}\n\n{}",
+                page.title, page.url, page.summary
+            ));
+        }
+        report
+    }
+
+    /// Pages fetched so far, by title once summarized and by path until then
+    fn links(&self) -> Vec<Link> {
+        self.fetched
+            .iter()
+            .enumerate()
+            .map(|(i, url)| {
+                let mut link = path_link(i + 1, url);
+                if let Some(page) = self.summarized.iter().find(|page| page.url == *url) {
+                    link.text = page.title.clone();
+                }
+                link
+            })
+            .collect()
+    }
+}
+
+/// The page left for a peek, put back as it was when the peek ends
+struct Peek {
+    state: BrowserState,
+    url: Option<String>,
+    links: Vec<Link>,
+    meta: PageMeta,
+    text: Option<String>,
+    previous_summary: Option<String>,
+    image: Option<Vec<u8>>,
+    expanded_hosts: HashSet<String>,
+    listing: bool,
+    scroll_position: u16,
+    selected_link: usize,
+}
+
 pub struct Browser {
-    client: WebClient,
+    config: Config,
+    client: Arc<WebClient>,
     extractor: TextExtractor,
-    openai: OpenAIClient,
+    /// `None` under `--local-only`: nothing is ever sent to an AI service
+    openai: Option<OpenAIClient>,
+    /// Saves what each page load fetched and sent with `--record`
+    recorder: Option<Arc<Recorder>>,
     link_extractor: LinkExtractor,
+    domains: DomainFilter,
     ui: Box<dyn UIInterface>,
     history: History,
+    /// Pages left this session, shown again on back and forward
+    page_cache: LruCache<CachedPage>,
+    /// Shared by the clients, and by crawls started from the UI
+    http: Http,
+    /// Crawl shown on the current page, while it is
+    crawl: Option<SiteCrawl>,
+    /// The page on screen lists the sitemap or a crawl instead of summarizing
+    /// its URL; such listings are never cached
+    listing: bool,
+    bookmarks: Bookmarks,
     current_url: Option<String>,
     current_links: Vec<Link>,
+    current_meta: PageMeta,
+    /// Extracted text of the current page, kept so the summary can be regenerated
+    current_text: Option<String>,
+    /// Summarize only the first `quick_look_chars` of each page
+    quick_look: bool,
+    /// Ask for bullet outlines instead of prose summaries
+    outline: bool,
+    /// Show summaries as their raw markdown instead of rendered
+    raw_summary: bool,
+    /// Summary replaced by the last regenerate, for showing what changed
+    previous_summary: Option<String>,
+    /// Inline image support, present only with `--images` on a capable terminal
+    images: Option<ImageOverlay>,
+    /// Lead image of the current page, already checked to be drawable
+    current_image: Option<Vec<u8>>,
+    /// Where the main text of the page just processed was found, until the
+    /// page is shown (`--debug-extract`)
+    extraction: Option<ContentSource>,
+    /// Hosts whose links are listed while the links panel is grouped by host
+    expanded_hosts: HashSet<String>,
+    /// Link selected when find-as-you-type started, restored when the query is cleared
+    selection_before_find: usize,
     current_state: BrowserState,
-    url_input: String,
+    state_before_overlay: Option<BrowserState>,
+    url_input: TextInput,
+    stats: SessionStats,
+    /// Set while peeking at a link: the page to return to on the next key
+    peek: Option<Peek>,
+    /// When the page shown is due for `--auto-refresh`
+    next_refresh: Option<Instant>,
+    /// The notice on screen and when it goes away by itself
+    notice_shown: Option<(String, Instant)>,
 }
 
 impl Browser {
-    pub fn new(ui: Box<dyn UIInterface>) -> Result<Self> {
+    pub fn new(ui: Box<dyn UIInterface>, config: Config, http: Http) -> Result<Self> {
+        let recorder = match &config.record_dir {
+            Some(dir) => Some(Arc::new(Recorder::new(dir)?)),
+            None => None,
+        };
+        let mut openai = Self::build_openai(&config, http.clone())?;
+        if let (Some(openai), Some(recorder)) = (&mut openai, &recorder) {
+            openai.set_recorder(recorder.clone());
+        }
         Ok(Self {
-            client: WebClient::new(),
-            extractor: TextExtractor::new(),
-            openai: OpenAIClient::new()?,
-            link_extractor: LinkExtractor::new(),
+            link_extractor: LinkExtractor::new()
+                .with_max_links_per_host(config.max_links_per_host)
+                .with_anchor_links(config.anchor_links)
+                .with_link_schemes(&config.link_schemes),
+            extractor: Self::build_extractor(&config),
+            domains: DomainFilter::new(&config.allow_domains, &config.deny_domains),
+            history: History::with_capacity(config.history_size)
+                .with_max_title_chars(config.history_title_chars),
+            page_cache: LruCache::new(config.cache_entries),
+            crawl: None,
+            listing: false,
+            bookmarks: Self::load_bookmarks(),
+            images: config
+                .images
+                .then(Protocol::detect)
+                .flatten()
+                .map(ImageOverlay::new),
+            openai,
+            recorder,
+            quick_look: config.quick_look,
+            outline: config.outline,
+            raw_summary: false,
+            client: Arc::new(
+                WebClient::new(http.clone())
+                    .with_host_delay(config.host_delay)
+                    .with_accept(&config.accept)
+                    .with_accept_header(config.accept_header.as_deref()),
+            ),
+            config,
+            http,
             ui,
-            history: History::new(),
             current_url: None,
             current_links: Vec::new(),
-            current_state: BrowserState::Loading {
-                url: "Starting...".to_string(),
-                progress: 0,
-                stage: "Initializing...".to_string(),
+            current_meta: PageMeta::default(),
+            current_text: None,
+            previous_summary: None,
+            current_image: None,
+            extraction: None,
+            expanded_hosts: HashSet::new(),
+            selection_before_find: 0,
+            // Nothing is loaded yet, so the first screen is the URL prompt
+            current_state: BrowserState::URLInput {
+                input: TextInput::default(),
             },
-            url_input: String::new(),
+            state_before_overlay: None,
+            url_input: TextInput::default(),
+            stats: SessionStats::default(),
+            peek: None,
+            next_refresh: None,
+            notice_shown: None,
+        })
+    }
+
+    /// Saved bookmarks; none when the file can't be read, which then stays
+    /// untouched instead of being overwritten by the next bookmark
+    fn load_bookmarks() -> Bookmarks {
+        Bookmarks::load(paths::data_dir().as_deref()).unwrap_or_else(|e| {
+            warn!("Bookmarks not loaded: {:#}", e);
+            Bookmarks::default()
         })
     }
 
+    pub fn build_extractor(config: &Config) -> TextExtractor {
+        TextExtractor::new()
+            .with_site_selectors(&config.site_selectors)
+            .with_content_selectors(&config.content_selectors)
+            .with_exclude_selectors(&config.exclude_selectors)
+            .with_title(config.summarize_title)
+    }
+
+    pub fn build_openai(config: &Config, http: Http) -> Result<Option<OpenAIClient>> {
+        if config.local_only {
+            info!("local-only mode: LLM calls disabled");
+            return Ok(None);
+        }
+        let mut openai = OpenAIClient::new(http)?;
+        openai.set_options(&config.model, config.summary_max_tokens, config.temperature);
+        openai.set_prices(config.prices.clone());
+        Ok(Some(openai))
+    }
+
+    /// The LLM client, or an error saying why there is none
+    fn llm(&self) -> Result<&OpenAIClient> {
+        self.openai
+            .as_ref()
+            .ok_or_else(|| anyhow!("Not available with --local-only: no AI service is contacted"))
+    }
+
     pub async fn navigate(&mut self, url: &str) -> Result<()> {
-        let normalized_url = self.normalize_url(url)?;
+        if url == STDIN_URL || url == TEXT_URL {
+            self.current_state = BrowserState::Error {
+                message: "Local input can't be loaded again".to_string(),
+            };
+            return self.render();
+        }
+        self.cache_current_page();
+        let url = &address_or_search(url, &self.config.search_engine);
+        let normalized_url = normalize_url(url)?;
+        if let Some(reason) = self.domains.blocked_reason(&Url::parse(&normalized_url)?) {
+            info!(url = %normalized_url, "navigation blocked: {}", reason);
+            self.current_state = BrowserState::Error {
+                message: format!("Navigation blocked: {}", reason),
+            };
+            return self.render();
+        }
         self.current_url = Some(normalized_url.clone());
 
-        self.set_loading_state(normalized_url.clone(), 0, "Starting...");
-        self.ui.render(&self.current_state)?;
+        self.set_loading_state(normalized_url.clone(), 0, LoadingStage::Starting);
+        self.render()?;
         self.ui.reset_scroll();
 
         match self.fetch_and_process_with_progress(&normalized_url).await {
-            Ok((title, summary, links)) => {
-                self.current_links = links.clone();
-                self.history.add(normalized_url, title.clone());
-                self.current_state = BrowserState::Page {
-                    url: self.current_url.as_ref().unwrap().clone(),
-                    title,
-                    summary,
-                    links,
+            Ok(page) => self.show_page(normalized_url, page)?,
+            Err(e) => self.handle_navigation_error(url, e).await?,
+        }
+
+        Ok(())
+    }
+
+    /// Show HTML read from stdin as a page. Relative links resolve against
+    /// `base_url`; without one only absolute links are kept.
+    pub async fn show_html(&mut self, base_url: Option<&str>, html: String) -> Result<()> {
+        let url = match base_url {
+            Some(base_url) => normalize_url(base_url)?,
+            None => STDIN_URL.to_string(),
+        };
+
+        self.set_loading_state(url.clone(), 0, LoadingStage::ReadingStdin);
+        self.render()?;
+
+        let page = FetchedPage {
+            body: html,
+            is_html: true,
+            is_json: false,
+            json_error: None,
+            status: 200,
+            content_type: "text/html".to_string(),
+        };
+        match self.process_fetched_page(&url, page).await {
+            Ok((title, summary, links, mut meta)) => {
+                // Nothing was fetched, so there is no response status to show
+                meta.status = None;
+                self.show_page(url, (title, summary, links, meta))
+            }
+            Err(e) => {
+                self.current_state = BrowserState::Error {
+                    message: format!("Could not process piped HTML: {}", e),
                 };
-                self.ui.render(&self.current_state)?;
+                self.render()
             }
-            Err(e) => self.handle_navigation_error(url, e).await?,
         }
+    }
+
+    /// Summarize plain text, e.g. notes loaded with `--text-file`, and show it
+    /// as a page without links
+    pub async fn show_text(&mut self, title: &str, text: String) -> Result<()> {
+        self.set_loading_state(TEXT_URL.to_string(), 90, LoadingStage::Summarizing);
+        self.render()?;
+
+        self.current_image = None;
+        if let Some(recorder) = &self.recorder {
+            recorder.begin(TEXT_URL);
+            recorder.save("text.txt", &text);
+        }
+        let mut meta = PageMeta::default();
+        let completion = self
+            .generate_summary(&text, TEXT_URL, &mut meta, &[])
+            .await?;
+        self.set_usage(&mut meta, completion.usage);
+        let summary = completion.text;
+        self.current_text = Some(text);
+        self.show_page(
+            TEXT_URL.to_string(),
+            (title.to_string(), summary, Vec::new(), meta),
+        )
+    }
+
+    fn show_page(&mut self, url: String, page: ProcessedPage) -> Result<()> {
+        let (title, summary, links, meta) = page;
+        self.current_url = Some(url.clone());
+        self.current_links = links.clone();
+        self.current_meta = meta.clone();
+        self.expanded_hosts.clear();
+        self.previous_summary = None;
+        self.listing = false;
+        self.stats.pages_visited += 1;
+        self.next_refresh = self.config.auto_refresh.map(|every| Instant::now() + every);
+        // A peeked page is gone on the next key, so it never enters history
+        if self.peek.is_none() {
+            self.history.add(url.clone(), title.clone());
+        }
+        // Tracking parameters and AMP wrappers often have a cleaner address
+        let notice = meta
+            .canonical
+            .as_deref()
+            .filter(|canonical| !is_same_address(canonical, &url))
+            .map(|canonical| format!("Canonical version: {} (K opens it)", canonical));
+        self.current_state = BrowserState::Page {
+            url,
+            title,
+            summary,
+            links,
+            meta,
+            link_find: None,
+            link_groups: None,
+            notice,
+            diff_from: None,
+            raw: self.raw_summary,
+            expanded: false,
+        };
+        match self.extraction.take() {
+            Some(source) if self.config.debug_extract => self.show_extraction(&source),
+            _ => self.render(),
+        }
+    }
+
+    /// Show how the page's main text was found over the page (`--debug-extract`)
+    fn show_extraction(&mut self, source: &ContentSource) -> Result<()> {
+        let text = self.current_text.as_deref().unwrap_or_default();
+        let missed = if source.missed.is_empty() {
+            "nothing, it was the first".to_string()
+        } else {
+            source.missed.join(", ")
+        };
+        let items = [
+            (
+                "Content from",
+                format!("{} ({} selector)", source.selector, source.origin),
+            ),
+            ("Tried first", missed),
+            (
+                "Characters",
+                format!(
+                    "{} matched, {} after cleaning",
+                    source.chars,
+                    text.chars().count()
+                ),
+            ),
+            ("Words", text.split_whitespace().count().to_string()),
+            (
+                "Excluded",
+                format!("{} selectors", self.config.exclude_selectors.len()),
+            ),
+        ]
+        .into_iter()
+        .map(|(label, value)| SettingsItem {
+            label: label.to_string(),
+            value,
+        })
+        .collect();
+        let extraction = BrowserState::Extraction { items };
+        let previous = std::mem::replace(&mut self.current_state, extraction);
+        self.state_before_overlay = Some(previous);
+        self.render()
+    }
+
+    /// Render the current state and keep the optional page image in step with it:
+    /// drawn while a page is shown, removed on every other screen
+    fn render(&mut self) -> Result<()> {
+        self.ui.render(&self.current_state)?;
 
+        let Some(overlay) = self.images.as_mut() else {
+            return Ok(());
+        };
+        let on_page = matches!(self.current_state, BrowserState::Page { .. });
+        match (&self.current_image, on_page, overlay.is_shown()) {
+            (Some(image), true, false) => overlay.show(image)?,
+            (_, false, true) => {
+                overlay.clear()?;
+                if overlay.protocol() == Protocol::ITerm2 {
+                    self.ui.clear()?;
+                    self.ui.render(&self.current_state)?;
+                }
+            }
+            _ => {}
+        }
         Ok(())
     }
 
+    /// Render again after a terminal resize. An inline image is redrawn too,
+    /// since its old position may no longer be the header corner.
+    fn redraw(&mut self) -> Result<()> {
+        if let Some(overlay) = self.images.as_mut() {
+            overlay.clear()?;
+        }
+        self.ui.clear()?;
+        self.render()
+    }
+
     pub async fn run(&mut self) -> Result<()> {
         let result = self.main_loop().await;
+        if let Some(overlay) = self.images.as_mut() {
+            overlay.clear()?;
+        }
         self.ui.cleanup()?;
         result
     }
 
     async fn main_loop(&mut self) -> Result<()> {
+        // Without an initial page nothing has been drawn yet
         if self.current_url.is_none() {
-            self.current_state = BrowserState::URLInput {
-                input: String::new(),
-            };
-            self.ui.render(&self.current_state)?;
+            self.render()?;
         }
 
         loop {
-            match self.ui.get_user_input(&self.current_state)? {
-                UserAction::Quit => break,
+            self.track_notice();
+            let action = self
+                .ui
+                .get_user_input(&self.current_state, self.input_timeout())?;
+            // While peeking, any key but scrolling returns to the page left
+            if self.peek.is_some()
+                && !matches!(
+                    action,
+                    UserAction::Redraw
+                        | UserAction::Tick
+                        | UserAction::Hover { .. }
+                        | UserAction::ScrollUp
+                        | UserAction::ScrollDown
+                        | UserAction::ScrollBy(_)
+                )
+            {
+                self.end_peek()?;
+                continue;
+            }
+            // A notice lasts until the next key press (or a few seconds), or the whole peek
+            if let BrowserState::Page { notice, .. } = &mut self.current_state {
+                let passive = matches!(
+                    action,
+                    UserAction::Redraw | UserAction::Tick | UserAction::Hover { .. }
+                );
+                if !passive && self.peek.is_none() {
+                    *notice = None;
+                }
+            }
+
+            match action {
+                UserAction::Quit => {
+                    if !self.config.confirm_quit {
+                        break;
+                    }
+                    self.show_confirm("Quit bbow?", ConfirmAction::Quit)?;
+                }
+                UserAction::Confirm => match self.take_confirm_action()? {
+                    Some(ConfirmAction::Quit) => break,
+                    Some(ConfirmAction::OpenInBrowser(url)) => self.open_in_browser(&url)?,
+                    Some(ConfirmAction::LoadWithoutRedirects(url)) => {
+                        self.load_without_redirects(url).await?
+                    }
+                    None => {}
+                },
                 UserAction::FollowLink(index) => self.follow_link_by_index(index).await?,
                 UserAction::FollowSelectedLink => self.follow_selected_link().await?,
+                UserAction::PeekLink => self.peek_selected_link().await?,
                 UserAction::GoBack => self.handle_go_back().await?,
                 UserAction::GoForward => self.handle_go_forward().await?,
+                UserAction::ReopenLast => self.reopen_last().await?,
                 UserAction::ShowHistory => self.show_history()?,
+                UserAction::AddBookmark => self.add_bookmark()?,
+                UserAction::ShowBookmarks => self.show_bookmarks()?,
+                UserAction::SelectPrevBookmark => self.move_bookmark_selection(false)?,
+                UserAction::SelectNextBookmark => self.move_bookmark_selection(true)?,
+                UserAction::OpenBookmark => self.open_bookmark().await?,
+                UserAction::RemoveBookmark => self.remove_bookmark()?,
                 UserAction::EnterUrl => self.enter_url_mode()?,
+                UserAction::EditUrl => self.edit_current_url()?,
                 UserAction::ConfirmInput(url) => {
                     if !url.is_empty() {
                         self.navigate(&url).await?;
                     }
                 }
-                UserAction::CancelInput => self.return_to_page()?,
+                UserAction::CancelInput => self.handle_cancel()?,
+                UserAction::FindLink => self.start_link_find()?,
+                UserAction::OpenSettings => self.open_settings()?,
+                UserAction::ShowInfo => self.show_info()?,
+                UserAction::ShowStats => self.show_stats()?,
+                UserAction::SelectPrevSetting => self.move_settings_selection(false)?,
+                UserAction::SelectNextSetting => self.move_settings_selection(true)?,
+                UserAction::NextSettingValue => self.change_setting(true)?,
+                UserAction::PrevSettingValue => self.change_setting(false)?,
+                UserAction::SaveSettings => self.save_settings()?,
                 UserAction::Refresh => self.handle_refresh().await?,
+                UserAction::Regenerate => self.handle_regenerate(None).await?,
+                UserAction::RegenerateWithInstruction => self.prompt_for_instruction()?,
+                UserAction::SubmitPrompt(text) => self.submit_prompt(&text).await?,
+                UserAction::ShowSitemap => self.show_sitemap().await?,
+                UserAction::StartCrawl => self.start_crawl()?,
+                UserAction::StopCrawl => self.stop_crawl()?,
+                UserAction::ExportPage => self.export_page()?,
+                UserAction::OpenInEditor => self.open_in_editor()?,
+                UserAction::CopyMarkdownLink => self.copy_markdown_link()?,
+                UserAction::YankLinks => self.yank_links()?,
+                UserAction::ToggleLinkGroups => self.toggle_link_groups()?,
+                UserAction::ToggleDiff => self.toggle_diff()?,
+                UserAction::ToggleQuickLook => self.toggle_quick_look().await?,
+                UserAction::ToggleOutline => self.toggle_outline().await?,
+                UserAction::ToggleRawSummary => self.toggle_raw_summary()?,
+                UserAction::OpenCanonical => self.open_canonical().await?,
+                UserAction::ToggleExpanded => self.toggle_expanded()?,
+                UserAction::PrevSection => self.jump_to_section(false)?,
+                UserAction::NextSection => self.jump_to_section(true)?,
+                UserAction::CopySection => self.copy_section()?,
+                UserAction::CopySectionLink => self.copy_section_link()?,
+                UserAction::CompareSummaries => self.compare_summaries().await?,
+                UserAction::SwitchPane => self.switch_pane()?,
+                UserAction::Redraw => self.redraw()?,
+                UserAction::Tick => self.handle_tick().await?,
+                UserAction::Hover { column, row } => self.hover(column, row)?,
+                UserAction::Click { column, row } => self.click(column, row).await?,
                 UserAction::ScrollUp => self.scroll_up()?,
                 UserAction::ScrollDown => self.scroll_down()?,
+                UserAction::ScrollBy(delta) => self.scroll_by(delta)?,
                 UserAction::SelectPrevLink => self.select_prev_link()?,
                 UserAction::SelectNextLink => self.select_next_link()?,
                 UserAction::InputChar(c) => self.handle_input_char(c)?,
                 UserAction::Backspace => self.handle_backspace()?,
+                UserAction::DeleteChar => self.handle_delete()?,
+                UserAction::MoveCursor(movement) => self.move_cursor(movement)?,
                 UserAction::SelectPrevSuggestion => self.select_prev_suggestion()?,
                 UserAction::SelectNextSuggestion => self.select_next_suggestion()?,
                 UserAction::ConfirmSuggestion => self.confirm_suggestion().await?,
@@ -117,133 +656,1759 @@ impl Browser {
 
     async fn follow_link_by_index(&mut self, index: usize) -> Result<()> {
         if let Some(link) = self.current_links.iter().find(|l| l.index == index) {
-            let url = link.url.clone();
-            self.navigate(&url).await?;
+            let link = link.clone();
+            self.follow_link(&link).await?;
+        }
+        Ok(())
+    }
+
+    /// Navigate to `link`, or scroll to its section when it is an anchor on
+    /// the page being shown
+    async fn follow_link(&mut self, link: &Link) -> Result<()> {
+        self.stats.links_followed += 1;
+        if links::opens_externally(&link.url) {
+            return self.open_externally(&link.url);
+        }
+        let Some(fragment) = self.same_page_fragment(&link.url) else {
+            return self.navigate(&link.url).await;
+        };
+
+        let BrowserState::Page {
+            summary,
+            notice,
+            expanded,
+            ..
+        } = &mut self.current_state
+        else {
+            return Ok(());
+        };
+        let summary = shown_summary(summary, *expanded, self.config.summary_preview);
+        // The summary is rewritten, so look for the link text or the fragment's words
+        let label = link.text.trim_start_matches(links::ANCHOR_LABEL);
+        let words = fragment.replace(['-', '_'], " ");
+        let found = [label, words.as_str()]
+            .iter()
+            .any(|text| !text.trim().is_empty() && self.ui.scroll_to_text(&summary, text));
+        if !found {
+            *notice = Some(format!("Section '{}' is not in the summary", label));
+        }
+        self.render()
+    }
+
+    fn hover(&mut self, column: u16, row: u16) -> Result<()> {
+        if self.ui.hover(column, row) {
+            self.render()?;
         }
         Ok(())
     }
 
+    /// Follow the link clicked in the links panel
+    async fn click(&mut self, column: u16, row: u16) -> Result<()> {
+        let Some(url) = self.ui.link_at(column, row) else {
+            return Ok(());
+        };
+        match self.current_links.iter().find(|link| link.url == url) {
+            Some(link) => {
+                let link = link.clone();
+                self.follow_link(&link).await
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// The fragment of `url` when it points into the current page
+    fn same_page_fragment(&self, url: &str) -> Option<String> {
+        let strip = |url: &str| {
+            Url::parse(url).ok().map(|mut url| {
+                let fragment = url.fragment().map(str::to_string);
+                url.set_fragment(None);
+                (url, fragment)
+            })
+        };
+        let (target, fragment) = strip(url)?;
+        let (current, _) = strip(self.current_url.as_deref()?)?;
+        fragment.filter(|f| !f.is_empty() && target == current)
+    }
+
     async fn follow_selected_link(&mut self) -> Result<()> {
         let selected_index = self.ui.get_selected_link();
+        // While grouped, Enter on a host header expands or collapses it
+        if let BrowserState::Page {
+            link_groups: Some(rows),
+            ..
+        } = &self.current_state
+        {
+            match rows.get(selected_index) {
+                Some(LinkRow::Host { host, .. }) => {
+                    let host = host.clone();
+                    return self.toggle_host(host);
+                }
+                Some(LinkRow::Link(link)) => {
+                    let link = link.clone();
+                    return self.follow_link(&link).await;
+                }
+                None => return Ok(()),
+            }
+        }
+
         if let Some(link) = self.current_links.get(selected_index) {
-            let url = link.url.clone();
-            self.navigate(&url).await?;
+            let link = link.clone();
+            self.follow_link(&link).await?;
+        }
+        Ok(())
+    }
+
+    /// Load the selected link like a normal navigation, but keep the current
+    /// page aside to be put back by `end_peek`
+    async fn peek_selected_link(&mut self) -> Result<()> {
+        let selected_index = self.ui.get_selected_link();
+        let link = match &self.current_state {
+            BrowserState::Page {
+                link_groups: Some(rows),
+                ..
+            } => match rows.get(selected_index) {
+                Some(LinkRow::Link(link)) => Some(link.clone()),
+                _ => None,
+            },
+            BrowserState::Page { .. } => self.current_links.get(selected_index).cloned(),
+            _ => None,
+        };
+        let Some(link) = link else {
+            return Ok(());
+        };
+        // An anchor only scrolls and other schemes open outside bbow, so
+        // there is nothing to come back from
+        if self.same_page_fragment(&link.url).is_some() || links::opens_externally(&link.url) {
+            return self.follow_link(&link).await;
+        }
+
+        let state = std::mem::replace(
+            &mut self.current_state,
+            BrowserState::Loading {
+                url: link.url.clone(),
+                progress: 0,
+                stage: LoadingStage::Starting,
+                thinking: None,
+            },
+        );
+        let title = match &state {
+            BrowserState::Page { title, .. } => title.clone(),
+            _ => String::new(),
+        };
+        self.peek = Some(Peek {
+            state,
+            url: self.current_url.clone(),
+            links: self.current_links.clone(),
+            meta: self.current_meta.clone(),
+            text: self.current_text.clone(),
+            previous_summary: self.previous_summary.take(),
+            image: self.current_image.clone(),
+            expanded_hosts: std::mem::take(&mut self.expanded_hosts),
+            listing: self.listing,
+            scroll_position: self.ui.scroll_position(),
+            selected_link: selected_index,
+        });
+
+        self.navigate(&link.url).await?;
+        if let BrowserState::Page { notice, .. } = &mut self.current_state {
+            *notice = Some(format!("Peeking · any key returns to {}", title));
+            self.render()?;
         }
         Ok(())
     }
 
+    /// Put back the page left by `peek_selected_link`, scrolled where it was
+    fn end_peek(&mut self) -> Result<()> {
+        let Some(peek) = self.peek.take() else {
+            return Ok(());
+        };
+        self.current_state = peek.state;
+        self.current_url = peek.url;
+        self.current_links = peek.links;
+        self.current_meta = peek.meta;
+        self.current_text = peek.text;
+        self.previous_summary = peek.previous_summary;
+        self.current_image = peek.image;
+        self.expanded_hosts = peek.expanded_hosts;
+        self.listing = peek.listing;
+        self.ui.set_scroll_position(peek.scroll_position);
+        self.ui.select_link(peek.selected_link);
+        // The peeked page's image may still be on screen
+        self.redraw()
+    }
+
     async fn handle_go_back(&mut self) -> Result<()> {
         if matches!(
             self.current_state,
             BrowserState::History { .. } | BrowserState::Error { .. }
         ) {
-            self.return_to_page_with_message("Use 'r' to refresh for summary")?;
+            // Before the first page there is nothing to go back to but the URL prompt
+            self.dismiss_error()?;
         } else if let Some(entry) = self.history.go_back() {
             let url = entry.url.clone();
-            self.navigate(&url).await?;
+            self.show_cached_or_navigate(&url).await?;
+        }
+        Ok(())
+    }
+
+    async fn handle_go_forward(&mut self) -> Result<()> {
+        if let Some(entry) = self.history.go_forward() {
+            let url = entry.url.clone();
+            self.show_cached_or_navigate(&url).await?;
+        }
+        Ok(())
+    }
+
+    /// Remember the page being shown, with its latest summary, for coming back to it
+    fn cache_current_page(&mut self) {
+        let BrowserState::Page {
+            url,
+            title,
+            summary,
+            links,
+            meta,
+            ..
+        } = &self.current_state
+        else {
+            return;
+        };
+        if self.listing || matches!(url.as_str(), STDIN_URL | TEXT_URL) {
+            return;
+        }
+        let cached = CachedPage {
+            page: (title.clone(), summary.clone(), links.clone(), meta.clone()),
+            text: self.current_text.clone(),
+            image: self.current_image.clone(),
+        };
+        self.page_cache.insert(url.clone(), cached);
+    }
+
+    /// Show `url` as it was left when it is in the cache, else load it
+    async fn show_cached_or_navigate(&mut self, url: &str) -> Result<()> {
+        self.cache_current_page();
+        let Some(cached) = self.page_cache.get(url).cloned() else {
+            return self.navigate(url).await;
+        };
+        debug!(url, "showing cached page");
+        self.current_text = cached.text;
+        self.current_image = cached.image;
+        self.ui.reset_scroll();
+        self.show_page(url.to_string(), cached.page)?;
+        if let BrowserState::Page { notice, .. } = &mut self.current_state {
+            *notice = Some("Shown as you left it · r reloads it".into());
+            self.render()?;
+        }
+        Ok(())
+    }
+
+    async fn reopen_last(&mut self) -> Result<()> {
+        if let Some(entry) = self.history.pop_closed() {
+            return self.show_cached_or_navigate(&entry.url).await;
+        }
+        if let BrowserState::Page { notice, .. } = &mut self.current_state {
+            *notice = Some("No recently closed pages".into());
+            self.render()?;
+        }
+        Ok(())
+    }
+
+    fn show_history(&mut self) -> Result<()> {
+        // Most recent first; ties keep the later entry on top
+        let list = self.history.list();
+        let mut order: Vec<usize> = (0..list.len()).collect();
+        order.sort_by_key(|&i| Reverse((list[i].visited_at, i)));
+
+        let entries: Vec<HistoryEntry> = order
+            .iter()
+            .map(|&i| HistoryEntry {
+                url: list[i].url.clone(),
+                title: shorten_title(&list[i].title, self.config.history_title_chars),
+                visited_at: list[i].visited_at,
+            })
+            .collect();
+
+        let current_index = self
+            .history
+            .current_index()
+            .and_then(|current| order.iter().position(|&i| i == current));
+
+        self.current_state = BrowserState::History {
+            entries,
+            current_index,
+        };
+        self.render()
+    }
+
+    /// Ask for the tags of the current page's bookmark, then bookmark it
+    fn add_bookmark(&mut self) -> Result<()> {
+        let BrowserState::Page { url, notice, .. } = &mut self.current_state else {
+            return Ok(());
+        };
+        if url == STDIN_URL || url == TEXT_URL {
+            *notice = Some("Only pages with a URL can be bookmarked".into());
+            return self.render();
+        }
+        let tags = self
+            .bookmarks
+            .get(url)
+            .map(|bookmark| bookmark.tags.join(" "))
+            .unwrap_or_default();
+        let prompt = BrowserState::Prompt {
+            title: "Tags for this bookmark (space-separated)".to_string(),
+            input: TextInput::new(&tags),
+            on_submit: PromptAction::Bookmark,
+        };
+        let previous = std::mem::replace(&mut self.current_state, prompt);
+        self.state_before_overlay = Some(previous);
+        self.render()
+    }
+
+    fn save_bookmark(&mut self, tags_text: &str) -> Result<()> {
+        let BrowserState::Page {
+            url, title, notice, ..
+        } = &mut self.current_state
+        else {
+            return Ok(());
+        };
+        let tags = bookmarks::parse_tags(tags_text);
+        let label = if tags.is_empty() {
+            "Bookmarked".to_string()
+        } else {
+            format!("Bookmarked with tags: {}", tags.join(", "))
+        };
+        self.bookmarks.set(url, title, tags);
+        *notice = Some(match self.bookmarks.save() {
+            Ok(_) => label,
+            Err(e) => format!("Could not save bookmark: {:#}", e),
+        });
+        self.render()
+    }
+
+    fn show_bookmarks(&mut self) -> Result<()> {
+        let bookmarks = self.bookmarks_state(String::new(), 0);
+        let previous = std::mem::replace(&mut self.current_state, bookmarks);
+        self.state_before_overlay = Some(previous);
+        self.render()
+    }
+
+    /// The bookmarks view listing those matching `filter`
+    fn bookmarks_state(&self, filter: String, selected: usize) -> BrowserState {
+        let entries: Vec<BookmarkEntry> = self
+            .bookmarks
+            .matching(&filter)
+            .into_iter()
+            .map(|bookmark| BookmarkEntry {
+                url: bookmark.url.clone(),
+                title: bookmark.title.clone(),
+                tags: bookmark.tags.clone(),
+            })
+            .collect();
+        let selected = selected.min(entries.len().saturating_sub(1));
+        BrowserState::Bookmarks {
+            entries,
+            filter,
+            selected,
+        }
+    }
+
+    fn move_bookmark_selection(&mut self, forward: bool) -> Result<()> {
+        if let BrowserState::Bookmarks {
+            entries, selected, ..
+        } = &mut self.current_state
+        {
+            if forward && *selected + 1 < entries.len() {
+                *selected += 1;
+            } else if !forward && *selected > 0 {
+                *selected -= 1;
+            }
+        }
+        self.render()
+    }
+
+    /// Narrow the bookmarks view with the filter changed by `edit`
+    fn edit_bookmark_filter(&mut self, edit: impl FnOnce(&mut String)) -> Result<()> {
+        let BrowserState::Bookmarks { filter, .. } = &mut self.current_state else {
+            return Ok(());
+        };
+        let mut filter = std::mem::take(filter);
+        edit(&mut filter);
+        self.current_state = self.bookmarks_state(filter, 0);
+        self.render()
+    }
+
+    async fn open_bookmark(&mut self) -> Result<()> {
+        let BrowserState::Bookmarks {
+            entries, selected, ..
+        } = &self.current_state
+        else {
+            return Ok(());
+        };
+        let Some(url) = entries.get(*selected).map(|entry| entry.url.clone()) else {
+            return Ok(());
+        };
+        self.state_before_overlay = None;
+        self.navigate(&url).await
+    }
+
+    fn remove_bookmark(&mut self) -> Result<()> {
+        let BrowserState::Bookmarks {
+            entries,
+            filter,
+            selected,
+        } = &mut self.current_state
+        else {
+            return Ok(());
+        };
+        let Some(url) = entries.get(*selected).map(|entry| entry.url.clone()) else {
+            return Ok(());
+        };
+        let (filter, selected) = (std::mem::take(filter), *selected);
+        self.bookmarks.remove(&url);
+        if let Err(e) = self.bookmarks.save() {
+            self.current_state = BrowserState::Error {
+                message: format!("Could not save bookmarks: {:#}", e),
+            };
+            return self.render();
+        }
+        self.current_state = self.bookmarks_state(filter, selected);
+        self.render()
+    }
+
+    /// Start at the URL prompt, optionally pre-filled, instead of loading a page
+    pub fn prefill_url_input(&mut self, url: Option<&str>) {
+        self.url_input = TextInput::new(url.unwrap_or_default());
+        self.current_state = BrowserState::URLInput {
+            input: self.url_input.clone(),
+        };
+    }
+
+    fn enter_url_mode(&mut self) -> Result<()> {
+        self.url_input = TextInput::default();
+        self.current_state = BrowserState::URLInput {
+            input: self.url_input.clone(),
+        };
+        self.render()
+    }
+
+    /// Open the URL input pre-filled with the current URL. Local input has no
+    /// URL worth editing, so it gets an empty prompt.
+    fn edit_current_url(&mut self) -> Result<()> {
+        let url = self
+            .current_url
+            .clone()
+            .filter(|url| url != STDIN_URL && url != TEXT_URL);
+        self.prefill_url_input(url.as_deref());
+        self.render()
+    }
+
+    fn show_confirm(&mut self, message: &str, on_yes: ConfirmAction) -> Result<()> {
+        let previous = std::mem::replace(
+            &mut self.current_state,
+            BrowserState::Confirm {
+                message: message.to_string(),
+                on_yes,
+            },
+        );
+        self.state_before_overlay = Some(previous);
+        self.render()
+    }
+
+    /// Leave the confirmation prompt, restoring the screen it was opened from,
+    /// and return the action that was confirmed (if a prompt was open).
+    fn take_confirm_action(&mut self) -> Result<Option<ConfirmAction>> {
+        let on_yes = match &self.current_state {
+            BrowserState::Confirm { on_yes, .. } => on_yes.clone(),
+            _ => return Ok(None),
+        };
+        self.restore_state_before_overlay()?;
+        Ok(Some(on_yes))
+    }
+
+    fn restore_state_before_overlay(&mut self) -> Result<()> {
+        if let Some(previous) = self.state_before_overlay.take() {
+            self.current_state = previous;
+            self.render()?;
         }
         Ok(())
     }
 
-    async fn handle_go_forward(&mut self) -> Result<()> {
-        if let Some(entry) = self.history.go_forward() {
-            let url = entry.url.clone();
-            self.navigate(&url).await?;
-        }
-        Ok(())
+    fn open_settings(&mut self) -> Result<()> {
+        let settings = self.settings_state(0, None);
+        let previous = std::mem::replace(&mut self.current_state, settings);
+        self.state_before_overlay = Some(previous);
+        self.render()
+    }
+
+    fn show_info(&mut self) -> Result<()> {
+        let info = BrowserState::Info {
+            items: self.info_items(),
+        };
+        let previous = std::mem::replace(&mut self.current_state, info);
+        self.state_before_overlay = Some(previous);
+        self.render()
+    }
+
+    fn show_stats(&mut self) -> Result<()> {
+        let stats = BrowserState::Stats {
+            items: self.stats_items(),
+        };
+        let previous = std::mem::replace(&mut self.current_state, stats);
+        self.state_before_overlay = Some(previous);
+        self.render()
+    }
+
+    fn stats_items(&self) -> Vec<SettingsItem> {
+        let SessionUsage {
+            requests,
+            tokens,
+            cost_usd,
+            unpriced_requests,
+        } = self
+            .openai
+            .as_ref()
+            .map(OpenAIClient::session_usage)
+            .unwrap_or_default();
+        let cost = match unpriced_requests {
+            0 => format!("~${:.4}", cost_usd),
+            n if n == requests => "unknown (no price for this model)".to_string(),
+            n => format!("~${:.4} (+{} requests without a price)", cost_usd, n),
+        };
+
+        [
+            ("Pages visited", self.stats.pages_visited.to_string()),
+            ("Pages cached", self.page_cache.len().to_string()),
+            ("Links followed", self.stats.links_followed.to_string()),
+            ("Bytes fetched", format_bytes(self.client.bytes_fetched())),
+            ("LLM requests", requests.to_string()),
+            (
+                "Tokens",
+                format!(
+                    "{} ({} prompt, {} completion)",
+                    tokens.total(),
+                    tokens.prompt_tokens,
+                    tokens.completion_tokens
+                ),
+            ),
+            ("Estimated cost", cost),
+        ]
+        .into_iter()
+        .map(|(label, value)| SettingsItem {
+            label: label.to_string(),
+            value,
+        })
+        .collect()
+    }
+
+    fn info_items(&self) -> Vec<SettingsItem> {
+        let config_file = match &self.config.config_path {
+            Some(path) if path.exists() => path.display().to_string(),
+            Some(path) => format!("{} (not created yet)", path.display()),
+            None => "none".to_string(),
+        };
+        let dir = |dir: Option<PathBuf>| {
+            dir.map_or_else(|| "none".to_string(), |dir| dir.display().to_string())
+        };
+        let llm = match &self.openai {
+            Some(openai) => format!("OpenAI · {}", openai.model()),
+            None => "off (--local-only)".to_string(),
+        };
+        let api_key = if std::env::var_os("OPENAI_API_KEY").is_some() {
+            "set (OPENAI_API_KEY)"
+        } else {
+            "missing (set OPENAI_API_KEY)"
+        };
+
+        [
+            ("Version", format!("bbow {}", env!("CARGO_PKG_VERSION"))),
+            ("Theme", self.config.theme.clone()),
+            ("LLM", llm),
+            ("API key", api_key.to_string()),
+            ("Config file", config_file),
+            ("Data directory", dir(paths::data_dir())),
+            ("Cache directory", dir(paths::cache_dir())),
+            (
+                "Log file",
+                self.config.log_file.as_ref().map_or_else(
+                    || "none (run with -v to log)".to_string(),
+                    |path| path.display().to_string(),
+                ),
+            ),
+            (
+                "Export directory",
+                self.config.export_dir.as_ref().map_or_else(
+                    || "current directory".to_string(),
+                    |dir| dir.display().to_string(),
+                ),
+            ),
+            (
+                "History",
+                format!(
+                    "{} of {} pages, kept in memory",
+                    self.history.list().len(),
+                    self.config.history_size
+                ),
+            ),
+        ]
+        .into_iter()
+        .map(|(label, value)| SettingsItem {
+            label: label.to_string(),
+            value,
+        })
+        .collect()
+    }
+
+    fn settings_state(&self, selected: usize, status: Option<String>) -> BrowserState {
+        BrowserState::Settings {
+            items: Setting::ALL
+                .iter()
+                .map(|setting| SettingsItem {
+                    label: setting.label().to_string(),
+                    value: setting.value(&self.config),
+                })
+                .collect(),
+            selected,
+            status,
+        }
+    }
+
+    fn move_settings_selection(&mut self, forward: bool) -> Result<()> {
+        if let BrowserState::Settings { selected, .. } = &mut self.current_state {
+            let len = Setting::ALL.len();
+            *selected = if forward {
+                (*selected + 1) % len
+            } else {
+                (*selected + len - 1) % len
+            };
+            self.render()?;
+        }
+        Ok(())
+    }
+
+    /// Cycle the selected setting and apply it right away
+    fn change_setting(&mut self, forward: bool) -> Result<()> {
+        let BrowserState::Settings { selected, .. } = self.current_state else {
+            return Ok(());
+        };
+
+        Setting::ALL[selected].cycle(&mut self.config, forward);
+        self.extractor = Self::build_extractor(&self.config);
+        if let Some(openai) = &mut self.openai {
+            openai.set_options(
+                &self.config.model,
+                self.config.summary_max_tokens,
+                self.config.temperature,
+            );
+        }
+
+        self.current_state = self.settings_state(selected, None);
+        self.render()
+    }
+
+    fn save_settings(&mut self) -> Result<()> {
+        let BrowserState::Settings { selected, .. } = self.current_state else {
+            return Ok(());
+        };
+
+        let status = match self.config.save_settings() {
+            Ok(path) => format!("Saved to {}", path.display()),
+            Err(e) => format!("Could not save settings: {:#}", e),
+        };
+        self.current_state = self.settings_state(selected, Some(status));
+        self.render()
+    }
+
+    fn handle_cancel(&mut self) -> Result<()> {
+        if matches!(
+            self.current_state,
+            BrowserState::Confirm { .. }
+                | BrowserState::Settings { .. }
+                | BrowserState::Info { .. }
+                | BrowserState::Stats { .. }
+                | BrowserState::Extraction { .. }
+                | BrowserState::Compare { .. }
+                | BrowserState::Prompt { .. }
+                | BrowserState::Bookmarks { .. }
+        ) {
+            self.restore_state_before_overlay()
+        } else if let BrowserState::Page { link_find, .. } = &mut self.current_state {
+            *link_find = None;
+            self.render()
+        } else {
+            self.return_to_page()
+        }
+    }
+
+    fn return_to_page(&mut self) -> Result<()> {
+        self.return_to_page_with_message("Use 'r' to refresh for summary")
+    }
+
+    fn return_to_page_with_message(&mut self, summary: &str) -> Result<()> {
+        if let Some(current) = self.history.current() {
+            self.listing = false;
+            self.current_state = BrowserState::Page {
+                url: current.url.clone(),
+                title: current.title.clone(),
+                summary: summary.to_string(),
+                links: self.current_links.clone(),
+                meta: self.current_meta.clone(),
+                link_find: None,
+                link_groups: None,
+                notice: None,
+                diff_from: None,
+                raw: self.raw_summary,
+                expanded: false,
+            };
+            self.render()?;
+        }
+        Ok(())
+    }
+
+    async fn handle_refresh(&mut self) -> Result<()> {
+        if matches!(self.current_state, BrowserState::Error { .. }) {
+            self.return_to_page()?;
+        } else if matches!(self.current_url.as_deref(), Some(STDIN_URL | TEXT_URL)) {
+            if let BrowserState::Page { notice, .. } = &mut self.current_state {
+                *notice = Some("Local input can't be refetched; use 'R' to regenerate".into());
+            }
+            self.render()?;
+        } else if let Some(url) = self.current_url.clone() {
+            let place = (self.ui.scroll_position(), self.ui.get_selected_link());
+            self.navigate(&url).await?;
+            if self.config.keep_scroll_on_refresh {
+                self.restore_place(place)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Scroll the page shown back to `place` (scroll position, selected link),
+    /// as far as its possibly shorter summary and link list allow
+    fn restore_place(&mut self, place: (u16, usize)) -> Result<()> {
+        if !matches!(self.current_state, BrowserState::Page { .. }) {
+            return Ok(());
+        }
+        let (scroll_position, selected_link) = place;
+        self.ui
+            .set_scroll_position(scroll_position.min(self.ui.max_scroll()));
+        self.ui
+            .select_link(selected_link.min(self.current_links.len().saturating_sub(1)));
+        self.render()
+    }
+
+    /// How long to wait for input before something time-based is due.
+    /// `None` blocks until a key is pressed, so an idle browser costs no CPU.
+    fn input_timeout(&self) -> Option<Duration> {
+        [
+            self.refresh_timeout(),
+            self.notice_timeout(),
+            self.crawl_timeout(),
+        ]
+        .into_iter()
+        .flatten()
+        .min()
+    }
+
+    /// Do whatever came due while no key was pressed
+    async fn handle_tick(&mut self) -> Result<()> {
+        if self.notice_timeout() == Some(Duration::ZERO) {
+            if let BrowserState::Page { notice, .. } = &mut self.current_state {
+                *notice = None;
+            }
+            self.notice_shown = None;
+            self.render()?;
+        }
+        self.poll_crawl().await?;
+        self.auto_refresh().await
+    }
+
+    /// Start the clock of a notice that has just appeared
+    fn track_notice(&mut self) {
+        let notice = match &self.current_state {
+            BrowserState::Page {
+                notice: Some(notice),
+                ..
+            } => notice,
+            _ => {
+                self.notice_shown = None;
+                return;
+            }
+        };
+        if !matches!(&self.notice_shown, Some((shown, _)) if shown == notice) {
+            self.notice_shown = Some((notice.clone(), Instant::now() + NOTICE_TIMEOUT));
+        }
+    }
+
+    /// Time left until the notice on screen goes away. The peek hint stays
+    /// for as long as the peek.
+    fn notice_timeout(&self) -> Option<Duration> {
+        if self.peek.is_some() {
+            return None;
+        }
+        let (_, until) = self.notice_shown.as_ref()?;
+        Some(until.saturating_duration_since(Instant::now()))
+    }
+
+    /// Time left until the page shown is due for `--auto-refresh`. `None`
+    /// (wait for input) off a plain page: while typing, peeking, on overlays
+    /// and for local input, which can't be fetched again.
+    fn refresh_timeout(&self) -> Option<Duration> {
+        let next_refresh = self.next_refresh?;
+        let on_page = matches!(
+            self.current_state,
+            BrowserState::Page {
+                link_find: None,
+                ..
+            }
+        );
+        let local = matches!(self.current_url.as_deref(), Some(STDIN_URL | TEXT_URL));
+        if !on_page || local || self.peek.is_some() {
+            return None;
+        }
+        Some(next_refresh.saturating_duration_since(Instant::now()))
+    }
+
+    /// Refresh the page once `--auto-refresh` says it is due, keeping the
+    /// reader's place in it whatever the refresh policy, since nobody asked
+    /// for this refresh
+    async fn auto_refresh(&mut self) -> Result<()> {
+        if self.refresh_timeout() != Some(Duration::ZERO) {
+            return Ok(());
+        }
+        let place = (self.ui.scroll_position(), self.ui.get_selected_link());
+        info!(url = ?self.current_url, "auto-refreshing page");
+        // Due again in one interval even if this refresh fails
+        self.next_refresh = self.config.auto_refresh.map(|every| Instant::now() + every);
+        self.handle_refresh().await?;
+        self.restore_place(place)
+    }
+
+    /// Summarize the current page again from its extracted text, following
+    /// `instruction` on top of the usual prompt when given
+    async fn handle_regenerate(&mut self, instruction: Option<&str>) -> Result<()> {
+        let Some(text) = self.current_text.clone() else {
+            return Ok(());
+        };
+        let options = match self.llm() {
+            Ok(openai) => openai.options(),
+            Err(e) => return self.show_llm_error(e),
+        };
+        let BrowserState::Page {
+            url,
+            title,
+            summary: previous,
+            ..
+        } = &self.current_state
+        else {
+            return Ok(());
+        };
+        let (url, title, previous) = (url.clone(), title.clone(), previous.clone());
+
+        self.set_loading_state(url.clone(), 90, LoadingStage::Regenerating);
+        self.render()?;
+
+        let (mut meta, links) = (self.current_meta.clone(), self.current_links.clone());
+        let completion = self
+            .generate_summary_with(&text, &url, &mut meta, &links, options, instruction)
+            .await?;
+        self.set_usage(&mut meta, completion.usage);
+        self.current_meta = meta;
+        let summary = completion.text;
+        self.ui.reset_scroll();
+        self.previous_summary = Some(previous.clone());
+        self.listing = false;
+        self.current_state = BrowserState::Page {
+            url,
+            title,
+            summary,
+            links: self.current_links.clone(),
+            meta: self.current_meta.clone(),
+            link_find: None,
+            link_groups: None,
+            notice: instruction.map(|instruction| format!("Summarized with: {}", instruction)),
+            diff_from: Some(previous),
+            raw: self.raw_summary,
+            expanded: false,
+        };
+        self.render()
+    }
+
+    /// Ask for a one-off instruction for the next summary of the page
+    fn prompt_for_instruction(&mut self) -> Result<()> {
+        if self.current_text.is_none() || !matches!(self.current_state, BrowserState::Page { .. }) {
+            return Ok(());
+        }
+        if let Err(e) = self.llm() {
+            return self.show_llm_error(e);
+        }
+        let prompt = BrowserState::Prompt {
+            title: "Instruction for a new summary".to_string(),
+            input: TextInput::default(),
+            on_submit: PromptAction::RegenerateWith,
+        };
+        let previous = std::mem::replace(&mut self.current_state, prompt);
+        self.state_before_overlay = Some(previous);
+        self.render()
+    }
+
+    /// Close the prompt and act on `text`; an empty instruction only closes
+    async fn submit_prompt(&mut self, text: &str) -> Result<()> {
+        let BrowserState::Prompt { on_submit, .. } = self.current_state else {
+            return Ok(());
+        };
+        self.restore_state_before_overlay()?;
+        let text = text.trim();
+        match on_submit {
+            PromptAction::RegenerateWith if text.is_empty() => Ok(()),
+            PromptAction::RegenerateWith => self.handle_regenerate(Some(text)).await,
+            // No tags is still a bookmark
+            PromptAction::Bookmark => self.save_bookmark(text),
+        }
+    }
+
+    /// Show or hide the words changed by the last regenerate
+    fn toggle_diff(&mut self) -> Result<()> {
+        let BrowserState::Page {
+            diff_from, notice, ..
+        } = &mut self.current_state
+        else {
+            return Ok(());
+        };
+        match (&diff_from, &self.previous_summary) {
+            (Some(_), _) => *diff_from = None,
+            (None, Some(previous)) => *diff_from = Some(previous.clone()),
+            (None, None) => *notice = Some("Regenerate with 'R' to see what changed".into()),
+        }
+        self.render()
+    }
+
+    /// Switch quick look on or off and summarize the page shown again to match
+    async fn toggle_quick_look(&mut self) -> Result<()> {
+        self.quick_look = !self.quick_look;
+        let notice = if self.quick_look {
+            format!(
+                "Quick look: summaries read the first {} characters",
+                self.config.quick_look_chars
+            )
+        } else {
+            "Quick look off: summaries read the whole page".to_string()
+        };
+        if self.current_text.is_some() && self.llm().is_ok() {
+            self.handle_regenerate(None).await?;
+        }
+        if let BrowserState::Page { notice: shown, .. } = &mut self.current_state {
+            *shown = Some(notice);
+        }
+        self.render()
+    }
+
+    /// Switch between prose summaries and bullet outlines and summarize the
+    /// page shown again to match
+    async fn toggle_outline(&mut self) -> Result<()> {
+        self.outline = !self.outline;
+        let notice = if self.outline {
+            "Outline: summaries are bullet outlines of the page"
+        } else {
+            "Outline off: summaries are written as prose"
+        };
+        if self.current_text.is_some() && self.llm().is_ok() {
+            self.handle_regenerate(None).await?;
+        }
+        if let BrowserState::Page { notice: shown, .. } = &mut self.current_state {
+            *shown = Some(notice.to_string());
+        }
+        self.render()
+    }
+
+    /// Summarize the page again with the current options and with the
+    /// `--compare-*` ones, and show both side by side
+    async fn compare_summaries(&mut self) -> Result<()> {
+        let Some(text) = self.current_text.clone() else {
+            return Ok(());
+        };
+        let options = match self.llm() {
+            Ok(openai) => openai.options(),
+            Err(e) => return self.show_llm_error(e),
+        };
+        let BrowserState::Page {
+            url, title, notice, ..
+        } = &mut self.current_state
+        else {
+            return Ok(());
+        };
+
+        let compare_options = SummaryOptions {
+            max_tokens: self.config.compare_max_tokens.unwrap_or(options.max_tokens),
+            temperature: self
+                .config
+                .compare_temperature
+                .unwrap_or(options.temperature),
+        };
+        // Two summaries cost twice as much, so only on request
+        if self.config.compare_max_tokens.is_none() && self.config.compare_temperature.is_none() {
+            *notice = Some(
+                "Start with --compare-max-tokens or --compare-temperature to compare summaries"
+                    .into(),
+            );
+            return self.render();
+        }
+        let (url, title) = (url.clone(), title.clone());
+
+        let loading = BrowserState::Loading {
+            url: url.clone(),
+            progress: 90,
+            stage: LoadingStage::SummarizingA,
+            thinking: None,
+        };
+        let page = std::mem::replace(&mut self.current_state, loading);
+        self.state_before_overlay = Some(page);
+        self.render()?;
+
+        let (mut meta, links) = (self.current_meta.clone(), self.current_links.clone());
+        let summaries = match self
+            .generate_summary_with(&text, &url, &mut meta, &links, options, None)
+            .await
+        {
+            Ok(left) => {
+                self.set_loading_state(url.clone(), 95, LoadingStage::SummarizingB);
+                self.render()?;
+                self.generate_summary_with(&text, &url, &mut meta, &links, compare_options, None)
+                    .await
+                    .map(|right| (left.text, right.text))
+            }
+            Err(e) => Err(e),
+        };
+        // The page stays as it was, with the reason on it
+        let (left, right) = match summaries {
+            Ok(summaries) => summaries,
+            Err(e) => {
+                warn!(url, error = %e, "failed to compare summaries");
+                if let Some(page) = self.state_before_overlay.take() {
+                    self.current_state = page;
+                }
+                if let BrowserState::Page { notice, .. } = &mut self.current_state {
+                    *notice = Some(format!("Could not compare summaries: {}", e));
+                }
+                return self.render();
+            }
+        };
+
+        self.ui.reset_pane_scroll();
+        self.current_state = BrowserState::Compare {
+            url,
+            title,
+            left: ComparePane {
+                label: format!("A · {}", options.label()),
+                summary: left,
+            },
+            right: ComparePane {
+                label: format!("B · {}", compare_options.label()),
+                summary: right,
+            },
+            focus: 0,
+        };
+        self.render()
+    }
+
+    fn switch_pane(&mut self) -> Result<()> {
+        if let BrowserState::Compare { focus, .. } = &mut self.current_state {
+            *focus = 1 - *focus;
+            self.render()?;
+        }
+        Ok(())
+    }
+
+    /// Replace the links panel with the URLs from the site's sitemap
+    pub async fn show_sitemap(&mut self) -> Result<()> {
+        let BrowserState::Page { url, title, .. } = &self.current_state else {
+            return Ok(());
+        };
+        let (url, title) = (url.clone(), title.clone());
+
+        self.set_loading_state(url.clone(), 50, LoadingStage::FetchingSitemap);
+        self.render()?;
+
+        let urls = match sitemap::fetch_sitemap_urls(&self.client, &url).await {
+            Ok(urls) => urls,
+            Err(e) => {
+                self.current_state = BrowserState::Error {
+                    message: format!("Could not load sitemap: {}", e),
+                };
+                return self.render();
+            }
+        };
+
+        let links: Vec<Link> = urls
+            .iter()
+            .enumerate()
+            .map(|(i, page_url)| path_link(i + 1, page_url))
+            .collect();
+
+        let summary = format!(
+            "## Sitemap\n\nFound **{}** pages in the sitemap for {}. \
+            Pick one from the links panel, or press `r` to reload this page.",
+            links.len(),
+            url
+        );
+
+        self.ui.reset_scroll();
+        self.current_links = links.clone();
+        // The sitemap list is not capped by --max-links
+        self.current_meta.links_found = None;
+        self.previous_summary = None;
+        self.listing = true;
+        self.crawl = None;
+        self.current_state = BrowserState::Page {
+            url,
+            title,
+            summary,
+            links,
+            meta: self.current_meta.clone(),
+            link_find: None,
+            link_groups: None,
+            notice: None,
+            diff_from: None,
+            raw: self.raw_summary,
+            expanded: false,
+        };
+        self.render()
+    }
+
+    /// Crawl the site of the page shown (`W`): its pages are listed in the
+    /// links panel as they are fetched and their summaries added below the
+    /// progress as they come in
+    fn start_crawl(&mut self) -> Result<()> {
+        let BrowserState::Page {
+            url, title, notice, ..
+        } = &mut self.current_state
+        else {
+            return Ok(());
+        };
+        if matches!(url.as_str(), STDIN_URL | TEXT_URL) {
+            *notice = Some("Only pages from the web can be crawled".to_string());
+            return self.render();
+        }
+        let crawler = match Crawler::new(&self.config, self.http.clone(), self.config.crawl_pages) {
+            Ok(crawler) => crawler,
+            Err(e) => {
+                *notice = Some(format!("Could not start the crawl: {}", e));
+                return self.render();
+            }
+        };
+        info!(url, pages = self.config.crawl_pages, "starting crawl");
+        let host = Url::parse(url)
+            .ok()
+            .and_then(|u| u.host_str().map(str::to_string))
+            .unwrap_or_else(|| url.clone());
+        self.crawl = Some(SiteCrawl {
+            running: Some(RunningCrawl::spawn(crawler, url)),
+            host,
+            fetched: Vec::new(),
+            queued: 0,
+            summarized: Vec::new(),
+            outcome: None,
+            report: String::new(),
+        });
+
+        let (url, title) = (url.clone(), title.clone());
+        self.ui.reset_scroll();
+        // Like the sitemap, the crawled pages are not capped by --max-links
+        self.current_meta.links_found = None;
+        self.previous_summary = None;
+        self.listing = true;
+        self.current_state = BrowserState::Page {
+            url,
+            title,
+            summary: String::new(),
+            links: Vec::new(),
+            meta: self.current_meta.clone(),
+            link_find: None,
+            link_groups: None,
+            notice: None,
+            diff_from: None,
+            raw: self.raw_summary,
+            expanded: false,
+        };
+        self.show_crawl()
+    }
+
+    /// Stop the crawl on the page (`Esc`), keeping what it found so far
+    fn stop_crawl(&mut self) -> Result<()> {
+        let Some(crawl) = &mut self.crawl else {
+            return Ok(());
+        };
+        // Dropping the crawl aborts its fetches and summaries
+        if crawl.running.take().is_none() {
+            return Ok(());
+        }
+        info!(fetched = crawl.fetched.len(), "crawl stopped");
+        crawl.outcome = Some(format!(
+            "Stopped after **{}** pages fetched, **{}** summarized.",
+            crawl.fetched.len(),
+            crawl.summarized.len()
+        ));
+        self.show_crawl()
+    }
+
+    /// Put what the crawl has found so far on its page
+    fn show_crawl(&mut self) -> Result<()> {
+        let Some(crawl) = &mut self.crawl else {
+            return Ok(());
+        };
+        let BrowserState::Page { summary, links, .. } = &mut self.current_state else {
+            return Ok(());
+        };
+        crawl.report = crawl.markdown();
+        *summary = crawl.report.clone();
+        *links = crawl.links();
+        self.current_links = links.clone();
+        self.render()
+    }
+
+    /// Take in what the crawl sent since the last tick and show it. Leaving
+    /// the crawl's page stops the crawl; an overlay over it does not.
+    async fn poll_crawl(&mut self) -> Result<()> {
+        let Some(crawl) = &mut self.crawl else {
+            return Ok(());
+        };
+        // A peek shows another page for a moment, then puts the crawl back
+        if !self.listing && self.peek.is_none() {
+            debug!("left the crawl's page, stopping it");
+            self.crawl = None;
+            return Ok(());
+        }
+        let shown = self.listing && matches!(self.current_state, BrowserState::Page { .. });
+        let Some((events, finished)) = crawl.running.as_mut().map(RunningCrawl::drain) else {
+            return Ok(());
+        };
+
+        for event in events {
+            crawl.record(event);
+        }
+        if let Some(mut running) = crawl.running.take_if(|_| finished) {
+            crawl.outcome = Some(match running.finish().await {
+                Ok(()) => format!(
+                    "Finished: **{}** pages fetched and summarized.",
+                    crawl.fetched.len()
+                ),
+                Err(e) => {
+                    warn!(error = %e, "crawl failed");
+                    format!("Crawl failed: {}", e)
+                }
+            });
+        }
+        if shown && crawl.markdown() != crawl.report {
+            self.show_crawl()?;
+        }
+        Ok(())
+    }
+
+    /// How often the crawl is checked for news, `None` once it is over and
+    /// its page is up to date (it may have ended under an overlay)
+    fn crawl_timeout(&self) -> Option<Duration> {
+        self.crawl
+            .as_ref()
+            .filter(|crawl| crawl.running.is_some() || crawl.markdown() != crawl.report)
+            .map(|_| CRAWL_POLL_INTERVAL)
+    }
+
+    /// Save the current summary as a standalone HTML file
+    fn export_page(&mut self) -> Result<()> {
+        let BrowserState::Page {
+            url,
+            title,
+            summary,
+            notice,
+            ..
+        } = &mut self.current_state
+        else {
+            return Ok(());
+        };
+
+        let dir = self
+            .config
+            .export_dir
+            .clone()
+            .unwrap_or_else(|| PathBuf::from("."));
+        *notice = Some(match export::export_html(&dir, url, title, summary) {
+            Ok(path) => {
+                info!(path = %path.display(), "exported summary");
+                format!("Exported to {}", path.display())
+            }
+            Err(e) => format!("Export failed: {:#}", e),
+        });
+        self.render()
+    }
+
+    /// Show the text extracted from the page in `$EDITOR`, to see what the
+    /// summary was made from
+    fn open_in_editor(&mut self) -> Result<()> {
+        let Some(text) = self.current_text.clone() else {
+            return Ok(());
+        };
+        if !matches!(self.current_state, BrowserState::Page { .. }) {
+            return Ok(());
+        }
+
+        let result = match editor::command() {
+            Some(command) => {
+                self.ui.suspend()?;
+                let result = editor::open(&command, &text);
+                self.ui.resume()?;
+                Some(result)
+            }
+            None => None,
+        };
+
+        if let BrowserState::Page { notice, .. } = &mut self.current_state {
+            *notice = Some(match result {
+                Some(Ok(path)) => format!("Extracted text is in {}", path.display()),
+                Some(Err(e)) => format!("Editor failed: {:#}", e),
+                None => "Set $EDITOR to open the extracted text".to_string(),
+            });
+        }
+        self.render()
+    }
+
+    /// Open `url` in the system's browser and leave the error it was offered from
+    fn open_in_browser(&mut self, url: &str) -> Result<()> {
+        match opener::open_url(url) {
+            Ok(()) => {
+                info!(url, "opened in system browser");
+                self.dismiss_error()
+            }
+            Err(e) => {
+                self.current_state = BrowserState::Error {
+                    message: format!("Could not open {}: {}", url, e),
+                };
+                self.render()
+            }
+        }
+    }
+
+    /// Hand a link bbow can't load, like `mailto:`, to the system's handler
+    fn open_externally(&mut self, url: &str) -> Result<()> {
+        let result = opener::open_url(url);
+        if let BrowserState::Page { notice, .. } = &mut self.current_state {
+            *notice = Some(match result {
+                Ok(()) => {
+                    info!(url, "opened with system handler");
+                    format!("Opened {} with the system handler", url)
+                }
+                Err(e) => format!("Could not open {}: {}", url, e),
+            });
+        }
+        self.render()
+    }
+
+    /// Load the address the page declares as its canonical one, or its AMP
+    /// variant when the canonical address is the one already shown
+    async fn open_canonical(&mut self) -> Result<()> {
+        let BrowserState::Page {
+            url, meta, notice, ..
+        } = &mut self.current_state
+        else {
+            return Ok(());
+        };
+        let target = meta
+            .canonical
+            .iter()
+            .chain(&meta.amp)
+            .find(|target| !is_same_address(target, url))
+            .cloned();
+        match target {
+            Some(target) => self.navigate(&target).await,
+            None => {
+                *notice = Some("This page has no other canonical or AMP version".into());
+                self.render()
+            }
+        }
+    }
+
+    /// Copy `[Title](URL)` of the current page to the clipboard
+    fn copy_markdown_link(&mut self) -> Result<()> {
+        let BrowserState::Page {
+            url, title, notice, ..
+        } = &mut self.current_state
+        else {
+            return Ok(());
+        };
+
+        let link = clipboard::markdown_link(title, url);
+        *notice = Some(match clipboard::copy(&link) {
+            Ok(()) => format!("Copied {}", link),
+            Err(e) => format!("Copy failed: {}", e),
+        });
+        self.render()
+    }
+
+    /// Show the summary's markdown exactly as the LLM returned it, for
+    /// checking or copying it, or rendered again
+    fn toggle_raw_summary(&mut self) -> Result<()> {
+        let BrowserState::Page { raw, notice, .. } = &mut self.current_state else {
+            return Ok(());
+        };
+        self.raw_summary = !self.raw_summary;
+        *raw = self.raw_summary;
+        *notice = Some(if *raw {
+            "Raw markdown: the summary as the LLM returned it".into()
+        } else {
+            "Rendered markdown".into()
+        });
+        self.render()
+    }
+
+    /// Scroll the summary to its next (`forward`) or previous header
+    fn jump_to_section(&mut self, forward: bool) -> Result<()> {
+        let BrowserState::Page {
+            summary, expanded, ..
+        } = &self.current_state
+        else {
+            return Ok(());
+        };
+        // Headers past a preview's cut are not on screen to land on
+        let summary = shown_summary(summary, *expanded, self.config.summary_preview);
+        let (position, width) = (self.ui.scroll_position(), self.ui.summary_width());
+        let spacing = self.config.line_spacing;
+        if let Some(line) = section_line(&summary, width, spacing, position, forward) {
+            self.ui.set_scroll_position(line);
+            self.render()?;
+        }
+        Ok(())
+    }
+
+    /// Show the whole of a summary cut short by `--summary-preview`, or only
+    /// its preview again
+    fn toggle_expanded(&mut self) -> Result<()> {
+        let preview = self.config.summary_preview;
+        let BrowserState::Page {
+            summary,
+            expanded,
+            notice,
+            ..
+        } = &mut self.current_state
+        else {
+            return Ok(());
+        };
+        if matches!(shown_summary(summary, false, preview), Cow::Borrowed(_)) {
+            *notice = Some("The whole summary is already shown".into());
+            return self.render();
+        }
+        *expanded = !*expanded;
+        if !*expanded {
+            self.ui.reset_scroll();
+        }
+        self.render()
+    }
+
+    /// Copy the section of the summary at the top of the view as markdown
+    fn copy_section(&mut self) -> Result<()> {
+        let position = self.ui.scroll_position();
+        let width = self.ui.summary_width();
+        let spacing = self.config.line_spacing;
+        let BrowserState::Page {
+            summary, notice, ..
+        } = &mut self.current_state
+        else {
+            return Ok(());
+        };
+
+        *notice = Some(match section_at(summary, width, spacing, position) {
+            Some(section) => match clipboard::copy(&section) {
+                Ok(()) => match section_heading(&section) {
+                    Some(heading) => format!("Copied section '{}'", heading),
+                    None => "Copied the text before the first header".to_string(),
+                },
+                Err(e) => format!("Copy failed: {}", e),
+            },
+            None => "This summary has no sections to copy".to_string(),
+        });
+        self.render()
+    }
+
+    /// Copy a deep link (`page#anchor`) to the part of the page the summary
+    /// section at the top of the view is about, found among its anchor links
+    fn copy_section_link(&mut self) -> Result<()> {
+        let position = self.ui.scroll_position();
+        let width = self.ui.summary_width();
+        let BrowserState::Page {
+            summary,
+            links,
+            notice,
+            ..
+        } = &mut self.current_state
+        else {
+            return Ok(());
+        };
+
+        let section = section_at(summary, width, self.config.line_spacing, position);
+        *notice = Some(match section.as_deref().and_then(section_heading) {
+            None => "Scroll to a section of the summary to link to it".to_string(),
+            Some(heading) => match links::anchor_for_heading(links, heading) {
+                Some(anchor) => match clipboard::copy(&anchor.url) {
+                    Ok(()) => format!("Copied {}", anchor.url),
+                    Err(e) => format!("Copy failed: {}", e),
+                },
+                None if !self.config.anchor_links => {
+                    "Section links need the page's #anchors: start with --anchor-links".to_string()
+                }
+                None => format!("No anchor on the page matches section '{}'", heading),
+            },
+        });
+        self.render()
+    }
+
+    /// Copy all of the page's links as a `- [text](url)` list, for notes
+    fn yank_links(&mut self) -> Result<()> {
+        let BrowserState::Page { links, notice, .. } = &mut self.current_state else {
+            return Ok(());
+        };
+        if links.is_empty() {
+            *notice = Some("This page has no links to copy".into());
+            return self.render();
+        }
+
+        let list: Vec<String> = links
+            .iter()
+            .map(|link| {
+                let text = if link.text.trim().is_empty() {
+                    &link.url
+                } else {
+                    &link.text
+                };
+                format!("- {}", clipboard::markdown_link(text, &link.url))
+            })
+            .collect();
+        *notice = Some(match clipboard::copy(&list.join("\n")) {
+            Ok(()) => format!("Copied {} links as a markdown list", list.len()),
+            Err(e) => format!("Copy failed: {}", e),
+        });
+        self.render()
+    }
+
+    /// Apply a merged burst of scroll keys and render once
+    fn scroll_by(&mut self, delta: i32) -> Result<()> {
+        if let BrowserState::Compare { focus, .. } = self.current_state {
+            self.ui.scroll_pane(focus, delta);
+            return self.render();
+        }
+        for _ in 0..delta.unsigned_abs() {
+            if delta > 0 {
+                self.ui.scroll_down();
+            } else {
+                self.ui.scroll_up();
+            }
+        }
+        self.render()
+    }
+
+    fn scroll_up(&mut self) -> Result<()> {
+        if matches!(self.current_state, BrowserState::Compare { .. }) {
+            return self.scroll_by(-1);
+        }
+        self.ui.scroll_up();
+        self.render()
+    }
+
+    fn scroll_down(&mut self) -> Result<()> {
+        if matches!(self.current_state, BrowserState::Compare { .. }) {
+            return self.scroll_by(1);
+        }
+        self.ui.scroll_down();
+        self.render()
+    }
+
+    fn select_prev_link(&mut self) -> Result<()> {
+        self.ui.select_prev_link(self.link_panel_len());
+        self.render()
     }
 
-    fn show_history(&mut self) -> Result<()> {
-        let entries: Vec<HistoryEntry> = self
-            .history
-            .list()
-            .iter()
-            .map(|e| HistoryEntry {
-                url: e.url.clone(),
-                title: e.title.clone(),
-            })
-            .collect();
+    fn select_next_link(&mut self) -> Result<()> {
+        self.ui.select_next_link(self.link_panel_len());
+        self.render()
+    }
 
-        let current_index = self
-            .history
-            .current()
-            .and_then(|current| entries.iter().position(|e| e.url == current.url));
+    /// Number of selectable rows in the links panel
+    fn link_panel_len(&self) -> usize {
+        match &self.current_state {
+            BrowserState::Page {
+                link_groups: Some(rows),
+                ..
+            } => rows.len(),
+            _ => self.current_links.len(),
+        }
+    }
 
-        self.current_state = BrowserState::History {
-            entries,
-            current_index,
+    /// Switch the links panel between the plain list and links grouped by host
+    fn toggle_link_groups(&mut self) -> Result<()> {
+        let rows = self.link_rows();
+        let BrowserState::Page { link_groups, .. } = &mut self.current_state else {
+            return Ok(());
         };
-        self.ui.render(&self.current_state)
-    }
 
-    fn enter_url_mode(&mut self) -> Result<()> {
-        self.url_input.clear();
-        self.current_state = BrowserState::URLInput {
-            input: self.url_input.clone(),
+        *link_groups = match link_groups {
+            Some(_) => None,
+            None => Some(rows),
         };
-        self.ui.render(&self.current_state)
+        self.ui.select_link(0);
+        self.render()
     }
 
-    fn return_to_page(&mut self) -> Result<()> {
-        self.return_to_page_with_message("Use 'r' to refresh for summary")
+    fn toggle_host(&mut self, host: String) -> Result<()> {
+        if !self.expanded_hosts.remove(&host) {
+            self.expanded_hosts.insert(host);
+        }
+        let rows = self.link_rows();
+        if let BrowserState::Page { link_groups, .. } = &mut self.current_state {
+            *link_groups = Some(rows);
+        }
+        self.render()
     }
 
-    fn return_to_page_with_message(&mut self, summary: &str) -> Result<()> {
-        if let Some(current) = self.history.current() {
-            self.current_state = BrowserState::Page {
-                url: current.url.clone(),
-                title: current.title.clone(),
-                summary: summary.to_string(),
-                links: self.current_links.clone(),
-            };
-            self.ui.render(&self.current_state)?;
+    /// Host headers for the current links, hosts with the most links first,
+    /// each followed by its links when expanded
+    fn link_rows(&self) -> Vec<LinkRow> {
+        let mut hosts: Vec<(String, Vec<&Link>)> = Vec::new();
+        for link in &self.current_links {
+            let host = Url::parse(&link.url)
+                .ok()
+                .and_then(|url| url.host_str().map(str::to_string))
+                .unwrap_or_else(|| link.url.clone());
+            match hosts.iter_mut().find(|(h, _)| *h == host) {
+                Some((_, links)) => links.push(link),
+                None => hosts.push((host, vec![link])),
+            }
         }
-        Ok(())
+        hosts.sort_by_key(|(_, links)| Reverse(links.len()));
+
+        let mut rows = Vec::new();
+        for (host, links) in hosts {
+            let expanded = self.expanded_hosts.contains(&host);
+            rows.push(LinkRow::Host {
+                host,
+                count: links.len(),
+                expanded,
+            });
+            if expanded {
+                rows.extend(links.into_iter().cloned().map(LinkRow::Link));
+            }
+        }
+        rows
     }
 
-    async fn handle_refresh(&mut self) -> Result<()> {
-        if matches!(self.current_state, BrowserState::Error { .. }) {
-            self.return_to_page()?;
-        } else if let Some(url) = self.current_url.clone() {
-            self.navigate(&url).await?;
+    fn start_link_find(&mut self) -> Result<()> {
+        if let BrowserState::Page {
+            link_find,
+            link_groups,
+            ..
+        } = &mut self.current_state
+        {
+            // Find works on the plain list
+            if link_groups.take().is_some() {
+                self.ui.select_link(0);
+            }
+            *link_find = Some(LinkFind::default());
+            self.selection_before_find = self.ui.get_selected_link();
+            self.render()?;
         }
         Ok(())
     }
 
-    fn scroll_up(&mut self) -> Result<()> {
-        self.ui.scroll_up();
-        self.ui.render(&self.current_state)
+    /// Recount matches and select the best one; an empty query restores the
+    /// selection from before the find started
+    fn update_link_find(&mut self) -> Result<()> {
+        if let BrowserState::Page {
+            links,
+            link_find: Some(find),
+            ..
+        } = &mut self.current_state
+        {
+            let matches = links::fuzzy_matches(links, &find.query);
+            find.matches = matches.len();
+
+            if find.query.is_empty() {
+                self.ui.select_link(self.selection_before_find);
+            } else if let Some(&best) = matches.first() {
+                self.ui.select_link(best);
+            }
+        }
+        self.render()
     }
 
-    fn scroll_down(&mut self) -> Result<()> {
-        self.ui.scroll_down();
-        self.ui.render(&self.current_state)
+    /// The text being typed into a prompt, while one is open
+    fn prompt_input(&mut self) -> Option<&mut TextInput> {
+        match &mut self.current_state {
+            BrowserState::Prompt { input, .. } => Some(input),
+            _ => None,
+        }
     }
 
-    fn select_prev_link(&mut self) -> Result<()> {
-        self.ui.select_prev_link(self.current_links.len());
-        self.ui.render(&self.current_state)
+    fn handle_input_char(&mut self, c: char) -> Result<()> {
+        if let Some(input) = self.prompt_input() {
+            input.insert(c);
+            return self.render();
+        }
+        if let BrowserState::Bookmarks { .. } = self.current_state {
+            return self.edit_bookmark_filter(|filter| filter.push(c));
+        }
+        if let BrowserState::Page {
+            link_find: Some(find),
+            ..
+        } = &mut self.current_state
+        {
+            find.query.push(c);
+            return self.update_link_find();
+        }
+
+        self.url_input.insert(c);
+        self.update_url_input()
     }
 
-    fn select_next_link(&mut self) -> Result<()> {
-        self.ui.select_next_link(self.current_links.len());
-        self.ui.render(&self.current_state)
+    fn handle_backspace(&mut self) -> Result<()> {
+        if let Some(input) = self.prompt_input() {
+            input.backspace();
+            return self.render();
+        }
+        if let BrowserState::Bookmarks { .. } = self.current_state {
+            return self.edit_bookmark_filter(|filter| {
+                filter.pop();
+            });
+        }
+        if let BrowserState::Page {
+            link_find: Some(find),
+            ..
+        } = &mut self.current_state
+        {
+            find.query.pop();
+            return self.update_link_find();
+        }
+
+        self.url_input.backspace();
+        self.update_url_input()
     }
 
-    fn handle_input_char(&mut self, c: char) -> Result<()> {
-        self.url_input.push(c);
-        self.current_state = BrowserState::URLInput {
-            input: self.url_input.clone(),
-        };
-        self.ui.render(&self.current_state)
+    fn handle_delete(&mut self) -> Result<()> {
+        if let Some(input) = self.prompt_input() {
+            input.delete();
+            return self.render();
+        }
+        self.url_input.delete();
+        self.update_url_input()
     }
 
-    fn handle_backspace(&mut self) -> Result<()> {
-        self.url_input.pop();
+    fn move_cursor(&mut self, movement: CursorMove) -> Result<()> {
+        if let Some(input) = self.prompt_input() {
+            input.move_cursor(movement);
+            return self.render();
+        }
+        self.url_input.move_cursor(movement);
+        self.update_url_input()
+    }
+
+    fn update_url_input(&mut self) -> Result<()> {
         self.current_state = BrowserState::URLInput {
             input: self.url_input.clone(),
         };
-        self.ui.render(&self.current_state)
+        self.render()
     }
 
     fn select_prev_suggestion(&mut self) -> Result<()> {
@@ -284,7 +2449,7 @@ impl Browser {
                 suggestions: suggestions.clone(),
                 selected_index: new_index,
             };
-            self.ui.render(&self.current_state)?;
+            self.render()?;
         }
         Ok(())
     }
@@ -309,90 +2474,364 @@ impl Browser {
             self.return_to_page()
         } else {
             self.current_state = BrowserState::URLInput {
-                input: String::new(),
+                input: TextInput::default(),
             };
-            self.ui.render(&self.current_state)
+            self.render()
         }
     }
 
-    async fn fetch_and_process_with_progress(
+    async fn fetch_and_process_with_progress(&mut self, url: &str) -> Result<ProcessedPage> {
+        self.update_loading_progress(25, LoadingStage::FetchingPage)
+            .await?;
+        let page = self.client.fetch(url).await?;
+        self.process_fetched_page(url, page).await
+    }
+
+    async fn process_fetched_page(
         &mut self,
         url: &str,
-    ) -> Result<(String, String, Vec<Link>)> {
-        self.update_loading_progress(25, "Fetching HTML content...")
+        page: FetchedPage,
+    ) -> Result<ProcessedPage> {
+        let body = page.body;
+        if let Some(recorder) = &self.recorder {
+            recorder.begin(url);
+            let name = if page.is_html {
+                "page.html"
+            } else {
+                "page.txt"
+            };
+            recorder.save(name, &body);
+        }
+        self.update_loading_progress(50, LoadingStage::ExtractingText)
             .await?;
-        let html = self.client.fetch(url).await?;
+        // Other accepted types are read as they are: no markup, title or links to find
+        let extracted = if page.is_html {
+            self.extractor.extract_text(&body, url)?
+        } else {
+            ExtractedText {
+                text: body.clone(),
+                ..Default::default()
+            }
+        };
+        let text = extracted.text;
+        if let Some(recorder) = &self.recorder {
+            recorder.save("text.txt", &text);
+        }
+        // Fetched alongside the summary, so a slow or missing image never delays it
+        let image = extracted
+            .image
+            .and_then(|image_url| self.spawn_page_image_fetch(image_url));
+        self.extraction = page.is_html.then_some(extracted.source);
+        let mut meta = PageMeta {
+            status: Some(page.status),
+            content_type: Some(page.content_type),
+            paywalled: extracted.paywalled,
+            published: extracted.published,
+            links_found: None,
+            tokens: None,
+            cost_usd: None,
+            quick_look: false,
+            outline: false,
+            json: page.is_json,
+            canonical: extracted.canonical,
+            amp: extracted.amp,
+        };
 
-        self.update_loading_progress(50, "Extracting text content...")
+        self.update_loading_progress(75, LoadingStage::ProcessingLinks)
             .await?;
-        let text = self.extractor.extract_text(&html)?;
+        let (title, mut links) = if page.is_html {
+            (
+                extractor::page_title(&body),
+                self.link_extractor.extract_links(&body, url)?,
+            )
+        } else {
+            (document_title(url), Vec::new())
+        };
+        // Numbers stay 1..=max, so digit keys only address links that are shown
+        if let Some(max) = self.config.max_links.filter(|&max| links.len() > max) {
+            meta.links_found = Some(links.len());
+            links.truncate(max);
+        }
 
-        self.update_loading_progress(75, "Processing page structure...")
+        self.update_loading_progress(90, LoadingStage::Summarizing)
             .await?;
-        let title = self.extract_title(&html);
-        let links = self.link_extractor.extract_links(&html, url)?;
+        // JSON is shown as received unless asked to be explained; broken JSON always is
+        let completion = if meta.json && !(self.config.explain_json && page.json_error.is_none()) {
+            json_response(&text, page.json_error.as_deref())
+        } else {
+            self.generate_summary(&text, url, &mut meta, &links).await?
+        };
+        self.set_usage(&mut meta, completion.usage);
+        let summary = completion.text;
+        self.current_text = Some(text);
+        self.current_image = match image {
+            Some(task) => task.await.ok().flatten(),
+            None => None,
+        };
 
-        self.update_loading_progress(90, "Generating AI summary...")
+        self.update_loading_progress(100, LoadingStage::Complete)
             .await?;
-        let summary = self.generate_summary(&text, url).await;
 
-        self.update_loading_progress(100, "Complete!").await?;
+        Ok((title, summary, links, meta))
+    }
+
+    /// Start downloading the page's lead image if images are enabled and the
+    /// terminal can draw them; `None` when there is nothing to fetch
+    fn spawn_page_image_fetch(
+        &self,
+        image_url: String,
+    ) -> Option<tokio::task::JoinHandle<Option<Vec<u8>>>> {
+        let protocol = self.images.as_ref()?.protocol();
+        let client = self.client.clone();
+        Some(tokio::spawn(async move {
+            fetch_page_image(&client, &image_url, protocol).await
+        }))
+    }
+
+    async fn generate_summary(
+        &mut self,
+        text: &str,
+        url: &str,
+        meta: &mut PageMeta,
+        links: &[Link],
+    ) -> Result<Completion> {
+        let Some(options) = self.openai.as_ref().map(OpenAIClient::options) else {
+            return Ok(Completion {
+                text: unsummarized(text),
+                usage: None,
+            });
+        };
+        self.generate_summary_with(text, url, meta, links, options, None)
+            .await
+    }
 
-        Ok((title, summary, links))
+    /// Explain on the page why an action needing the LLM did nothing
+    fn show_llm_error(&mut self, error: anyhow::Error) -> Result<()> {
+        if let BrowserState::Page { notice, .. } = &mut self.current_state {
+            *notice = Some(error.to_string());
+        }
+        self.render()
     }
 
-    async fn generate_summary(&self, text: &str, url: &str) -> String {
+    /// Summarize `text`, or only its start in quick look mode, which is then
+    /// marked in `meta`
+    async fn generate_summary_with(
+        &mut self,
+        text: &str,
+        url: &str,
+        meta: &mut PageMeta,
+        links: &[Link],
+        options: SummaryOptions,
+        instruction: Option<&str>,
+    ) -> Result<Completion> {
+        let without_llm = |text: String| Completion { text, usage: None };
         if text.trim().is_empty() {
-            return "No content found on this page.".to_string();
+            return Ok(without_llm("No content found on this page.".to_string()));
+        }
+
+        // Error pages, redirect stubs and JS shells aren't worth an API call;
+        // a short JSON response still is
+        let too_short = insufficient_content(
+            text,
+            self.config.min_content_words,
+            self.config.fallback_text_length,
+        );
+        if let Some(note) = too_short.filter(|_| !meta.json) {
+            debug!("too little content, skipping summary");
+            return Ok(without_llm(note));
         }
+        let Some(openai) = &self.openai else {
+            return Ok(without_llm(unsummarized(text)));
+        };
+        // Quick look reuses the fallback excerpt's cut, at a word boundary
+        let content = if self.quick_look {
+            truncate_chars_at_word_boundary(text, self.config.quick_look_chars)
+        } else {
+            text.to_string()
+        };
+        let excerpt = content != text;
+        meta.quick_look = excerpt;
+        meta.outline = self.outline;
+
+        let references: Vec<(String, String)> = links
+            .iter()
+            .take(self.config.references.unwrap_or(0))
+            .map(|link| (link.text.clone(), link.url.clone()))
+            .collect();
+        let hints = SummaryHints {
+            possibly_partial: meta.paywalled,
+            published: meta
+                .published
+                .map(|date| date.format("%Y-%m-%d").to_string()),
+            references: references.clone(),
+            instruction: instruction.map(str::to_string),
+            excerpt,
+            outline: self.outline,
+            json: meta.json,
+        };
+
+        // Animate the loading screen while waiting so the LLM, not the
+        // network, reads as the current wait
+        let label = format!("Summarizing with {}…", openai.model());
+        let summarize = openai.summarize(&content, url, &hints, options);
+        tokio::pin!(summarize);
+        let mut ticker = tokio::time::interval(SPINNER_INTERVAL);
+        let mut frame = 0;
+        let result = loop {
+            tokio::select! {
+                result = &mut summarize => break result,
+                _ = ticker.tick() => {
+                    if let BrowserState::Loading { thinking, .. } = &mut self.current_state {
+                        let spinner = SPINNER_FRAMES[frame % SPINNER_FRAMES.len()];
+                        *thinking = Some(format!("{} {}", spinner, label));
+                        frame += 1;
+                        self.ui.render(&self.current_state)?;
+                    }
+                }
+            }
+        };
 
-        match self.openai.summarize(text, url).await {
-            Ok(summary) => summary,
-            Err(e) => format!(
+        Ok(match result {
+            // The explanation comes first and the response it explains after
+            Ok(completion) if meta.json => Completion {
+                text: format!("{}\n\n## Response\n\n{}", completion.text, json_block(text)),
+                usage: completion.usage,
+            },
+            Ok(completion) => Completion {
+                text: append_references(completion.text, &references),
+                usage: completion.usage,
+            },
+            Err(e) => without_llm(format!(
                 "Failed to generate summary: {}\n\nRaw text:\n{}",
                 e,
-                text.chars().take(1000).collect::<String>()
-            ),
-        }
+                truncate_chars_at_word_boundary(text, self.config.fallback_text_length)
+            )),
+        })
+    }
+
+    /// Note the tokens the page's summary took, and their estimated cost, in `meta`
+    fn set_usage(&self, meta: &mut PageMeta, usage: Option<TokenUsage>) {
+        meta.tokens = usage.map(|usage| usage.total());
+        meta.cost_usd = usage
+            .zip(self.openai.as_ref())
+            .and_then(|(usage, openai)| openai.estimated_cost(usage));
     }
 
-    async fn update_loading_progress(&mut self, progress: u16, stage: &str) -> Result<()> {
+    async fn update_loading_progress(&mut self, progress: u16, stage: LoadingStage) -> Result<()> {
         if let BrowserState::Loading { url, .. } = &self.current_state {
             let url = url.clone();
             self.set_loading_state(url, progress, stage);
-            self.ui.render(&self.current_state)?;
+            self.render()?;
             tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
         }
         Ok(())
     }
 
-    fn set_loading_state(&mut self, url: String, progress: u16, stage: &str) {
+    fn set_loading_state(&mut self, url: String, progress: u16, stage: LoadingStage) {
         self.current_state = BrowserState::Loading {
             url,
             progress,
-            stage: stage.to_string(),
+            stage,
+            thinking: None,
         };
     }
 
+    /// Load `url` stopping at its first redirect, which is shown as a page
+    /// linking to its target
+    async fn load_without_redirects(&mut self, url: String) -> Result<()> {
+        self.current_url = Some(url.clone());
+        self.set_loading_state(url.clone(), 25, LoadingStage::FetchingPage);
+        self.render()?;
+        self.ui.reset_scroll();
+
+        let result = match self.client.fetch_without_redirects(&url).await {
+            Ok(page) => self.process_fetched_page(&url, page).await,
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(page) => self.show_page(url, page),
+            Err(e) => {
+                self.current_state = BrowserState::Error {
+                    message: format!("Failed to load page: {}", e),
+                };
+                self.render()
+            }
+        }
+    }
+
     async fn handle_navigation_error(&mut self, url: &str, error: anyhow::Error) -> Result<()> {
+        // Some failures have one obvious next step; offer it instead of guessing
+        let tried = normalize_url(url).unwrap_or_else(|_| url.to_string());
+        match error.downcast_ref::<FetchError>() {
+            Some(FetchError::NotAccepted { .. }) => {
+                // The error stays behind the prompt, for when the answer is no
+                self.current_state = BrowserState::Error {
+                    message: error.to_string(),
+                };
+                let message = format!("{}. Open it in your browser?", error);
+                return self.show_confirm(&message, ConfirmAction::OpenInBrowser(tried));
+            }
+            // No other address or LLM suggestion helps while the server loops
+            Some(FetchError::RedirectLoop { .. }) => {
+                self.current_state = BrowserState::Error {
+                    message: error.to_string(),
+                };
+                let message = format!("{}. Load it without following redirects?", error);
+                return self.show_confirm(&message, ConfirmAction::LoadWithoutRedirects(tried));
+            }
+            Some(FetchError::Timeout { .. }) => {
+                return self.show_suggestions(
+                    url,
+                    &format!("{} - select to retry", error),
+                    vec![tried],
+                );
+            }
+            Some(FetchError::Tls { .. }) => {
+                if let Some(plain) = self
+                    .suggest_scheme_swap(&tried)
+                    .filter(|u| u.starts_with("http://"))
+                {
+                    let message = format!("{} - plain HTTP is not encrypted", error);
+                    return self.show_suggestions(url, &message, vec![plain]);
+                }
+            }
+            _ => {}
+        }
+
+        // Local-only mode probes no other addresses on its own
+        if self.config.local_only {
+            self.current_state = BrowserState::Error {
+                message: format!("Failed to load page: {}", error),
+            };
+            return self.render();
+        }
+
         match self.get_url_suggestions(url, &error.to_string()).await {
             Ok(suggestions) if !suggestions.is_empty() => {
-                self.current_state = BrowserState::URLSuggestions {
-                    original_url: url.to_string(),
-                    error_message: error.to_string(),
-                    suggestions,
-                    selected_index: 0,
-                };
-                self.ui.render(&self.current_state)?;
+                self.show_suggestions(url, &error.to_string(), suggestions)
             }
             _ => {
                 self.current_state = BrowserState::Error {
                     message: format!("Failed to load page: {}", error),
                 };
-                self.ui.render(&self.current_state)?;
+                self.render()
             }
         }
-        Ok(())
+    }
+
+    fn show_suggestions(
+        &mut self,
+        url: &str,
+        message: &str,
+        suggestions: Vec<String>,
+    ) -> Result<()> {
+        self.current_state = BrowserState::URLSuggestions {
+            original_url: url.to_string(),
+            error_message: message.to_string(),
+            suggestions,
+            selected_index: 0,
+        };
+        self.render()
     }
 
     async fn get_url_suggestions(
@@ -400,18 +2839,69 @@ impl Browser {
         failed_url: &str,
         error_message: &str,
     ) -> Result<Vec<String>> {
-        self.openai
-            .suggest_urls(failed_url, error_message)
-            .await
-            .or_else(|_| Ok(self.generate_fallback_suggestions(failed_url)))
+        // Obvious fixes (www, scheme, TLD) that answer are offered on their
+        // own; the LLM is only asked when none of them does. None is loaded
+        // until the user picks it.
+        let fallbacks =
+            self.usable_suggestions(failed_url, self.generate_fallback_suggestions(failed_url));
+        let fallbacks = if self.config.verify_suggestions {
+            self.keep_reachable(fallbacks).await
+        } else {
+            fallbacks
+        };
+        if !fallbacks.is_empty() {
+            return Ok(fallbacks);
+        }
+
+        let guesses = match self.llm()?.suggest_urls(failed_url, error_message).await {
+            Ok(guesses) => self.usable_suggestions(failed_url, guesses),
+            Err(e) => {
+                debug!(error = %e, "no LLM suggestions");
+                return Ok(Vec::new());
+            }
+        };
+        if !self.config.verify_suggestions {
+            return Ok(guesses);
+        }
+        Ok(self.keep_reachable(guesses).await)
+    }
+
+    /// `candidates` the domain rules allow, without the URL that just failed
+    fn usable_suggestions(&self, failed_url: &str, mut candidates: Vec<String>) -> Vec<String> {
+        let tried = normalize_url(failed_url).ok();
+        candidates.retain(|url| self.is_allowed(url) && normalize_url(url).ok() != tried);
+        candidates
+    }
+
+    /// Probe suggestions concurrently and keep only those that answer 2xx,
+    /// fastest first. Anything still pending at the deadline is dropped.
+    async fn keep_reachable(&self, candidates: Vec<String>) -> Vec<String> {
+        let deadline = tokio::time::Instant::now() + PROBE_TOTAL_TIMEOUT;
+        let mut probes = stream::iter(candidates)
+            .map(|url| async move { self.client.probe(&url).await.then_some(url) })
+            .buffer_unordered(PROBE_CONCURRENCY);
+
+        let mut reachable = Vec::new();
+        while let Ok(Some(result)) = tokio::time::timeout_at(deadline, probes.next()).await {
+            reachable.extend(result);
+        }
+
+        debug!(reachable = reachable.len(), "verified URL suggestions");
+        reachable
     }
 
     fn generate_fallback_suggestions(&self, failed_url: &str) -> Vec<String> {
         let clean_input = failed_url.trim();
-        let url_lower = failed_url.to_lowercase();
 
-        let suggestions = if url_lower.starts_with("http") {
-            self.suggest_www_variants(&url_lower)
+        // Only the scheme is matched without case: paths can be case-sensitive
+        let suggestions = if clean_input.to_ascii_lowercase().starts_with("http") {
+            let mut variants = self.suggest_www_variants(clean_input);
+            // Plain HTTP is only offered after a TLS error, with a warning
+            variants.extend(
+                self.suggest_scheme_swap(clean_input)
+                    .filter(|url| url.starts_with("https://")),
+            );
+            variants
         } else if !clean_input.contains('.') {
             self.suggest_domain_extensions(clean_input)
         } else {
@@ -420,25 +2910,35 @@ impl Browser {
 
         suggestions
             .into_iter()
-            .filter(|url| self.is_valid_url_format(url))
+            .filter(|url| self.is_valid_url_format(url) && self.is_allowed(url))
             .take(5)
             .collect()
     }
 
-    fn suggest_www_variants(&self, url_lower: &str) -> Vec<String> {
-        if !url_lower.contains("www.") {
-            if let Ok(parsed) = Url::parse(url_lower) {
-                if let Some(host) = parsed.host_str() {
-                    return vec![format!(
-                        "{}://www.{}{}",
-                        parsed.scheme(),
-                        host,
-                        parsed.path()
-                    )];
-                }
-            }
+    /// `url` on the `www.` host, keeping its path and query as they are
+    fn suggest_www_variants(&self, url: &str) -> Vec<String> {
+        let Ok(mut parsed) = Url::parse(url) else {
+            return Vec::new();
+        };
+        let Some(host) = parsed.host_str().filter(|host| !host.starts_with("www.")) else {
+            return Vec::new();
+        };
+        let www = format!("www.{}", host);
+        if parsed.set_host(Some(&www)).is_err() {
+            return Vec::new();
         }
-        Vec::new()
+        vec![parsed.to_string()]
+    }
+
+    /// `url` with http and https swapped, the rest of it unchanged
+    fn suggest_scheme_swap(&self, url: &str) -> Option<String> {
+        let scheme_len = url.find("://")?;
+        let swapped = match url[..scheme_len].to_ascii_lowercase().as_str() {
+            "https" => "http",
+            "http" => "https",
+            _ => return None,
+        };
+        Some(format!("{}{}", swapped, &url[scheme_len..]))
     }
 
     fn suggest_domain_extensions(&self, clean_input: &str) -> Vec<String> {
@@ -471,28 +2971,300 @@ impl Browser {
         }
     }
 
-    fn extract_title(&self, html: &str) -> String {
-        use scraper::{Html, Selector};
+    /// Whether the domain rules let `url` be visited
+    fn is_allowed(&self, url: &str) -> bool {
+        normalize_url(url)
+            .ok()
+            .and_then(|url| Url::parse(&url).ok())
+            .is_some_and(|url| self.domains.blocked_reason(&url).is_none())
+    }
+}
+
+/// `url` with `https://` added when it has no http(s) scheme
+pub fn normalize_url(url: &str) -> Result<String> {
+    let url = url.trim();
+    let with_protocol = if url.starts_with("http://") || url.starts_with("https://") {
+        url.to_string()
+    } else {
+        format!("https://{}", url)
+    };
+    Ok(Url::parse(&with_protocol)?.to_string())
+}
+
+/// `input` itself when it looks like an address, else the URL searching for it
+/// with `search_engine`, where `{}` stands for the URL-encoded query
+fn address_or_search(input: &str, search_engine: &str) -> String {
+    let input = input.trim();
+    if !is_search_query(input) {
+        return input.to_string();
+    }
+    let query: String = url::form_urlencoded::byte_serialize(input.as_bytes()).collect();
+    if search_engine.contains("{}") {
+        search_engine.replace("{}", &query)
+    } else {
+        format!("{}{}", search_engine, query)
+    }
+}
+
+/// Whether typed `input` is a search rather than an address: it has spaces,
+/// or no scheme, dot, port or `localhost` to make it a host
+fn is_search_query(input: &str) -> bool {
+    if input.is_empty() || input.contains("://") {
+        return false;
+    }
+    if input.contains(char::is_whitespace) {
+        return true;
+    }
+    let host = input.split(['/', '?', '#']).next().unwrap_or_default();
+    !(host.contains(['.', ':']) || host.eq_ignore_ascii_case("localhost"))
+}
+
+/// Text of the header a summary section starts with, `None` for the text
+/// before the first header
+fn section_heading(section: &str) -> Option<&str> {
+    let line = section.lines().next()?;
+    line.starts_with('#')
+        .then(|| line.trim_start_matches('#').trim())
+}
+
+/// Whether `a` and `b` address the same document, ignoring fragments and a trailing slash
+fn is_same_address(a: &str, b: &str) -> bool {
+    let strip = |url: &str| {
+        let mut url = Url::parse(url).ok()?;
+        url.set_fragment(None);
+        Some(url.as_str().trim_end_matches('/').to_string())
+    };
+    strip(a).is_some_and(|a| Some(a) == strip(b))
+}
+
+/// Title for a document without markup: the last part of its path, else its host
+pub fn document_title(url: &str) -> String {
+    let Ok(parsed) = Url::parse(url) else {
+        return url.to_string();
+    };
+    parsed
+        .path_segments()
+        .and_then(|mut segments| segments.rfind(|s| !s.is_empty()))
+        .map(str::to_string)
+        .or_else(|| parsed.host_str().map(str::to_string))
+        .unwrap_or_else(|| url.to_string())
+}
+
+/// Link numbered `index` to a page of a listed site, shown by its path
+fn path_link(index: usize, url: &str) -> Link {
+    Link {
+        text: Url::parse(url)
+            .map(|u| u.path().to_string())
+            .unwrap_or_else(|_| url.to_string()),
+        url: url.to_string(),
+        index,
+    }
+}
+
+/// The page's lead image if `protocol` can draw it, retried once after a
+/// transient failure. Failures are logged, never shown.
+async fn fetch_page_image(
+    client: &WebClient,
+    image_url: &str,
+    protocol: Protocol,
+) -> Option<Vec<u8>> {
+    let fetch = async {
+        match client.fetch_image(image_url).await {
+            Err(e)
+                if e.downcast_ref::<FetchError>()
+                    .is_some_and(FetchError::is_transient) =>
+            {
+                debug!(url = image_url, error = %e, "retrying page image");
+                tokio::time::sleep(IMAGE_RETRY_DELAY).await;
+                client.fetch_image(image_url).await
+            }
+            result => result,
+        }
+    };
+    match tokio::time::timeout(IMAGE_FETCH_TIMEOUT, fetch).await {
+        Ok(Ok(bytes)) if protocol.supports(&bytes) => Some(bytes),
+        Ok(Ok(_)) => {
+            debug!(url = image_url, "image format not supported by terminal");
+            None
+        }
+        Ok(Err(e)) => {
+            debug!(url = image_url, error = %e, "failed to fetch page image");
+            None
+        }
+        Err(_) => {
+            debug!(url = image_url, "page image timed out");
+            None
+        }
+    }
+}
+
+/// The page text as is, for `--local-only` where nothing is summarized
+pub(crate) fn unsummarized(text: &str) -> String {
+    format!("*Not summarized (--local-only).*\n\n{}", text)
+}
+
+/// What is shown instead of a summary when `text` has fewer than `min_words`
+/// words, too few to be worth an API call: a note and the text's first
+/// `excerpt_length` characters. `None` when there is enough to summarize.
+pub(crate) fn insufficient_content(
+    text: &str,
+    min_words: usize,
+    excerpt_length: usize,
+) -> Option<String> {
+    let words = text.split_whitespace().count();
+    (words < min_words).then(|| {
+        format!(
+            "*Insufficient content to summarize ({} words).*\n\nRaw text:\n{}",
+            words,
+            truncate_chars_at_word_boundary(text, excerpt_length)
+        )
+    })
+}
+
+/// A JSON response shown as received: pretty-printed, or as it came with
+/// why it did not parse
+fn json_response(json: &str, error: Option<&str>) -> Completion {
+    let note = match error {
+        Some(error) => format!("*Not valid JSON ({}), shown as received.*", error),
+        None => "*JSON response. `R` explains it with the LLM (`--explain-json` on load).*".into(),
+    };
+    Completion {
+        text: format!("{}\n\n{}", note, json_block(json)),
+        usage: None,
+    }
+}
+
+/// `json` fenced as code, so the summary pane keeps its indentation
+fn json_block(json: &str) -> String {
+    format!("```json\n{}\n```", json)
+}
+
+/// Append a numbered "References" section matching the `[n]` markers the
+/// summary was asked to use
+fn append_references(mut summary: String, references: &[(String, String)]) -> String {
+    if references.is_empty() {
+        return summary;
+    }
+
+    summary.push_str("\n\n## References\n\n");
+    for (i, (text, url)) in references.iter().enumerate() {
+        summary.push_str(&format!("- **[{}]** {}: {}\n", i + 1, text, url));
+    }
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{header, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    /// A UI that draws nothing and is never asked for input
+    struct HeadlessUI;
+
+    impl UIInterface for HeadlessUI {
+        fn new() -> Result<Self> {
+            Ok(Self)
+        }
+        fn cleanup(&mut self) -> Result<()> {
+            Ok(())
+        }
+        fn render(&mut self, _state: &BrowserState) -> Result<()> {
+            Ok(())
+        }
+        fn clear(&mut self) -> Result<()> {
+            Ok(())
+        }
+        fn set_split_ratio(&mut self, _content_percent: u16) {}
+        fn set_max_width(&mut self, _columns: u16) {}
+        fn set_line_spacing(&mut self, _enabled: bool) {}
+        fn set_summary_preview(&mut self, _chars: Option<usize>) {}
+        fn set_hyperlinks(&mut self, _enabled: bool) {}
+        fn set_emoji(&mut self, _enabled: bool) {}
+        fn get_user_input(
+            &mut self,
+            _state: &BrowserState,
+            _timeout: Option<Duration>,
+        ) -> Result<UserAction> {
+            unreachable!("tests drive the browser directly")
+        }
+        fn scroll_up(&mut self) {}
+        fn scroll_down(&mut self) {}
+        fn reset_scroll(&mut self) {}
+        fn scroll_position(&self) -> u16 {
+            0
+        }
+        fn set_scroll_position(&mut self, _position: u16) {}
+        fn max_scroll(&self) -> u16 {
+            0
+        }
+        fn summary_width(&self) -> usize {
+            80
+        }
+        fn scroll_to_text(&mut self, _summary: &str, _text: &str) -> bool {
+            false
+        }
+        fn scroll_pane(&mut self, _pane: usize, _lines: i32) {}
+        fn reset_pane_scroll(&mut self) {}
+        fn select_prev_link(&mut self, _total_links: usize) {}
+        fn select_next_link(&mut self, _total_links: usize) {}
+        fn get_selected_link(&self) -> usize {
+            0
+        }
+        fn select_link(&mut self, _index: usize) {}
+    }
+
+    #[tokio::test]
+    async fn redirect_loop_offers_no_redirects_instead_of_suggestions() {
+        let server = MockServer::start().await;
+        Mock::given(path("/loop"))
+            .respond_with(ResponseTemplate::new(302).insert_header("location", "/loop"))
+            .mount(&server)
+            .await;
+        let url = format!("{}/loop", server.uri());
+
+        // No LLM client, but not local-only either: had the error gone down
+        // the suggestion path, asking the LLM would have failed to an error page
+        let config = Config {
+            local_only: true,
+            ..Config::default()
+        };
+        let http = Http::new(4, &Default::default()).unwrap();
+        let mut browser = Browser::new(Box::new(HeadlessUI), config, http).unwrap();
+        browser.config.local_only = false;
 
-        let document = Html::parse_document(html);
-        let title_selector = Selector::parse("title").unwrap();
+        browser.navigate(&url).await.unwrap();
 
-        document
-            .select(&title_selector)
-            .next()
-            .map(|el| el.text().collect::<String>())
-            .unwrap_or_else(|| "Untitled".to_string())
-            .trim()
-            .to_string()
+        let BrowserState::Confirm { message, on_yes } = &browser.current_state else {
+            panic!("expected a confirmation, got {:?}", browser.current_state);
+        };
+        assert!(message.starts_with("Redirect loop detected"), "{}", message);
+        assert_eq!(*on_yes, ConfirmAction::LoadWithoutRedirects(url));
     }
 
-    fn normalize_url(&self, url: &str) -> Result<String> {
-        let url = url.trim();
-        let with_protocol = if url.starts_with("http://") || url.starts_with("https://") {
-            url.to_string()
-        } else {
-            format!("https://{}", url)
+    #[tokio::test]
+    async fn reachable_www_variant_is_offered_without_asking_the_llm() {
+        // Every host is proxied to the mock, which answers for www only
+        let server = MockServer::start().await;
+        Mock::given(header("host", "www.example.test"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("<p>Hello</p>"))
+            .mount(&server)
+            .await;
+        let config = Config {
+            local_only: true,
+            proxies: [("*".to_string(), server.uri())].into(),
+            ..Config::default()
+        };
+        let http = Http::new(4, &config.proxies).unwrap();
+        let mut browser = Browser::new(Box::new(HeadlessUI), config, http).unwrap();
+        // No LLM client: asking it would have ended in an error page
+        browser.config.local_only = false;
+
+        browser.navigate("http://example.test/").await.unwrap();
+
+        let BrowserState::URLSuggestions { suggestions, .. } = &browser.current_state else {
+            panic!("expected suggestions, got {:?}", browser.current_state);
         };
-        Ok(Url::parse(&with_protocol)?.to_string())
+        assert_eq!(*suggestions, vec!["http://www.example.test/".to_string()]);
     }
 }
@@ -0,0 +1,101 @@
+// Pages shown this session, for going back and forward without refetching
+// A small least-recently-used cache keyed by normalized URL: long sessions
+// keep at most `--cache-entries` pages, dropping the one unused the longest.
+
+use std::collections::VecDeque;
+use tracing::debug;
+
+/// Default number of pages kept for going back and forward
+pub const DEFAULT_CACHE_ENTRIES: usize = 20;
+
+pub struct LruCache<V> {
+    capacity: usize,
+    /// Least recently used first; few enough entries that a scan is cheap
+    entries: VecDeque<(String, V)>,
+}
+
+impl<V> LruCache<V> {
+    /// A cache of at most `capacity` entries; 0 caches nothing
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: VecDeque::new(),
+        }
+    }
+
+    /// The value cached under `key`, which becomes the most recently used
+    pub fn get(&mut self, key: &str) -> Option<&V> {
+        let index = self.entries.iter().position(|(k, _)| k == key)?;
+        let entry = self.entries.remove(index)?;
+        self.entries.push_back(entry);
+        self.entries.back().map(|(_, value)| value)
+    }
+
+    /// Cache `value` under `key` as the most recently used, replacing what was
+    /// there and evicting the least recently used entries beyond capacity
+    pub fn insert(&mut self, key: String, value: V) {
+        if self.capacity == 0 {
+            return;
+        }
+        self.entries.retain(|(k, _)| *k != key);
+        self.entries.push_back((key, value));
+        while self.entries.len() > self.capacity {
+            if let Some((evicted, _)) = self.entries.pop_front() {
+                debug!(url = evicted, "evicted page from cache");
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cache_of(capacity: usize, keys: &[&str]) -> LruCache<usize> {
+        let mut cache = LruCache::new(capacity);
+        for (value, key) in keys.iter().enumerate() {
+            cache.insert(key.to_string(), value);
+        }
+        cache
+    }
+
+    #[test]
+    fn evicts_least_recently_inserted_beyond_capacity() {
+        let mut cache = cache_of(2, &["a", "b", "c"]);
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get("a"), None);
+        assert_eq!(cache.get("b"), Some(&1));
+        assert_eq!(cache.get("c"), Some(&2));
+    }
+
+    #[test]
+    fn get_makes_an_entry_most_recently_used() {
+        let mut cache = cache_of(2, &["a", "b"]);
+        assert_eq!(cache.get("a"), Some(&0));
+        cache.insert("c".to_string(), 2);
+        assert_eq!(cache.get("b"), None);
+        assert_eq!(cache.get("a"), Some(&0));
+        assert_eq!(cache.get("c"), Some(&2));
+    }
+
+    #[test]
+    fn insert_replaces_and_promotes_an_existing_key() {
+        let mut cache = cache_of(2, &["a", "b"]);
+        cache.insert("a".to_string(), 10);
+        cache.insert("c".to_string(), 2);
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get("a"), Some(&10));
+        assert_eq!(cache.get("b"), None);
+    }
+
+    #[test]
+    fn capacity_zero_caches_nothing() {
+        let mut cache = cache_of(0, &["a", "b"]);
+        assert_eq!(cache.len(), 0);
+        assert_eq!(cache.get("a"), None);
+    }
+}
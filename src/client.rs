@@ -1,52 +1,747 @@
+use crate::{common::markdown::escape_html, http::Http};
 use anyhow::{anyhow, Result};
-use reqwest::Client;
-use std::time::Duration;
+use reqwest::header::{ACCEPT, LOCATION, RANGE};
+use reqwest::{Method, StatusCode};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
+use url::Url;
 
-const USER_AGENT: &str = "bbow/0.1.0";
-const REQUEST_TIMEOUT_SECS: u64 = 30;
-const MAX_REDIRECTS: usize = 5;
+const PROBE_TIMEOUT_SECS: u64 = 3;
+/// Images larger than this are not worth drawing in a header corner
+const MAX_IMAGE_BYTES: usize = 4 * 1024 * 1024;
+/// Declared types that say nothing reliable about the body, so it is sniffed
+const AMBIGUOUS_CONTENT_TYPES: &[&str] = &[
+    "",
+    "application/octet-stream",
+    "binary/octet-stream",
+    "application/unknown",
+    "text/plain",
+];
+/// How far into the body to look for the start of an HTML document
+const SNIFF_BYTES: usize = 512;
+/// Content types read when `--accept` is not given
+pub const DEFAULT_ACCEPT: &[&str] = &["text/html"];
+/// HTML served as XML; read as HTML whenever HTML is accepted
+const XHTML: &str = "application/xhtml+xml";
+
+/// Why a fetch failed, so the browser can offer a recovery that fits:
+/// spelling fixes for DNS failures, plain HTTP after a TLS error, a retry
+/// after a timeout. Returned inside `anyhow::Error`; use `downcast_ref`.
+#[derive(Debug)]
+pub enum FetchError {
+    /// The host name did not resolve
+    Dns { host: String },
+    /// The TLS handshake or certificate check failed
+    Tls { host: String, detail: String },
+    /// The server actively refused the connection
+    ConnectionRefused { host: String },
+    /// No response (or no complete body) within the request timeout
+    Timeout { url: String },
+    /// The server answered with a non-2xx status
+    Http { status: u16, url: String },
+    /// The response is of a type not in the `--accept` list
+    NotAccepted { content_type: String },
+    /// Redirects went on past the limit, usually because they go round in a loop
+    RedirectLoop { url: String },
+    /// Anything else reqwest reported
+    Other { url: String, detail: String },
+}
+
+impl FetchError {
+    /// Sort a reqwest error into one of the kinds above by walking its
+    /// source chain, since hyper and the TLS backend only expose messages
+    fn classify(url: &str, error: &reqwest::Error) -> Self {
+        let host = Url::parse(url)
+            .ok()
+            .and_then(|u| u.host_str().map(str::to_string))
+            .unwrap_or_else(|| url.to_string());
+
+        if error.is_timeout() {
+            return Self::Timeout {
+                url: url.to_string(),
+            };
+        }
+        if error.is_redirect() {
+            return Self::RedirectLoop {
+                url: url.to_string(),
+            };
+        }
+
+        let mut chain = Vec::new();
+        let mut source: Option<&dyn std::error::Error> = Some(error);
+        while let Some(err) = source {
+            chain.push(err.to_string());
+            source = err.source();
+        }
+        let detail = chain.last().cloned().unwrap_or_default();
+        let chain = chain.join(": ").to_lowercase();
+
+        if chain.contains("dns error")
+            || chain.contains("failed to lookup address")
+            || chain.contains("name or service not known")
+        {
+            Self::Dns { host }
+        } else if chain.contains("certificate")
+            || chain.contains("tls")
+            || chain.contains("ssl")
+            || chain.contains("handshake")
+        {
+            Self::Tls { host, detail }
+        } else if chain.contains("connection refused") {
+            Self::ConnectionRefused { host }
+        } else if chain.contains("timed out") {
+            Self::Timeout {
+                url: url.to_string(),
+            }
+        } else {
+            Self::Other {
+                url: url.to_string(),
+                detail: error.to_string(),
+            }
+        }
+    }
+}
+
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Dns { host } => {
+                write!(f, "Could not find the server {} (DNS lookup failed)", host)
+            }
+            Self::Tls { host, detail } => {
+                write!(f, "Secure connection to {} failed: {}", host, detail)
+            }
+            Self::ConnectionRefused { host } => write!(f, "{} refused the connection", host),
+            Self::Timeout { url } => write!(f, "{} took too long to respond", url),
+            Self::Http { status, url } => write!(f, "HTTP error {}: {}", status, url),
+            Self::NotAccepted { content_type } if content_type.is_empty() => {
+                write!(
+                    f,
+                    "The page has no content type and does not look like HTML"
+                )
+            }
+            Self::NotAccepted { content_type } => {
+                write!(f, "bbow does not read {} (see --accept)", content_type)
+            }
+            Self::RedirectLoop { url } => {
+                write!(
+                    f,
+                    "Redirect loop detected: {} redirects too many times",
+                    url
+                )
+            }
+            Self::Other { url, detail } => write!(f, "Failed to fetch {}: {}", url, detail),
+        }
+    }
+}
+
+impl std::error::Error for FetchError {}
+
+impl FetchError {
+    /// Whether trying again shortly might work: timeouts, dropped
+    /// connections and server-side or rate-limit statuses
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Self::Timeout { .. } | Self::ConnectionRefused { .. } | Self::Other { .. } => true,
+            Self::Http { status, .. } => *status >= 500 || matches!(status, 408 | 429),
+            Self::Dns { .. }
+            | Self::Tls { .. }
+            | Self::NotAccepted { .. }
+            | Self::RedirectLoop { .. } => false,
+        }
+    }
+}
+
+/// A successfully fetched page and the response details worth showing
+pub struct FetchedPage {
+    /// The response text, pretty-printed when it is JSON
+    pub body: String,
+    /// Whether `body` is HTML; other accepted types are read as plain text
+    pub is_html: bool,
+    /// Whether the response declared itself JSON
+    pub is_json: bool,
+    /// Why a JSON body did not parse, in which case it is left as received
+    pub json_error: Option<String>,
+    pub status: u16,
+    pub content_type: String,
+}
 
 pub struct WebClient {
-    client: Client,
+    http: Http,
+    host_delay: Duration,
+    /// Earliest start of the next request to each host, while `host_delay` is set
+    next_request: Mutex<HashMap<String, Instant>>,
+    /// Response body bytes read since startup
+    bytes_fetched: AtomicU64,
+    /// Lowercase MIME types (or `type/*` patterns) `fetch` reads
+    accept: Vec<String>,
+    /// `Accept` header sent with page requests instead of one built from `accept`
+    accept_header: Option<String>,
 }
 
 impl WebClient {
-    pub fn new() -> Self {
-        let client = Client::builder()
-            .user_agent(USER_AGENT)
-            .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
-            .redirect(reqwest::redirect::Policy::limited(MAX_REDIRECTS))
-            .build()
-            .expect("Failed to create HTTP client");
+    pub fn new(http: Http) -> Self {
+        Self {
+            http,
+            host_delay: Duration::ZERO,
+            next_request: Mutex::new(HashMap::new()),
+            bytes_fetched: AtomicU64::new(0),
+            accept: DEFAULT_ACCEPT.iter().map(|mime| mime.to_string()).collect(),
+            accept_header: None,
+        }
+    }
 
-        Self { client }
+    /// Read responses of these MIME types; `text/*` and `*/*` match whole groups
+    pub fn with_accept(mut self, types: &[String]) -> Self {
+        self.accept = types
+            .iter()
+            .map(|mime| mime.trim().to_ascii_lowercase())
+            .filter(|mime| !mime.is_empty())
+            .collect();
+        self
     }
 
-    pub async fn fetch(&self, url: &str) -> Result<String> {
+    /// Send `header` as the `Accept` header of page requests, for servers
+    /// that want something other than what the accepted types ask for
+    pub fn with_accept_header(mut self, header: Option<&str>) -> Self {
+        self.accept_header = header.map(str::to_string);
+        self
+    }
+
+    fn accept_header(&self) -> String {
+        self.accept_header
+            .clone()
+            .unwrap_or_else(|| accept_header(&self.accept))
+    }
+
+    fn accepts(&self, mime: &str) -> bool {
+        let mime = if mime == XHTML { "text/html" } else { mime };
+        let group = mime.split('/').next().unwrap_or("");
+        self.accept.iter().any(|pattern| {
+            pattern == mime
+                || pattern == "*/*"
+                || pattern.strip_suffix("/*") == Some(group) && !group.is_empty()
+        })
+    }
+
+    /// Start requests to the same host at least `delay` apart. Requests to
+    /// other hosts are not held up.
+    pub fn with_host_delay(mut self, delay: Duration) -> Self {
+        self.host_delay = delay;
+        self
+    }
+
+    /// Response body bytes read so far, across pages, sitemaps and images
+    pub fn bytes_fetched(&self) -> u64 {
+        self.bytes_fetched.load(Ordering::Relaxed)
+    }
+
+    fn count_bytes(&self, len: usize) {
+        self.bytes_fetched.fetch_add(len as u64, Ordering::Relaxed);
+    }
+
+    pub async fn fetch(&self, url: &str) -> Result<FetchedPage> {
+        let started = Instant::now();
+        debug!(url, "fetching page");
+
+        self.wait_for_host(url).await;
+        let _permit = self.http.permit().await;
         let response = self
-            .client
+            .http
+            .client()
             .get(url)
+            .header(ACCEPT, self.accept_header())
             .send()
             .await
-            .map_err(|e| anyhow!("Failed to fetch {}: {}", url, e))?;
+            .map_err(|e| {
+                let error = FetchError::classify(url, &e);
+                warn!(url, error = %e, kind = ?error, "request failed");
+                error
+            })?;
+
+        info!(
+            url,
+            final_url = %response.url(),
+            status = response.status().as_u16(),
+            elapsed_ms = started.elapsed().as_millis() as u64,
+            "received response"
+        );
+        self.read_page(url, response).await
+    }
+
+    /// Fetch `url` like `fetch`, but without following redirects. A redirect
+    /// is read as a short page linking to where it points, so the user can
+    /// step through a redirect loop by hand.
+    pub async fn fetch_without_redirects(&self, url: &str) -> Result<FetchedPage> {
+        self.wait_for_host(url).await;
+        let _permit = self.http.permit().await;
+        let response = self
+            .http
+            .client_without_redirects()
+            .get(url)
+            .header(ACCEPT, self.accept_header())
+            .send()
+            .await
+            .map_err(|e| FetchError::classify(url, &e))?;
+
+        let status = response.status();
+        debug!(
+            url,
+            status = status.as_u16(),
+            "fetched without following redirects"
+        );
+        if !status.is_redirection() {
+            return self.read_page(url, response).await;
+        }
+        let target = response
+            .headers()
+            .get(LOCATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|location| Url::parse(url).ok()?.join(location).ok());
+        let target = match target {
+            Some(target) => {
+                let target = escape_html(target.as_str());
+                format!("<a href=\"{}\">{}</a>", target, target)
+            }
+            None => "nowhere (no Location header)".to_string(),
+        };
+        let body = format!(
+            "<html><head><title>Redirect {status}</title></head><body><main><p>{url} \
+            redirects ({status}) to {target}</p></main></body></html>",
+            status = status.as_u16(),
+            url = escape_html(url),
+            target = target,
+        );
+        Ok(FetchedPage {
+            body,
+            is_html: true,
+            is_json: false,
+            json_error: None,
+            status: status.as_u16(),
+            content_type: "text/html".to_string(),
+        })
+    }
 
+    /// Check and read the body of a page response
+    async fn read_page(&self, url: &str, response: reqwest::Response) -> Result<FetchedPage> {
         if !response.status().is_success() {
-            return Err(anyhow!("HTTP error {}: {}", response.status(), url));
+            return Err(FetchError::Http {
+                status: response.status().as_u16(),
+                url: url.to_string(),
+            }
+            .into());
         }
 
+        let status = response.status().as_u16();
         let content_type = response
             .headers()
             .get("content-type")
             .and_then(|v| v.to_str().ok())
-            .unwrap_or("");
+            .unwrap_or("")
+            .to_string();
+        let mime = mime_type(&content_type);
+
+        // Misconfigured servers send HTML as octet-stream or with no type at all
+        let sniff = is_ambiguous_type(&mime) && self.accepts("text/html");
+        if !sniff && !self.accepts(&mime) {
+            warn!(
+                url,
+                content_type, "rejecting response of a type not accepted"
+            );
+            return Err(FetchError::NotAccepted { content_type: mime }.into());
+        }
+
+        let (body, is_html) = if sniff {
+            let bytes = response
+                .bytes()
+                .await
+                .map_err(|e| FetchError::classify(url, &e))?;
+            self.count_bytes(bytes.len());
+            let is_html = looks_like_html(&bytes);
+            if is_html {
+                debug!(
+                    url,
+                    content_type, "declared type is ambiguous but body is HTML"
+                );
+            } else if !self.accepts(&mime) {
+                warn!(
+                    url,
+                    content_type, "rejecting response that does not look like HTML"
+                );
+                return Err(FetchError::NotAccepted { content_type: mime }.into());
+            }
+            (String::from_utf8_lossy(&bytes).into_owned(), is_html)
+        } else {
+            let text = response
+                .text()
+                .await
+                .map_err(|e| FetchError::classify(url, &e))?;
+            self.count_bytes(text.len());
+            (text, mime == "text/html" || mime == XHTML)
+        };
+        let is_json = is_json(&mime);
+        let (body, json_error) = match is_json.then(|| pretty_json(&body)) {
+            Some(Ok(pretty)) => (pretty, None),
+            Some(Err(e)) => {
+                debug!(url, error = %e, "JSON response does not parse");
+                (body, Some(e.to_string()))
+            }
+            None => (body, None),
+        };
+
+        debug!(url, bytes = body.len(), is_html, "read response body");
+        Ok(FetchedPage {
+            body,
+            is_html,
+            is_json,
+            json_error,
+            status,
+            content_type,
+        })
+    }
+
+    /// Fetch any text document (e.g. a sitemap), without the HTML-only check of `fetch`
+    pub async fn fetch_text(&self, url: &str) -> Result<String> {
+        self.wait_for_host(url).await;
+        let _permit = self.http.permit().await;
+        let response = self
+            .http
+            .client()
+            .get(url)
+            .send()
+            .await
+            .map_err(|e| anyhow!("Failed to fetch {}: {}", url, e))?;
 
-        if !content_type.contains("text/html") {
-            return Err(anyhow!("Not an HTML page: {}", content_type));
+        if !response.status().is_success() {
+            return Err(anyhow!("HTTP error {}: {}", response.status(), url));
         }
 
-        response
+        let text = response
             .text()
             .await
-            .map_err(|e| anyhow!("Failed to read response body: {}", e))
+            .map_err(|e| anyhow!("Failed to read response body: {}", e))?;
+        self.count_bytes(text.len());
+        Ok(text)
+    }
+
+    /// Download an image, refusing non-image responses and oversized files
+    pub async fn fetch_image(&self, url: &str) -> Result<Vec<u8>> {
+        self.wait_for_host(url).await;
+        let _permit = self.http.permit().await;
+        let response = self
+            .http
+            .client()
+            .get(url)
+            .send()
+            .await
+            .map_err(|e| FetchError::classify(url, &e))?;
+
+        if !response.status().is_success() {
+            return Err(FetchError::Http {
+                status: response.status().as_u16(),
+                url: url.to_string(),
+            }
+            .into());
+        }
+
+        let is_image = response
+            .headers()
+            .get("content-type")
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|ct| ct.starts_with("image/"));
+        if !is_image {
+            return Err(anyhow!("Not an image: {}", url));
+        }
+        if response
+            .content_length()
+            .is_some_and(|len| len as usize > MAX_IMAGE_BYTES)
+        {
+            return Err(anyhow!("Image too large: {}", url));
+        }
+
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|e| FetchError::classify(url, &e))?;
+        self.count_bytes(bytes.len());
+        if bytes.len() > MAX_IMAGE_BYTES {
+            return Err(anyhow!("Image too large: {}", url));
+        }
+        Ok(bytes.to_vec())
+    }
+
+    /// Cheap reachability check: a HEAD request that must answer 2xx quickly.
+    /// Servers that don't support HEAD get a GET for the first byte instead.
+    pub async fn probe(&self, url: &str) -> bool {
+        self.wait_for_host(url).await;
+        let Some(status) = self.probe_with(url, Method::HEAD).await else {
+            return false;
+        };
+        if status != StatusCode::METHOD_NOT_ALLOWED && status != StatusCode::NOT_IMPLEMENTED {
+            return status.is_success();
+        }
+        debug!(url, "HEAD not supported, probing with a ranged GET");
+        match self.probe_with(url, Method::GET).await {
+            // An empty body can't satisfy the range, but the page is there
+            Some(status) => status.is_success() || status == StatusCode::RANGE_NOT_SATISFIABLE,
+            None => false,
+        }
+    }
+
+    /// Status of a probe request, `None` when it got no response in time. A
+    /// GET asks for the first byte only and the body is never read.
+    async fn probe_with(&self, url: &str, method: Method) -> Option<StatusCode> {
+        let _permit = self.http.permit().await;
+        let mut request = self
+            .http
+            .client()
+            .request(method.clone(), url)
+            .timeout(Duration::from_secs(PROBE_TIMEOUT_SECS));
+        if method == Method::GET {
+            request = request.header(RANGE, "bytes=0-0");
+        }
+
+        match request.send().await {
+            Ok(response) => {
+                debug!(url, %method, status = response.status().as_u16(), "probed URL");
+                Some(response.status())
+            }
+            Err(e) => {
+                debug!(url, %method, error = %e, "probe failed");
+                None
+            }
+        }
+    }
+
+    /// Sleep until `url`'s host may be contacted again and book the slot
+    /// after that, so concurrent requests to one host queue up in turn
+    async fn wait_for_host(&self, url: &str) {
+        if self.host_delay.is_zero() {
+            return;
+        }
+        let Some(host) = Url::parse(url)
+            .ok()
+            .and_then(|u| u.host_str().map(str::to_lowercase))
+        else {
+            return;
+        };
+
+        let start = {
+            let mut next_request = self
+                .next_request
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            let now = Instant::now();
+            let start = next_request.get(&host).map_or(now, |&next| next.max(now));
+            next_request.insert(host.clone(), start + self.host_delay);
+            start
+        };
+
+        let wait = start.saturating_duration_since(Instant::now());
+        if !wait.is_zero() {
+            debug!(
+                host,
+                wait_ms = wait.as_millis() as u64,
+                "delaying request to host"
+            );
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+/// The lowercase MIME type of a `Content-Type` header, without parameters
+fn mime_type(content_type: &str) -> String {
+    content_type
+        .split(';')
+        .next()
+        .unwrap_or("")
+        .trim()
+        .to_ascii_lowercase()
+}
+
+/// Whether `mime` is missing or too generic to trust
+fn is_ambiguous_type(mime: &str) -> bool {
+    AMBIGUOUS_CONTENT_TYPES.contains(&mime)
+}
+
+/// `Accept` header asking for `types`, HTML first. Without one reqwest
+/// sends `*/*`, and servers that negotiate may answer HTML pages with
+/// another format or a 406.
+fn accept_header(types: &[String]) -> String {
+    let html = types.iter().any(|mime| mime == "text/html");
+    let mut parts: Vec<String> = Vec::new();
+    if html {
+        parts.extend(["text/html".to_string(), XHTML.to_string()]);
+    }
+    // Anything besides HTML is asked for with a lower preference
+    let others: Vec<String> = types
+        .iter()
+        .filter(|mime| !parts.contains(mime))
+        .map(|mime| {
+            if html {
+                format!("{};q=0.9", mime)
+            } else {
+                mime.clone()
+            }
+        })
+        .collect();
+    parts.extend(others);
+    if parts.is_empty() {
+        return "*/*".to_string();
+    }
+    parts.join(",")
+}
+
+fn is_json(mime: &str) -> bool {
+    mime == "application/json" || mime.ends_with("+json")
+}
+
+/// `body` indented for reading, or why it does not parse as JSON
+fn pretty_json(body: &str) -> serde_json::Result<String> {
+    serde_json::from_str::<serde_json::Value>(body)
+        .and_then(|value| serde_json::to_string_pretty(&value))
+}
+
+/// Whether the body starts like an HTML document (`<!doctype html` or `<html`),
+/// after any byte order mark, whitespace and leading comments
+fn looks_like_html(body: &[u8]) -> bool {
+    let head = &body[..body.len().min(SNIFF_BYTES)];
+    let mut rest = head.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(head);
+    loop {
+        rest = rest.trim_ascii_start();
+        if rest.starts_with(b"<!--") {
+            match rest.windows(3).position(|w| w == b"-->") {
+                Some(end) => rest = &rest[end + 3..],
+                None => return false,
+            }
+            continue;
+        }
+        let starts_with = |prefix: &[u8]| {
+            rest.len() >= prefix.len() && rest[..prefix.len()].eq_ignore_ascii_case(prefix)
+        };
+        return starts_with(b"<!doctype html") || starts_with(b"<html");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn client() -> WebClient {
+        WebClient::new(Http::new(4, &HashMap::new()).unwrap())
+    }
+
+    /// A server answering HEAD with `head` and a ranged GET with `get`, or
+    /// failing the test on any GET when `get` is `None`
+    async fn server(head: u16, get: Option<u16>) -> MockServer {
+        let server = MockServer::start().await;
+        Mock::given(method("HEAD"))
+            .respond_with(ResponseTemplate::new(head))
+            .mount(&server)
+            .await;
+        let get_mock = match get {
+            Some(status) => Mock::given(method("GET"))
+                .and(header("range", "bytes=0-0"))
+                .respond_with(ResponseTemplate::new(status)),
+            None => Mock::given(method("GET"))
+                .respond_with(ResponseTemplate::new(200))
+                .expect(0),
+        };
+        get_mock.mount(&server).await;
+        server
+    }
+
+    #[test]
+    fn sniffs_html_documents() {
+        for (body, html) in [
+            ("<!DOCTYPE html><html><body>Hi</body></html>", true),
+            ("<!doctype HTML>", true),
+            ("<html lang=\"en\">", true),
+            ("<HTML>", true),
+            ("\u{feff}<!DOCTYPE html>", true),
+            ("\n\t  <html>", true),
+            ("<!-- generated --> <!DOCTYPE html>", true),
+            ("<!-- never closed <html>", false),
+            ("<?xml version=\"1.0\"?><rss>", false),
+            ("{\"html\": true}", false),
+            ("plain text mentioning <html>", false),
+            ("", false),
+        ] {
+            assert_eq!(looks_like_html(body.as_bytes()), html, "{:?}", body);
+        }
+    }
+
+    #[test]
+    fn only_uninformative_types_are_ambiguous() {
+        for (content_type, ambiguous) in [
+            ("", true),
+            ("application/octet-stream", true),
+            ("binary/octet-stream", true),
+            ("application/unknown", true),
+            ("text/plain; charset=utf-8", true),
+            ("Text/Plain", true),
+            ("text/html", false),
+            ("application/json", false),
+            ("image/png", false),
+        ] {
+            let mime = mime_type(content_type);
+            assert_eq!(is_ambiguous_type(&mime), ambiguous, "{:?}", content_type);
+        }
+    }
+
+    #[tokio::test]
+    async fn probe_trusts_a_head_answer() {
+        for (head, reachable) in [(200, true), (404, false), (500, false)] {
+            let server = server(head, None).await;
+            assert_eq!(
+                client().probe(&server.uri()).await,
+                reachable,
+                "HEAD {}",
+                head
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn probe_falls_back_to_a_ranged_get_when_head_is_unsupported() {
+        for (head, get, reachable) in [
+            (405, 206, true),
+            (501, 200, true),
+            (405, 416, true),
+            (501, 404, false),
+        ] {
+            let server = server(head, Some(get)).await;
+            assert_eq!(
+                client().probe(&server.uri()).await,
+                reachable,
+                "HEAD {} then GET {}",
+                head,
+                get
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn endless_redirects_are_a_redirect_loop() {
+        let server = MockServer::start().await;
+        Mock::given(path("/loop"))
+            .respond_with(ResponseTemplate::new(302).insert_header("location", "/loop"))
+            .mount(&server)
+            .await;
+        let url = format!("{}/loop", server.uri());
+        let error = client().fetch(&url).await.err().unwrap();
+        let error = error.downcast_ref::<FetchError>().unwrap();
+        assert!(
+            matches!(error, FetchError::RedirectLoop { .. }),
+            "{:?}",
+            error
+        );
+        assert!(!error.is_transient());
     }
 }
@@ -0,0 +1,22 @@
+// Clipboard access through the OSC 52 escape sequence. Works in most modern
+// terminals, including over SSH, and needs no system clipboard libraries.
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use std::io::{self, Write};
+
+/// Ask the terminal to put `text` on the system clipboard
+pub fn copy(text: &str) -> io::Result<()> {
+    let mut out = io::stdout();
+    write!(out, "\x1b]52;c;{}\x07", STANDARD.encode(text))?;
+    out.flush()
+}
+
+/// `[title](url)`, escaping the characters that would end either part early
+pub fn markdown_link(title: &str, url: &str) -> String {
+    let title = title.trim().replace('[', "\\[").replace(']', "\\]");
+    let url = url
+        .replace(' ', "%20")
+        .replace('(', "%28")
+        .replace(')', "%29");
+    format!("[{}]({})", title, url)
+}
@@ -0,0 +1,100 @@
+// Word-level diff between two versions of a summary
+// Changed words are wrapped in marker characters inside the new markdown, so
+// the shared markdown renderer can style them without a separate layout path
+
+use similar::{ChangeTag, TextDiff};
+
+/// Opens a run of words only in the new summary
+pub const ADDED_START: char = '\u{1}';
+/// Opens a run of words only in the previous summary
+pub const REMOVED_START: char = '\u{2}';
+/// Closes an added or removed run
+pub const CHANGE_END: char = '\u{3}';
+
+/// `summary` marked with what changed since `previous`, or unchanged when
+/// there is nothing to compare against
+pub fn summary_with_changes(summary: &str, previous: Option<&str>) -> String {
+    match previous {
+        Some(previous) => mark_word_changes(previous, summary),
+        None => summary.to_string(),
+    }
+}
+
+/// `new` with the words that differ from `old` marked: added words where they
+/// are, removed words just before what replaced them. Line breaks follow
+/// `new`, and list or heading markers starting a line are left unmarked, so
+/// the result keeps the markdown structure of `new`.
+pub fn mark_word_changes(old: &str, new: &str) -> String {
+    let mut marked = Marked::default();
+
+    for change in TextDiff::from_words(old, new).iter_all_changes() {
+        let token = change.value();
+        let is_space = token.trim().is_empty();
+        let is_marker = is_block_marker(token) && marked.at_line_start();
+
+        match change.tag() {
+            ChangeTag::Equal => marked.push(None, token),
+            ChangeTag::Insert if is_space || is_marker => marked.push(None, token),
+            ChangeTag::Insert => marked.push(Some(ADDED_START), token),
+            // Keep removed line breaks so removed lines stay on their own
+            ChangeTag::Delete if token.contains('\n') => marked.push(None, token),
+            ChangeTag::Delete if is_marker => {}
+            ChangeTag::Delete if is_space => marked.push(Some(REMOVED_START), " "),
+            ChangeTag::Delete => marked.push(Some(REMOVED_START), token),
+        }
+    }
+
+    marked.finish()
+}
+
+#[derive(Default)]
+struct Marked {
+    text: String,
+    /// Marker of the run currently open, if any
+    open: Option<char>,
+}
+
+impl Marked {
+    fn push(&mut self, run: Option<char>, token: &str) {
+        if self.open != run {
+            let after_removed = self.open == Some(REMOVED_START);
+            if self.open.is_some() {
+                self.text.push(CHANGE_END);
+            }
+            // Removed words would otherwise run into the word replacing them
+            if after_removed && !token.starts_with(char::is_whitespace) {
+                self.text.push(' ');
+            }
+            if let Some(marker) = run {
+                self.text.push(marker);
+            }
+            self.open = run;
+        }
+        self.text.push_str(token);
+    }
+
+    fn at_line_start(&self) -> bool {
+        self.text
+            .rsplit('\n')
+            .next()
+            .is_none_or(|line| line.trim().is_empty())
+    }
+
+    fn finish(mut self) -> String {
+        if self.open.is_some() {
+            self.text.push(CHANGE_END);
+        }
+        self.text
+    }
+}
+
+/// Tokens that give a line its markdown structure: `#` headings, `-`/`*`/`+`
+/// bullets, `>` quotes and `1.` numbered items
+fn is_block_marker(token: &str) -> bool {
+    if matches!(token, "-" | "*" | "+" | ">") || token.chars().all(|c| c == '#') {
+        return !token.is_empty();
+    }
+    token
+        .strip_suffix(['.', ')'])
+        .is_some_and(|number| !number.is_empty() && number.chars().all(|c| c.is_ascii_digit()))
+}
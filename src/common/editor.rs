@@ -0,0 +1,78 @@
+// Opening text in the user's editor, for looking at what bbow extracted from
+// a page. The TUI has to hand over the terminal while the editor runs.
+
+use anyhow::{anyhow, Context, Result};
+use std::fs::OpenOptions;
+use std::io::{ErrorKind, Write};
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Names of temp files tried before giving up on finding a free one
+const MAX_TEMP_ATTEMPTS: usize = 100;
+
+/// Numbers temp files apart within a session
+static NEXT_TEMP_FILE: AtomicUsize = AtomicUsize::new(0);
+
+/// The editor from `$VISUAL` or `$EDITOR`, split into the program and its
+/// arguments (e.g. `code --wait`), or `None` when neither is set
+pub fn command() -> Option<Vec<String>> {
+    ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .map(|value| {
+            value
+                .split_whitespace()
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        })
+        .find(|words| !words.is_empty())
+}
+
+/// Write `text` to a temp file and open it in `editor`, waiting for the
+/// editor to exit. The file is kept so it can be looked at again.
+pub fn open(editor: &[String], text: &str) -> Result<PathBuf> {
+    let path = write_temp_file(text)?;
+
+    let (program, args) = editor.split_first().context("No editor command")?;
+    let status = Command::new(program)
+        .args(args)
+        .arg(&path)
+        .status()
+        .with_context(|| format!("Could not start {}", program))?;
+    if !status.success() {
+        return Err(anyhow!("{} exited with {}", program, status));
+    }
+    Ok(path)
+}
+
+/// Write `text` to a new temp file only this user can read. The file must
+/// not exist yet, so a link planted at the name can't redirect the write.
+fn write_temp_file(text: &str) -> Result<PathBuf> {
+    for _ in 0..MAX_TEMP_ATTEMPTS {
+        let name = format!(
+            "bbow-extract-{}-{}.txt",
+            std::process::id(),
+            NEXT_TEMP_FILE.fetch_add(1, Ordering::Relaxed)
+        );
+        let path = std::env::temp_dir().join(name);
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = match options.open(&path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => continue,
+            Err(e) => {
+                return Err(e).with_context(|| format!("Could not create {}", path.display()));
+            }
+        };
+        file.write_all(text.as_bytes())
+            .with_context(|| format!("Could not write {}", path.display()))?;
+        return Ok(path);
+    }
+    Err(anyhow!("Could not find a free temp file name"))
+}
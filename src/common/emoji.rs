@@ -0,0 +1,47 @@
+// Decorative emoji in titles, labels and badges, and their ASCII stand-ins
+// Some terminals and fonts draw emoji as tofu or at the wrong width, which
+// shifts whole columns, so `--no-emoji` swaps them out everywhere at once
+
+use std::borrow::Cow;
+
+/// Emoji used in the chrome and what `--no-emoji` shows instead
+const ASCII: &[(&str, &str)] = &[
+    ("🌐", "@"),
+    ("💬", ">"),
+    ("📊", "#"),
+    ("📄", "="),
+    ("⌨", "::"),
+    ("📍", "@"),
+    ("🔍", "/"),
+    ("🔗", "->"),
+    ("➤", ">"),
+    ("📚", "::"),
+    ("🔖", "*"),
+    ("❌", "x"),
+    ("💡", "?"),
+    ("❓", "?"),
+    ("⚙", "::"),
+    ("⚡", "~"),
+    ("⚠", "!"),
+    ("ℹ", "i"),
+    ("📝", "#"),
+    ("🎨", "*"),
+];
+
+/// Asks for the emoji rather than the text form of the character before it
+const VARIATION_SELECTOR: char = '\u{FE0F}';
+
+/// `label` as shown in the chrome: unchanged while `enabled`, or with its
+/// emoji replaced under `--no-emoji`
+pub fn chrome(label: &str, enabled: bool) -> Cow<'_, str> {
+    if enabled {
+        return Cow::Borrowed(label);
+    }
+    let mut label = label.replace(VARIATION_SELECTOR, "");
+    for (emoji, ascii) in ASCII {
+        if label.contains(emoji) {
+            label = label.replace(emoji, ascii);
+        }
+    }
+    Cow::Owned(label)
+}
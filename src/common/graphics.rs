@@ -0,0 +1,139 @@
+// Inline terminal graphics for the optional page image (`--images`)
+// Only protocols that take encoded image files directly are supported, so no
+// image decoding is needed: kitty (PNG only) and iTerm2 (any format it reads).
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use crossterm::{cursor::MoveTo, queue, terminal};
+use std::io::{self, Write};
+
+/// Height of the image in terminal rows; it sits in the top-right header corner
+const IMAGE_ROWS: u16 = 3;
+const MIN_IMAGE_COLS: u16 = 4;
+const MAX_IMAGE_COLS: u16 = 24;
+/// Kitty transmits base64 payloads in chunks of at most this many bytes
+const KITTY_CHUNK: usize = 4096;
+/// Image id used for kitty placements so the image can be removed again
+const KITTY_IMAGE_ID: u32 = 4242;
+const PNG_MAGIC: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Protocol {
+    Kitty,
+    ITerm2,
+}
+
+impl Protocol {
+    /// Guess the graphics protocol from the environment, `None` when unsupported
+    pub fn detect() -> Option<Self> {
+        let var = |name: &str| std::env::var(name).unwrap_or_default();
+
+        if std::env::var_os("KITTY_WINDOW_ID").is_some() || var("TERM").contains("kitty") {
+            Some(Self::Kitty)
+        } else if matches!(var("TERM_PROGRAM").as_str(), "iTerm.app" | "WezTerm") {
+            Some(Self::ITerm2)
+        } else {
+            None
+        }
+    }
+
+    /// Whether this protocol can draw `bytes` without decoding them first
+    pub fn supports(&self, bytes: &[u8]) -> bool {
+        match self {
+            Self::Kitty => bytes.starts_with(PNG_MAGIC),
+            Self::ITerm2 => true,
+        }
+    }
+}
+
+/// Draws one image in the top-right corner of the screen and removes it again
+pub struct ImageOverlay {
+    protocol: Protocol,
+    shown: bool,
+}
+
+impl ImageOverlay {
+    pub fn new(protocol: Protocol) -> Self {
+        Self {
+            protocol,
+            shown: false,
+        }
+    }
+
+    pub fn protocol(&self) -> Protocol {
+        self.protocol
+    }
+
+    pub fn is_shown(&self) -> bool {
+        self.shown
+    }
+
+    pub fn show(&mut self, bytes: &[u8]) -> io::Result<()> {
+        let (width, _) = terminal::size()?;
+        let cols = image_cols(bytes);
+        let x = width.saturating_sub(cols + 2);
+        let data = STANDARD.encode(bytes);
+
+        let mut out = io::stdout();
+        queue!(out, MoveTo(x, 0))?;
+        match self.protocol {
+            Protocol::Kitty => {
+                let chunks: Vec<&[u8]> = data.as_bytes().chunks(KITTY_CHUNK).collect();
+                for (i, chunk) in chunks.iter().enumerate() {
+                    let more = u8::from(i + 1 < chunks.len());
+                    let chunk = std::str::from_utf8(chunk).unwrap_or_default();
+                    if i == 0 {
+                        write!(
+                            out,
+                            "\x1b_Gf=100,a=T,i={},c={},r={},C=1,q=2,m={};{}\x1b\\",
+                            KITTY_IMAGE_ID, cols, IMAGE_ROWS, more, chunk
+                        )?;
+                    } else {
+                        write!(out, "\x1b_Gm={};{}\x1b\\", more, chunk)?;
+                    }
+                }
+            }
+            Protocol::ITerm2 => write!(
+                out,
+                "\x1b]1337;File=inline=1;size={};width={};height={};preserveAspectRatio=1:{}\x07",
+                bytes.len(),
+                cols,
+                IMAGE_ROWS,
+                data
+            )?,
+        }
+        out.flush()?;
+
+        self.shown = true;
+        Ok(())
+    }
+
+    /// Remove the image. iTerm2 images live in the cells themselves, so the
+    /// caller must also redraw the whole screen.
+    pub fn clear(&mut self) -> io::Result<()> {
+        if self.shown && self.protocol == Protocol::Kitty {
+            let mut out = io::stdout();
+            write!(out, "\x1b_Ga=d,d=I,i={},q=2\x1b\\", KITTY_IMAGE_ID)?;
+            out.flush()?;
+        }
+        self.shown = false;
+        Ok(())
+    }
+}
+
+/// Width in columns that keeps the image's aspect ratio at `IMAGE_ROWS` rows,
+/// assuming cells are about twice as tall as wide. PNG sizes come from the
+/// IHDR chunk; other formats get a 16:9 guess.
+fn image_cols(bytes: &[u8]) -> u16 {
+    let size = |at: usize| {
+        bytes
+            .get(at..at + 4)
+            .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    };
+    let (width, height) = match (bytes.starts_with(PNG_MAGIC), size(16), size(20)) {
+        (true, Some(w), Some(h)) if h > 0 => (w, h),
+        _ => (16, 9),
+    };
+
+    let cols = u64::from(IMAGE_ROWS) * 2 * u64::from(width) / u64::from(height);
+    cols.clamp(u64::from(MIN_IMAGE_COLS), u64::from(MAX_IMAGE_COLS)) as u16
+}
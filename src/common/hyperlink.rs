@@ -0,0 +1,196 @@
+// OSC 8 hyperlinks, so links can be ctrl-clicked open in the real browser
+// ratatui has no notion of hyperlinks, so once a frame is drawn the cells of
+// each link are printed again between the OSC 8 open and close sequences
+
+use super::ui::LinkLine;
+use ratatui::{
+    backend::Backend,
+    buffer::{Buffer, Cell},
+    layout::Rect,
+    Frame, Terminal,
+};
+use std::io::{self, Write};
+use textwrap::fill;
+use unicode_width::UnicodeWidthStr;
+
+/// Characters that end a URL written out in the text
+const URL_END: &[char] = &['<', '>', '"', '\'', '`', '│', '┃', '|'];
+/// Punctuation more likely closing the sentence than part of the URL
+const URL_TRAILING: &[char] = &['.', ',', ';', ':', '!', '?', ')', ']', '*', '…'];
+
+/// A part of the screen that opens `url` when clicked
+#[derive(Debug, Clone)]
+pub struct Hyperlink {
+    pub area: Rect,
+    pub url: String,
+}
+
+/// The cells of one link row, copied out of a drawn frame
+pub struct LinkCells {
+    url: String,
+    cells: Vec<(u16, u16, Cell)>,
+}
+
+/// Whether the terminal is likely to understand OSC 8. The Linux console and
+/// dumb terminals print the sequences as text; everything else ignores them
+/// at worst.
+pub fn supported() -> bool {
+    !matches!(
+        std::env::var("TERM").as_deref(),
+        Ok("linux") | Ok("dumb") | Ok("")
+    )
+}
+
+/// Links for the items of a links list drawn in `inner`, starting at its top.
+/// Each item is `label(line)` wrapped at `wrap_width` columns, as the theme drew it.
+pub fn list_links(
+    inner: Rect,
+    lines: &[LinkLine],
+    wrap_width: usize,
+    label: impl Fn(&LinkLine) -> String,
+) -> Vec<Hyperlink> {
+    let mut links = Vec::new();
+    let mut y = inner.y;
+    for line in lines {
+        let height = fill(&label(line), wrap_width).lines().count().max(1) as u16;
+        if y + height > inner.bottom() {
+            break;
+        }
+        if let Some(url) = &line.url {
+            links.push(Hyperlink {
+                area: Rect::new(inner.x, y, inner.width, height),
+                url: url.clone(),
+            });
+        }
+        y += height;
+    }
+    links
+}
+
+/// Draw a frame with `render`, which returns the links it drew, then make
+/// them and every URL written out on screen clickable when `enabled`.
+/// Returns the links drawn, for finding the one under the mouse.
+pub fn draw<B: Backend + Write>(
+    terminal: &mut Terminal<B>,
+    enabled: bool,
+    render: impl FnOnce(&mut Frame) -> Vec<Hyperlink>,
+) -> io::Result<Vec<Hyperlink>> {
+    let mut hyperlinks = Vec::new();
+    let frame = terminal.draw(|f| hyperlinks = render(f))?;
+    if enabled {
+        let links = collect(frame.buffer, &hyperlinks);
+        write(terminal.backend_mut(), &links)?;
+    }
+    Ok(hyperlinks)
+}
+
+/// The link of `links` drawn over the cell at `column`, `row`
+pub fn at(links: &[Hyperlink], column: u16, row: u16) -> Option<&Hyperlink> {
+    links.iter().find(|link| {
+        (link.area.left()..link.area.right()).contains(&column)
+            && (link.area.top()..link.area.bottom()).contains(&row)
+    })
+}
+
+/// The cells of `links` in `buffer`, row by row without surrounding blanks,
+/// plus every http(s) URL written out anywhere on screen
+fn collect(buffer: &Buffer, links: &[Hyperlink]) -> Vec<LinkCells> {
+    let mut collected = Vec::new();
+    for y in buffer.area.top()..buffer.area.bottom() {
+        let row = visible_cells(buffer, buffer.area.x, buffer.area.right(), y);
+        collected.extend(written_urls(&row));
+    }
+    for link in links {
+        let area = link.area.intersection(buffer.area);
+        for y in area.top()..area.bottom() {
+            let cells = trim_blank(visible_cells(buffer, area.left(), area.right(), y));
+            if !cells.is_empty() {
+                collected.push(LinkCells {
+                    url: link.url.clone(),
+                    cells,
+                });
+            }
+        }
+    }
+    collected
+}
+
+/// Print `links` again as OSC 8 hyperlinks. The cells are already on screen,
+/// so nothing changes but the terminal learning where each link points.
+fn write<B: Backend + Write>(backend: &mut B, links: &[LinkCells]) -> io::Result<()> {
+    for link in links {
+        write!(backend, "\x1b]8;;{}\x1b\\", link.url)?;
+        backend.draw(link.cells.iter().map(|(x, y, cell)| (*x, *y, cell)))?;
+        write!(backend, "\x1b]8;;\x1b\\")?;
+    }
+    Backend::flush(backend)
+}
+
+/// Cells of row `y` from `left` to `right`, leaving out those covered by the
+/// second half of a wide character
+fn visible_cells(buffer: &Buffer, left: u16, right: u16, y: u16) -> Vec<(u16, u16, Cell)> {
+    let mut cells = Vec::new();
+    let mut hidden = 0;
+    for x in left..right {
+        if hidden > 0 {
+            hidden -= 1;
+            continue;
+        }
+        let cell = buffer.get(x, y);
+        hidden = cell.symbol().width().saturating_sub(1);
+        cells.push((x, y, cell.clone()));
+    }
+    cells
+}
+
+fn trim_blank(mut cells: Vec<(u16, u16, Cell)>) -> Vec<(u16, u16, Cell)> {
+    let is_blank = |(_, _, cell): &(u16, u16, Cell)| cell.symbol().trim().is_empty();
+    while cells.last().is_some_and(is_blank) {
+        cells.pop();
+    }
+    let leading = cells.iter().take_while(|cell| is_blank(cell)).count();
+    cells.drain(..leading);
+    cells
+}
+
+/// Runs of `row` that spell out an http(s) URL
+fn written_urls(row: &[(u16, u16, Cell)]) -> Vec<LinkCells> {
+    let mut urls = Vec::new();
+    let mut start = 0;
+    while start < row.len() {
+        let rest: String = row[start..]
+            .iter()
+            .take(8)
+            .map(|(_, _, cell)| cell.symbol())
+            .collect();
+        if !rest.starts_with("https://") && !rest.starts_with("http://") {
+            start += 1;
+            continue;
+        }
+
+        let mut end = start;
+        while end < row.len() {
+            let symbol = row[end].2.symbol();
+            if symbol.trim().is_empty() || symbol.contains(URL_END) {
+                break;
+            }
+            end += 1;
+        }
+        while end > start && row[end - 1].2.symbol().ends_with(URL_TRAILING) {
+            end -= 1;
+        }
+
+        let url: String = row[start..end]
+            .iter()
+            .map(|(_, _, cell)| cell.symbol())
+            .collect();
+        if url::Url::parse(&url).is_ok_and(|parsed| parsed.host_str().is_some()) {
+            urls.push(LinkCells {
+                url,
+                cells: row[start..end].to_vec(),
+            });
+        }
+        start = end.max(start + 1);
+    }
+    urls
+}
@@ -0,0 +1,244 @@
+// Shared key bindings - every UI maps keys to actions the same way
+// Themes only differ in how they render, not in how they are driven
+
+use crate::ui::{BrowserState, CursorMove, UserAction};
+use anyhow::Result;
+use crossterm::event::{
+    self, Event, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
+use std::time::{Duration, Instant};
+
+/// Reads terminal events and turns them into actions for the current state.
+/// Scroll keys already queued behind the first one (a held arrow key) are
+/// merged into one `ScrollBy`, so a burst costs one render instead of one per
+/// key repeat.
+#[derive(Default)]
+pub struct InputReader {
+    /// Event read while draining a burst that was not part of it
+    pending: Option<Event>,
+}
+
+impl InputReader {
+    /// Wait for the next action, or `UserAction::Tick` once `timeout` passes
+    /// without one. `None` waits as long as it takes.
+    pub fn next_action(
+        &mut self,
+        state: &BrowserState,
+        timeout: Option<Duration>,
+    ) -> Result<UserAction> {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        loop {
+            let event = match self.pending.take() {
+                Some(event) => event,
+                None => {
+                    if let Some(deadline) = deadline {
+                        let left = deadline.saturating_duration_since(Instant::now());
+                        if !event::poll(left)? {
+                            return Ok(UserAction::Tick);
+                        }
+                    }
+                    event::read()?
+                }
+            };
+            match event {
+                Event::Key(key) => {
+                    if let Some(action) = map_key(state, key) {
+                        return self.coalesce_scrolls(state, action);
+                    }
+                }
+                Event::Mouse(mouse) => {
+                    if let Some(action) = map_mouse(state, mouse) {
+                        return Ok(action);
+                    }
+                }
+                // Layout, max_scroll and links_scroll are recomputed on the next render
+                Event::Resize(_, _) => return Ok(UserAction::Redraw),
+                _ => {}
+            }
+        }
+    }
+
+    /// Merge scroll actions that are already waiting in the queue with `first`
+    fn coalesce_scrolls(&mut self, state: &BrowserState, first: UserAction) -> Result<UserAction> {
+        let Some(mut delta) = scroll_delta(&first) else {
+            return Ok(first);
+        };
+
+        let mut merged = 1;
+        while event::poll(Duration::ZERO)? {
+            let event = event::read()?;
+            let step = match &event {
+                Event::Key(key) => map_key(state, *key).as_ref().and_then(scroll_delta),
+                _ => None,
+            };
+            match step {
+                Some(step) => {
+                    delta += step;
+                    merged += 1;
+                }
+                None => {
+                    self.pending = Some(event);
+                    break;
+                }
+            }
+        }
+
+        Ok(if merged == 1 {
+            first
+        } else {
+            UserAction::ScrollBy(delta)
+        })
+    }
+}
+
+fn scroll_delta(action: &UserAction) -> Option<i32> {
+    match action {
+        UserAction::ScrollUp => Some(-1),
+        UserAction::ScrollDown => Some(1),
+        _ => None,
+    }
+}
+
+/// Map a mouse event to a browser action. Only pages react to the mouse:
+/// pointing at a link shows where it goes and a left click follows it.
+pub fn map_mouse(state: &BrowserState, mouse: MouseEvent) -> Option<UserAction> {
+    if !matches!(state, BrowserState::Page { .. }) {
+        return None;
+    }
+    let (column, row) = (mouse.column, mouse.row);
+    match mouse.kind {
+        MouseEventKind::Moved => Some(UserAction::Hover { column, row }),
+        MouseEventKind::Down(MouseButton::Left) => Some(UserAction::Click { column, row }),
+        _ => None,
+    }
+}
+
+/// Map a key press to a browser action for the given state.
+/// Returns `None` for keys that have no meaning in that state.
+pub fn map_key(state: &BrowserState, key: KeyEvent) -> Option<UserAction> {
+    match state {
+        BrowserState::URLInput { input } | BrowserState::Prompt { input, .. } => match key.code {
+            KeyCode::Esc => Some(UserAction::CancelInput),
+            KeyCode::Enter if matches!(state, BrowserState::Prompt { .. }) => {
+                Some(UserAction::SubmitPrompt(input.text.clone()))
+            }
+            KeyCode::Enter => Some(UserAction::ConfirmInput(input.text.clone())),
+            KeyCode::Backspace => Some(UserAction::Backspace),
+            KeyCode::Delete => Some(UserAction::DeleteChar),
+            KeyCode::Left => Some(UserAction::MoveCursor(CursorMove::Left)),
+            KeyCode::Right => Some(UserAction::MoveCursor(CursorMove::Right)),
+            KeyCode::Home => Some(UserAction::MoveCursor(CursorMove::Home)),
+            KeyCode::End => Some(UserAction::MoveCursor(CursorMove::End)),
+            KeyCode::Char(c) => Some(UserAction::InputChar(c)),
+            _ => None,
+        },
+        BrowserState::History { .. } => Some(UserAction::GoBack),
+        BrowserState::Bookmarks { .. } => match key.code {
+            KeyCode::Esc => Some(UserAction::CancelInput),
+            KeyCode::Up => Some(UserAction::SelectPrevBookmark),
+            KeyCode::Down => Some(UserAction::SelectNextBookmark),
+            KeyCode::Enter => Some(UserAction::OpenBookmark),
+            KeyCode::Delete => Some(UserAction::RemoveBookmark),
+            KeyCode::Backspace => Some(UserAction::Backspace),
+            KeyCode::Char(c) => Some(UserAction::InputChar(c)),
+            _ => None,
+        },
+        BrowserState::URLSuggestions { .. } => match key.code {
+            KeyCode::Esc => Some(UserAction::CancelInput),
+            KeyCode::Char('q') => Some(UserAction::Quit),
+            KeyCode::Up => Some(UserAction::SelectPrevSuggestion),
+            KeyCode::Down => Some(UserAction::SelectNextSuggestion),
+            KeyCode::Enter => Some(UserAction::ConfirmSuggestion),
+            _ => None,
+        },
+        BrowserState::Error { .. } => Some(UserAction::DismissError),
+        BrowserState::Info { .. }
+        | BrowserState::Stats { .. }
+        | BrowserState::Extraction { .. } => Some(UserAction::CancelInput),
+        BrowserState::Confirm { .. } => match key.code {
+            KeyCode::Char('y') | KeyCode::Char('Y') => Some(UserAction::Confirm),
+            _ => Some(UserAction::CancelInput),
+        },
+        BrowserState::Settings { .. } => match key.code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char(',') => Some(UserAction::CancelInput),
+            KeyCode::Up => Some(UserAction::SelectPrevSetting),
+            KeyCode::Down => Some(UserAction::SelectNextSetting),
+            KeyCode::Enter | KeyCode::Right | KeyCode::Char(' ') => {
+                Some(UserAction::NextSettingValue)
+            }
+            KeyCode::Left => Some(UserAction::PrevSettingValue),
+            KeyCode::Char('w') => Some(UserAction::SaveSettings),
+            _ => None,
+        },
+        BrowserState::Compare { .. } => match key.code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('C') => Some(UserAction::CancelInput),
+            KeyCode::Tab | KeyCode::Left | KeyCode::Right => Some(UserAction::SwitchPane),
+            KeyCode::Up => Some(UserAction::ScrollUp),
+            KeyCode::Down => Some(UserAction::ScrollDown),
+            _ => None,
+        },
+        BrowserState::Page {
+            link_find: Some(_), ..
+        } => match key.code {
+            KeyCode::Esc => Some(UserAction::CancelInput),
+            KeyCode::Enter => Some(UserAction::FollowSelectedLink),
+            KeyCode::Backspace => Some(UserAction::Backspace),
+            KeyCode::Up => Some(UserAction::SelectPrevLink),
+            KeyCode::Down => Some(UserAction::SelectNextLink),
+            KeyCode::Char(c) => Some(UserAction::InputChar(c)),
+            _ => None,
+        },
+        _ => match key.code {
+            KeyCode::Char('q') => Some(UserAction::Quit),
+            KeyCode::Char('b') => Some(UserAction::GoBack),
+            KeyCode::Char('p') => Some(UserAction::PeekLink),
+            KeyCode::Char('f') => Some(UserAction::GoForward),
+            KeyCode::Char('T') => Some(UserAction::ReopenLast),
+            KeyCode::Char('h') => Some(UserAction::ShowHistory),
+            KeyCode::Char('a') => Some(UserAction::AddBookmark),
+            KeyCode::Char('B') => Some(UserAction::ShowBookmarks),
+            KeyCode::Char('g') => Some(UserAction::EnterUrl),
+            KeyCode::Char('G') => Some(UserAction::EditUrl),
+            KeyCode::Char('r') => Some(UserAction::Refresh),
+            KeyCode::Char('R') => Some(UserAction::Regenerate),
+            KeyCode::Char('I') => Some(UserAction::RegenerateWithInstruction),
+            KeyCode::Char('D') => Some(UserAction::ToggleDiff),
+            KeyCode::Char('Q') => Some(UserAction::ToggleQuickLook),
+            KeyCode::Char('O') => Some(UserAction::ToggleOutline),
+            KeyCode::Char('m') => Some(UserAction::ToggleRawSummary),
+            KeyCode::Char('K') => Some(UserAction::OpenCanonical),
+            KeyCode::Char('v') => Some(UserAction::ToggleExpanded),
+            KeyCode::Char('S') => Some(UserAction::ShowSitemap),
+            KeyCode::Char('W') => Some(UserAction::StartCrawl),
+            KeyCode::Esc => Some(UserAction::StopCrawl),
+            KeyCode::Char('e') => Some(UserAction::ExportPage),
+            KeyCode::Char('E') => Some(UserAction::OpenInEditor),
+            KeyCode::Char('M') => Some(UserAction::CopyMarkdownLink),
+            KeyCode::Char('y') => Some(UserAction::YankLinks),
+            KeyCode::Char('[') => Some(UserAction::PrevSection),
+            KeyCode::Char(']') => Some(UserAction::NextSection),
+            KeyCode::Char('c') => Some(UserAction::CopySection),
+            KeyCode::Char('A') => Some(UserAction::CopySectionLink),
+            KeyCode::Char('H') => Some(UserAction::ToggleLinkGroups),
+            KeyCode::Char('C') => Some(UserAction::CompareSummaries),
+            KeyCode::Char(',') => Some(UserAction::OpenSettings),
+            KeyCode::Char('i') => Some(UserAction::ShowInfo),
+            KeyCode::Char('x') => Some(UserAction::ShowStats),
+            KeyCode::Char('\'') => Some(UserAction::FindLink),
+            KeyCode::Up if key.modifiers.contains(KeyModifiers::SHIFT) => {
+                Some(UserAction::SelectPrevLink)
+            }
+            KeyCode::Down if key.modifiers.contains(KeyModifiers::SHIFT) => {
+                Some(UserAction::SelectNextLink)
+            }
+            KeyCode::Up => Some(UserAction::ScrollUp),
+            KeyCode::Down => Some(UserAction::ScrollDown),
+            KeyCode::Enter => Some(UserAction::FollowSelectedLink),
+            KeyCode::Char(c) if c.is_ascii_digit() => match c.to_digit(10) {
+                Some(digit) if digit > 0 => Some(UserAction::FollowLink(digit as usize)),
+                _ => None,
+            },
+            _ => None,
+        },
+    }
+}
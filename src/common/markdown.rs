@@ -1,6 +1,8 @@
 // Shared markdown parsing logic - content should be identical across all UIs
 // Only styling/colors should differ between UI implementations
 
+use crate::common::diff::{ADDED_START, CHANGE_END, REMOVED_START};
+use crate::common::text::{display_width, take_width};
 use ratatui::{
     style::Style,
     text::{Line, Span},
@@ -18,6 +20,16 @@ pub enum MarkdownElement {
     Code(String),
     Normal(String),
     Empty,
+    /// Checkbox of a task-list item; only used to look up its style
+    TaskMarker(bool),
+    /// Term column of a definition line; only used to look up its style
+    DefinitionTerm,
+    /// Words added since the previous summary; only used to look up the
+    /// style patched over theirs
+    DiffAdded,
+    /// Words removed since the previous summary; only used to look up the
+    /// style patched over theirs
+    DiffRemoved,
 }
 
 #[derive(Debug, Clone)]
@@ -34,14 +46,84 @@ pub enum LineType {
     Header3,
     Header4,
     Bullet,
+    /// `- [ ]` / `- [x]` list item, `true` when checked
+    Task(bool),
+    /// `**Term:** description` line; the term is the prefix, padded so the
+    /// descriptions of consecutive definitions line up
+    Definition,
     Normal,
 }
 
-pub fn parse_markdown_to_structured(markdown: &str, width: usize) -> Vec<ParsedLine> {
-    let mut parsed_lines = Vec::new();
+// Bullet markers cycle through these as lists nest deeper
+const BULLET_MARKERS: &[&str] = &["•", "◦", "▪"];
+const INDENT_PER_LEVEL: usize = 2;
+const TASK_OPEN: &str = "☐";
+const TASK_DONE: &str = "☑";
+/// Longer bold openers are treated as emphasis, not as a term
+const MAX_DEFINITION_TERM: usize = 30;
+
+/// Lines of `markdown` wrapped at `width`. With `line_spacing`, every
+/// paragraph, header and list item is followed by a blank line, for airier
+/// reading in the terminal.
+pub fn parse_markdown_to_structured(
+    markdown: &str,
+    width: usize,
+    line_spacing: bool,
+) -> Vec<ParsedLine> {
+    parse_with_sources(markdown, width, line_spacing).0
+}
+
+/// Headers of `markdown` wrapped at `width`, top to bottom: the rendered line
+/// each starts on and its line index in the markdown
+pub fn header_lines(markdown: &str, width: usize, line_spacing: bool) -> Vec<(usize, usize)> {
+    let (parsed_lines, sources) = parse_with_sources(markdown, width, line_spacing);
+    let mut headers: Vec<(usize, usize)> = Vec::new();
+    for (index, (line, source)) in parsed_lines.iter().zip(sources).enumerate() {
+        let is_header = matches!(
+            line.line_type,
+            LineType::Header1 | LineType::Header2 | LineType::Header3 | LineType::Header4
+        );
+        // A wrapped header takes several rendered lines from one markdown line
+        if is_header && headers.last().map(|&(_, last)| last) != Some(source) {
+            headers.push((index, source));
+        }
+    }
+    headers
+}
 
-    for line in markdown.lines() {
-        let line = line.trim();
+/// Parsed lines, and for each the index of the markdown line it comes from
+fn parse_with_sources(
+    markdown: &str,
+    width: usize,
+    line_spacing: bool,
+) -> (Vec<ParsedLine>, Vec<usize>) {
+    let raw_lines: Vec<&str> = markdown.lines().collect();
+    let mut parsed_lines = Vec::new();
+    let mut sources = Vec::new();
+    // Indentation of each currently open list level, outermost first
+    let mut list_indents: Vec<usize> = Vec::new();
+    let term_widths = definition_term_widths(markdown);
+    // Inside a ``` fence, where lines are code shown as they are
+    let mut in_code = false;
+
+    for (source, (raw_line, term_width)) in markdown.lines().zip(term_widths).enumerate() {
+        if raw_line.trim_start().starts_with("```") {
+            in_code = !in_code;
+            continue;
+        }
+        if in_code {
+            for chunk in code_chunks(raw_line, width) {
+                parsed_lines.push(ParsedLine {
+                    elements: vec![MarkdownElement::Code(chunk)],
+                    prefix: String::new(),
+                    line_type: LineType::Normal,
+                });
+            }
+            sources.resize(parsed_lines.len(), source);
+            continue;
+        }
+        let indent = leading_indent(raw_line);
+        let line = raw_line.trim();
 
         if line.is_empty() {
             parsed_lines.push(ParsedLine {
@@ -49,10 +131,36 @@ pub fn parse_markdown_to_structured(markdown: &str, width: usize) -> Vec<ParsedL
                 prefix: String::new(),
                 line_type: LineType::Normal,
             });
+            sources.push(source);
             continue;
         }
 
         let (prefix, text, line_type) = parse_markdown_line_structure(line);
+        let (prefix, text, line_type) = match line_type {
+            LineType::Bullet => {
+                let depth = list_depth(&mut list_indents, indent);
+                match task_item(text) {
+                    Some((done, rest)) => (task_prefix(depth, done), rest, LineType::Task(done)),
+                    None => (bullet_prefix(depth), text, LineType::Bullet),
+                }
+            }
+            LineType::Normal if term_width > 0 => {
+                list_indents.clear();
+                match definition_item(text) {
+                    Some((term, description)) => (
+                        definition_prefix(term, term_width),
+                        description,
+                        LineType::Definition,
+                    ),
+                    None => (prefix, text, LineType::Normal),
+                }
+            }
+            other => {
+                list_indents.clear();
+                (prefix, text, other)
+            }
+        };
+        let prefix_width = display_width(&prefix);
 
         // Parse inline formatting within the text
         let inline_elements = parse_inline_elements(text);
@@ -67,9 +175,11 @@ pub fn parse_markdown_to_structured(markdown: &str, width: usize) -> Vec<ParsedL
             .collect::<Vec<_>>()
             .join("");
 
-        if combined_text.len() > width && !prefix.is_empty() {
+        let text_width = display_width(&combined_text);
+        if text_width > width && !prefix.is_empty() {
             // For wrapped lines with prefixes (bullets), keep the prefix only on first line
-            let wrapped = fill(&combined_text, width - prefix.len());
+            // and indent continuation lines to line up with the bullet's text
+            let wrapped = fill(&combined_text, width.saturating_sub(prefix_width).max(1));
             let mut first = true;
             for wrapped_line in wrapped.lines() {
                 parsed_lines.push(ParsedLine {
@@ -77,13 +187,13 @@ pub fn parse_markdown_to_structured(markdown: &str, width: usize) -> Vec<ParsedL
                     prefix: if first {
                         prefix.clone()
                     } else {
-                        " ".repeat(prefix.len())
+                        " ".repeat(prefix_width)
                     },
                     line_type: line_type.clone(),
                 });
                 first = false;
             }
-        } else if combined_text.len() > width {
+        } else if text_width > width {
             // For wrapped lines without prefixes
             let wrapped = fill(&combined_text, width);
             for wrapped_line in wrapped.lines() {
@@ -100,9 +210,21 @@ pub fn parse_markdown_to_structured(markdown: &str, width: usize) -> Vec<ParsedL
                 line_type: line_type.clone(),
             });
         }
+        // Blank lines already in the markdown are not doubled
+        let next_is_blank = raw_lines
+            .get(source + 1)
+            .is_none_or(|next| next.trim().is_empty());
+        if line_spacing && !next_is_blank {
+            parsed_lines.push(ParsedLine {
+                elements: vec![MarkdownElement::Empty],
+                prefix: String::new(),
+                line_type: LineType::Normal,
+            });
+        }
+        sources.resize(parsed_lines.len(), source);
     }
 
-    parsed_lines
+    (parsed_lines, sources)
 }
 
 fn parse_markdown_line_structure(line: &str) -> (String, &str, LineType) {
@@ -115,12 +237,140 @@ fn parse_markdown_line_structure(line: &str) -> (String, &str, LineType) {
     } else if let Some(text) = line.strip_prefix("# ") {
         (String::new(), text, LineType::Header1)
     } else if let Some(text) = line.strip_prefix("- ").or_else(|| line.strip_prefix("* ")) {
-        ("• ".to_string(), text, LineType::Bullet)
+        // The marker depends on nesting depth, which the caller fills in from the indentation
+        (String::new(), text, LineType::Bullet)
     } else {
         (String::new(), line, LineType::Normal)
     }
 }
 
+/// `line` cut into pieces at most `width` columns wide, so long code wraps
+/// without losing its indentation
+fn code_chunks(line: &str, width: usize) -> Vec<String> {
+    let mut chunks = vec![take_width(line, width.max(1)).to_string()];
+    let mut rest = &line[chunks[0].len()..];
+    while !rest.is_empty() {
+        let mut chunk = take_width(rest, width.max(1));
+        // A character wider than the whole line still has to go somewhere
+        if chunk.is_empty() {
+            chunk = &rest[..rest.chars().next().map_or(0, char::len_utf8)];
+        }
+        chunks.push(chunk.to_string());
+        rest = &rest[chunk.len()..];
+    }
+    chunks
+}
+
+/// Width of a line's leading whitespace, counting tabs as four columns
+fn leading_indent(line: &str) -> usize {
+    line.chars()
+        .take_while(|c| c.is_whitespace())
+        .map(|c| if c == '\t' { 4 } else { 1 })
+        .sum()
+}
+
+/// Work out a list item's nesting depth from its indentation relative to the
+/// items before it, so both 2- and 4-space nested lists map to one level per step
+fn list_depth(list_indents: &mut Vec<usize>, indent: usize) -> usize {
+    while list_indents.last().is_some_and(|&open| open > indent) {
+        list_indents.pop();
+    }
+    if list_indents.last() != Some(&indent) {
+        list_indents.push(indent);
+    }
+    list_indents.len() - 1
+}
+
+fn bullet_prefix(depth: usize) -> String {
+    format!(
+        "{}{} ",
+        " ".repeat(depth * INDENT_PER_LEVEL),
+        BULLET_MARKERS[depth % BULLET_MARKERS.len()]
+    )
+}
+
+/// Split a list item's text into its checkbox state and the rest, if it is a task
+fn task_item(text: &str) -> Option<(bool, &str)> {
+    if let Some(rest) = text.strip_prefix("[ ] ") {
+        Some((false, rest))
+    } else {
+        text.strip_prefix("[x] ")
+            .or_else(|| text.strip_prefix("[X] "))
+            .map(|rest| (true, rest))
+    }
+}
+
+fn task_prefix(depth: usize, done: bool) -> String {
+    format!(
+        "{}{} ",
+        " ".repeat(depth * INDENT_PER_LEVEL),
+        if done { TASK_DONE } else { TASK_OPEN }
+    )
+}
+
+/// Split a `**Term:** description` (or `**Term**: description`) line into
+/// its term and description. Conservative on purpose: the line must open with
+/// the bold term, which is short, has no sentence punctuation and is followed
+/// by a description, so ordinary bold sentences with a colon are left alone.
+fn definition_item(line: &str) -> Option<(&str, &str)> {
+    let rest = line.strip_prefix("**")?;
+    let end = rest.find("**")?;
+    let (bold, after) = (&rest[..end], &rest[end + 2..]);
+
+    let (term, description) = match bold.strip_suffix(':') {
+        Some(term) => (term, after.strip_prefix(char::is_whitespace)?),
+        None => (bold, after.strip_prefix(':')?),
+    };
+    let (term, description) = (term.trim(), description.trim());
+
+    let plausible_term = !term.is_empty()
+        && term.chars().count() <= MAX_DEFINITION_TERM
+        && !term.contains(['*', '.', '!', '?', ':']);
+    (plausible_term && !description.is_empty()).then_some((term, description))
+}
+
+/// Term column width for every line: lines that are definitions get the
+/// widest term of their run of consecutive definitions, all others 0
+fn definition_term_widths(markdown: &str) -> Vec<usize> {
+    let terms: Vec<Option<usize>> = markdown
+        .lines()
+        .map(|line| {
+            let line = line.trim();
+            // Only plain lines qualify; list items and headers keep their own layout
+            let (_, text, line_type) = parse_markdown_line_structure(line);
+            match line_type {
+                LineType::Normal => definition_item(text).map(|(term, _)| display_width(term)),
+                _ => None,
+            }
+        })
+        .collect();
+
+    let mut widths = vec![0; terms.len()];
+    let mut start = 0;
+    while start < terms.len() {
+        if terms[start].is_none() {
+            start += 1;
+            continue;
+        }
+        let end = start + terms[start..].iter().take_while(|t| t.is_some()).count();
+        let width = terms[start..end]
+            .iter()
+            .flatten()
+            .max()
+            .copied()
+            .unwrap_or(0);
+        widths[start..end].fill(width);
+        start = end;
+    }
+    widths
+}
+
+fn definition_prefix(term: &str, width: usize) -> String {
+    // Padded by hand: format! pads by chars, which misaligns wide characters
+    let padding = width.saturating_sub(display_width(term));
+    format!("{}:{} ", term, " ".repeat(padding))
+}
+
 fn parse_inline_elements(text: &str) -> Vec<MarkdownElement> {
     let mut elements = Vec::new();
     let mut current_text = String::new();
@@ -190,7 +440,7 @@ fn parse_inline_elements(text: &str) -> Vec<MarkdownElement> {
     elements
 }
 
-fn element_text(element: &MarkdownElement) -> &str {
+pub fn element_text(element: &MarkdownElement) -> &str {
     match element {
         MarkdownElement::Header1(text) => text,
         MarkdownElement::Header2(text) => text,
@@ -200,7 +450,11 @@ fn element_text(element: &MarkdownElement) -> &str {
         MarkdownElement::Italic(text) => text,
         MarkdownElement::Code(text) => text,
         MarkdownElement::Normal(text) => text,
-        MarkdownElement::Empty => "",
+        MarkdownElement::Empty
+        | MarkdownElement::TaskMarker(_)
+        | MarkdownElement::DefinitionTerm
+        | MarkdownElement::DiffAdded
+        | MarkdownElement::DiffRemoved => "",
     }
 }
 
@@ -210,6 +464,8 @@ where
     F: Fn(&MarkdownElement) -> Style,
 {
     let mut lines = Vec::new();
+    // Style of the diff run still open at the end of the previous line
+    let mut diff_run = None;
 
     for parsed_line in parsed_lines {
         if parsed_line.elements.len() == 1
@@ -221,11 +477,16 @@ where
 
         let mut spans = Vec::new();
 
-        // Add prefix if present
+        // Add prefix if present; task checkboxes get their own style
         if !parsed_line.prefix.is_empty() {
+            let prefix_element = match parsed_line.line_type {
+                LineType::Task(done) => MarkdownElement::TaskMarker(done),
+                LineType::Definition => MarkdownElement::DefinitionTerm,
+                _ => MarkdownElement::Normal(String::new()),
+            };
             spans.push(Span::styled(
                 parsed_line.prefix.clone(),
-                styler(&MarkdownElement::Normal(String::new())),
+                styler(&prefix_element),
             ));
         }
 
@@ -259,8 +520,276 @@ where
             }
         }
 
-        lines.push(Line::from(spans));
+        lines.push(Line::from(style_diff_runs(spans, &mut diff_run, &styler)));
     }
 
     lines
 }
+
+/// Drop the diff markers from `spans`, patching the text between them with
+/// the `DiffAdded`/`DiffRemoved` style. `open` carries a run across lines.
+fn style_diff_runs<F>(
+    spans: Vec<Span<'static>>,
+    open: &mut Option<Style>,
+    styler: &F,
+) -> Vec<Span<'static>>
+where
+    F: Fn(&MarkdownElement) -> Style,
+{
+    let is_marker = |c: char| matches!(c, ADDED_START | REMOVED_START | CHANGE_END);
+    if open.is_none() && !spans.iter().any(|span| span.content.contains(is_marker)) {
+        return spans;
+    }
+
+    let mut styled = Vec::new();
+    for span in spans {
+        let mut text = String::new();
+        for c in span.content.chars() {
+            if !is_marker(c) {
+                text.push(c);
+                continue;
+            }
+            if !text.is_empty() {
+                let style = open.map_or(span.style, |run| span.style.patch(run));
+                styled.push(Span::styled(std::mem::take(&mut text), style));
+            }
+            *open = match c {
+                ADDED_START => Some(styler(&MarkdownElement::DiffAdded)),
+                REMOVED_START => Some(styler(&MarkdownElement::DiffRemoved)),
+                _ => None,
+            };
+        }
+        if !text.is_empty() {
+            let style = open.map_or(span.style, |run| span.style.patch(run));
+            styled.push(Span::styled(text, style));
+        }
+    }
+    styled
+}
+
+/// Second output backend for the parsed markdown: an HTML fragment, used for
+/// exports. Parse with `usize::MAX` as the width so no line gets wrapped.
+pub fn render_structured_to_html(parsed_lines: &[ParsedLine]) -> String {
+    let mut html = String::new();
+    let mut open_lists = 0;
+    let mut in_paragraph = false;
+    let mut in_definitions = false;
+
+    for parsed_line in parsed_lines {
+        let is_text = matches!(parsed_line.line_type, LineType::Normal)
+            && !matches!(parsed_line.elements.as_slice(), [MarkdownElement::Empty]);
+        if in_paragraph && !is_text {
+            html.push_str("</p>\n");
+            in_paragraph = false;
+        }
+        let is_definition = matches!(parsed_line.line_type, LineType::Definition);
+        if in_definitions && !is_definition {
+            html.push_str("</dl>\n");
+            in_definitions = false;
+        }
+
+        match &parsed_line.line_type {
+            LineType::Bullet | LineType::Task(_) => {
+                let indent = parsed_line.prefix.len() - parsed_line.prefix.trim_start().len();
+                let depth = indent / INDENT_PER_LEVEL + 1;
+                if open_lists >= depth {
+                    close_lists(&mut html, &mut open_lists, depth);
+                    html.push_str("</li>\n");
+                }
+                while open_lists < depth {
+                    html.push_str("<ul>\n");
+                    open_lists += 1;
+                }
+                html.push_str("<li>");
+                if let LineType::Task(done) = parsed_line.line_type {
+                    html.push_str(if done { TASK_DONE } else { TASK_OPEN });
+                    html.push(' ');
+                }
+                push_inline_html(&mut html, &parsed_line.elements);
+            }
+            LineType::Definition => {
+                close_lists(&mut html, &mut open_lists, 0);
+                if !in_definitions {
+                    html.push_str("<dl>\n");
+                    in_definitions = true;
+                }
+                let term = parsed_line.prefix.trim_end().trim_end_matches(':');
+                html.push_str(&format!("<dt>{}</dt><dd>", escape_html(term)));
+                push_inline_html(&mut html, &parsed_line.elements);
+                html.push_str("</dd>\n");
+            }
+            LineType::Normal if !is_text => close_lists(&mut html, &mut open_lists, 0),
+            LineType::Normal => {
+                close_lists(&mut html, &mut open_lists, 0);
+                html.push_str(if in_paragraph { "\n" } else { "<p>" });
+                in_paragraph = true;
+                push_inline_html(&mut html, &parsed_line.elements);
+            }
+            header => {
+                close_lists(&mut html, &mut open_lists, 0);
+                let level = match header {
+                    LineType::Header1 => 1,
+                    LineType::Header2 => 2,
+                    LineType::Header3 => 3,
+                    _ => 4,
+                };
+                let text: String = parsed_line.elements.iter().map(element_text).collect();
+                html.push_str(&format!("<h{0}>{1}</h{0}>\n", level, escape_html(&text)));
+            }
+        }
+    }
+
+    if in_paragraph {
+        html.push_str("</p>\n");
+    }
+    if in_definitions {
+        html.push_str("</dl>\n");
+    }
+    close_lists(&mut html, &mut open_lists, 0);
+    html
+}
+
+/// Close nested lists until only `keep` remain open
+fn close_lists(html: &mut String, open_lists: &mut usize, keep: usize) {
+    while *open_lists > keep {
+        html.push_str("</li>\n</ul>\n");
+        *open_lists -= 1;
+    }
+}
+
+fn push_inline_html(html: &mut String, elements: &[MarkdownElement]) {
+    for element in elements {
+        let text = escape_html(element_text(element));
+        match element {
+            MarkdownElement::Bold(_) => html.push_str(&format!("<strong>{}</strong>", text)),
+            MarkdownElement::Italic(_) => html.push_str(&format!("<em>{}</em>", text)),
+            MarkdownElement::Code(_) => html.push_str(&format!("<code>{}</code>", text)),
+            _ => html.push_str(&text),
+        }
+    }
+}
+
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Prefix and text of each line of `markdown` wrapped wide enough not to wrap
+    fn lines(markdown: &str) -> Vec<(String, String)> {
+        parse_markdown_to_structured(markdown, 200, false)
+            .into_iter()
+            .map(|line| {
+                let text = line.elements.iter().map(element_text).collect();
+                (line.prefix, text)
+            })
+            .collect()
+    }
+
+    fn is_definition(line: &ParsedLine) -> bool {
+        matches!(line.line_type, LineType::Definition)
+    }
+
+    #[test]
+    fn definition_terms_are_padded_to_the_widest_of_their_run() {
+        assert_eq!(
+            lines("**Name:** Alice\n**Occupation**: Engineer\n\n**Age:** 30"),
+            [
+                ("Name:       ".to_string(), "Alice".to_string()),
+                ("Occupation: ".to_string(), "Engineer".to_string()),
+                (String::new(), String::new()),
+                ("Age: ".to_string(), "30".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn definition_terms_align_by_columns() {
+        let prefixes: Vec<String> = lines("**名前:** Alice\n**Job:** Pilot")
+            .into_iter()
+            .map(|(prefix, _)| prefix)
+            .collect();
+        assert_eq!(prefixes, ["名前: ", "Job:  "]);
+    }
+
+    #[test]
+    fn terms_longer_than_the_cutoff_stay_bold_text() {
+        let term = "t".repeat(MAX_DEFINITION_TERM);
+        let longer = "t".repeat(MAX_DEFINITION_TERM + 1);
+        let parsed = parse_markdown_to_structured(
+            &format!("**{}:** fits\n\n**{}:** too long", term, longer),
+            200,
+            false,
+        );
+        assert!(is_definition(&parsed[0]));
+        assert!(!is_definition(&parsed[2]));
+    }
+
+    #[test]
+    fn bold_sentences_with_a_colon_are_not_definitions() {
+        for line in [
+            "**Note: this matters.** Read on",
+            "**Important** is what it is",
+            "**Term:**",
+        ] {
+            let parsed = parse_markdown_to_structured(line, 200, false);
+            assert!(!is_definition(&parsed[0]), "{}", line);
+        }
+    }
+
+    #[test]
+    fn task_items_get_checkboxes() {
+        let parsed = parse_markdown_to_structured(
+            "- [ ] Write tests\n- [x] Ship **it**\n- [X] Done too\n- [] not a task",
+            200,
+            false,
+        );
+        let states: Vec<Option<bool>> = parsed
+            .iter()
+            .map(|line| match line.line_type {
+                LineType::Task(done) => Some(done),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(states, [Some(false), Some(true), Some(true), None]);
+        assert_eq!(
+            lines("- [ ] Write tests\n- [x] Ship **it**\n  - [ ] Nested"),
+            [
+                ("☐ ".to_string(), "Write tests".to_string()),
+                ("☑ ".to_string(), "Ship it".to_string()),
+                ("  ☐ ".to_string(), "Nested".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn two_level_lists_nest_with_their_own_markers() {
+        let expected = [
+            ("• ".to_string(), "Fruit".to_string()),
+            ("  ◦ ".to_string(), "Apple".to_string()),
+            ("  ◦ ".to_string(), "Pear".to_string()),
+            ("• ".to_string(), "Vegetables".to_string()),
+        ];
+        assert_eq!(
+            lines("- Fruit\n  - Apple\n  - Pear\n- Vegetables"),
+            expected
+        );
+        // Four-space indents nest one level too
+        assert_eq!(
+            lines("- Fruit\n    - Apple\n    - Pear\n* Vegetables"),
+            expected
+        );
+    }
+
+    #[test]
+    fn wrapped_nested_items_line_up_under_their_text() {
+        let parsed = parse_markdown_to_structured("- Top\n  - one two three four", 12, false);
+        let prefixes: Vec<&str> = parsed.iter().map(|line| line.prefix.as_str()).collect();
+        assert_eq!(prefixes, ["• ", "  ◦ ", "    ", "    "]);
+    }
+}
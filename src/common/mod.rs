@@ -1,5 +1,14 @@
 // Common utilities and shared modules
 // This package contains functionality shared across different components
 
+pub mod clipboard;
+pub mod diff;
+pub mod editor;
+pub mod emoji;
+pub mod graphics;
+pub mod hyperlink;
+pub mod keys;
 pub mod markdown;
+pub mod opener;
+pub mod text;
 pub mod ui;
@@ -0,0 +1,31 @@
+// Handing URLs to the system's default browser, for pages bbow does not read
+// itself. The browser is started in the background and the TUI carries on.
+
+use anyhow::{anyhow, Context, Result};
+use std::process::{Command, Stdio};
+
+/// Open `url` with the platform's opener (`open`, `xdg-open` or `start`)
+pub fn open_url(url: &str) -> Result<()> {
+    let mut command = if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else if cfg!(windows) {
+        let mut command = Command::new("cmd");
+        // The empty argument is the window title `start` would take the URL for
+        command.args(["/C", "start", ""]);
+        command
+    } else {
+        Command::new("xdg-open")
+    };
+    let program = command.get_program().to_string_lossy().into_owned();
+    let status = command
+        .arg(url)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .with_context(|| format!("Could not start {}", program))?;
+    if !status.success() {
+        return Err(anyhow!("{} exited with {}", program, status));
+    }
+    Ok(())
+}
@@ -0,0 +1,166 @@
+// Text helpers shared by the browser and the UI implementations
+
+use chrono::{DateTime, Utc};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// Columns `text` takes in a terminal; CJK characters and most emoji take two
+pub fn display_width(text: &str) -> usize {
+    text.width()
+}
+
+/// The longest start of `text` at most `max_width` columns wide, cut at a
+/// grapheme boundary
+pub fn take_width(text: &str, max_width: usize) -> &str {
+    take_measured(text, max_width, UnicodeWidthStr::width)
+}
+
+/// Truncate `text` to at most `max_width` columns, cutting at the last word
+/// boundary that fits and appending an ellipsis when anything was removed.
+/// A single word wider than the limit is cut at a grapheme boundary instead.
+/// For fitting text on screen; see `truncate_chars_at_word_boundary` for
+/// limits given in characters.
+pub fn truncate_at_word_boundary(text: &str, max_width: usize) -> String {
+    truncate_measured(text, max_width, UnicodeWidthStr::width)
+}
+
+/// Like `truncate_at_word_boundary`, but to at most `max_chars` characters
+/// whatever their width, for text cut to a length rather than a screen
+pub fn truncate_chars_at_word_boundary(text: &str, max_chars: usize) -> String {
+    truncate_measured(text, max_chars, |text| text.chars().count())
+}
+
+/// The longest start of `text` whose `measure` is at most `max`, cut at a
+/// grapheme boundary
+fn take_measured(text: &str, max: usize, measure: fn(&str) -> usize) -> &str {
+    let mut total = 0;
+    for (index, grapheme) in text.grapheme_indices(true) {
+        total += measure(grapheme);
+        if total > max {
+            return &text[..index];
+        }
+    }
+    text
+}
+
+fn truncate_measured(text: &str, max: usize, measure: fn(&str) -> usize) -> String {
+    if measure(text) <= max {
+        return text.to_string();
+    }
+
+    let mut end = 0;
+    let mut total = 0;
+    for (index, word) in text.split_word_bound_indices() {
+        if total + measure(word) > max {
+            break;
+        }
+        total += measure(word);
+        end = index + word.len();
+    }
+
+    if end == 0 {
+        end = take_measured(text, max, measure).len();
+    }
+
+    format!("{}…", text[..end].trim_end())
+}
+
+/// How long ago `date` was, in the largest whole unit: "today", "yesterday",
+/// "3 days ago", "2 months ago", "4 years ago". Future dates count as today.
+pub fn relative_age(date: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let days = (now - date).num_days();
+    let (count, unit) = match days {
+        i64::MIN..=0 => return "today".to_string(),
+        1 => return "yesterday".to_string(),
+        2..=29 => (days, "day"),
+        30..=364 => (days / 30, "month"),
+        _ => (days / 365, "year"),
+    };
+
+    if count == 1 {
+        format!("1 {} ago", unit)
+    } else {
+        format!("{} {}s ago", count, unit)
+    }
+}
+
+/// Like `relative_age`, but with minute and hour resolution within the last day:
+/// "just now", "5m ago", "2h ago", then "yesterday", "3 days ago" and so on.
+pub fn relative_time(time: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let elapsed = now - time;
+    if elapsed.num_minutes() < 1 {
+        "just now".to_string()
+    } else if elapsed.num_hours() < 1 {
+        format!("{}m ago", elapsed.num_minutes())
+    } else if elapsed.num_days() < 1 {
+        format!("{}h ago", elapsed.num_hours())
+    } else {
+        relative_age(time, now)
+    }
+}
+
+/// Byte count in the largest fitting binary unit: "512 B", "3.4 KiB", "1.2 MiB"
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wide_characters_take_two_columns() {
+        assert_eq!(display_width("abc"), 3);
+        assert_eq!(display_width("日本語"), 6);
+        assert_eq!(display_width("한국어 text"), 11);
+        assert_eq!(display_width("😀👍"), 4);
+        assert_eq!(display_width("e\u{301}"), 1);
+    }
+
+    #[test]
+    fn take_width_never_splits_a_wide_character() {
+        assert_eq!(take_width("日本語", 4), "日本");
+        assert_eq!(take_width("日本語", 5), "日本");
+        assert_eq!(take_width("日本語", 1), "");
+        assert_eq!(take_width("a😀b", 2), "a");
+        assert_eq!(take_width("a😀b", 3), "a😀");
+        assert_eq!(take_width("日本語", 6), "日本語");
+    }
+
+    #[test]
+    fn take_width_keeps_combining_marks_with_their_letter() {
+        assert_eq!(take_width("e\u{301}x", 1), "e\u{301}");
+    }
+
+    #[test]
+    fn truncation_counts_columns_not_characters() {
+        assert_eq!(truncate_at_word_boundary("日本語の文章", 6), "日本語…");
+        assert_eq!(truncate_at_word_boundary("😀😀😀", 6), "😀😀😀");
+    }
+
+    #[test]
+    fn character_truncation_counts_characters_not_columns() {
+        assert_eq!(
+            truncate_chars_at_word_boundary("日本語の文章", 3),
+            "日本語…"
+        );
+        assert_eq!(
+            truncate_chars_at_word_boundary("日本語の文章", 6),
+            "日本語の文章"
+        );
+        assert_eq!(
+            truncate_chars_at_word_boundary("one two three", 9),
+            "one two…"
+        );
+    }
+}
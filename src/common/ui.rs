@@ -2,9 +2,183 @@
 // This reduces code duplication between different UI themes
 
 use crate::common::markdown::{
-    parse_markdown_to_structured, render_structured_to_lines, MarkdownElement,
+    element_text, header_lines, parse_markdown_to_structured, render_structured_to_lines,
+    MarkdownElement,
 };
-use ratatui::{style::Style, text::Line};
+use crate::common::text::{display_width, take_width, truncate_chars_at_word_boundary};
+use crate::links::Link;
+use crate::ui::{ComparePane, LinkRow, TextInput};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Paragraph, Wrap},
+    Frame,
+};
+use std::borrow::Cow;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Scroll events closer together than this count as one continuous scroll
+const SCROLL_ACCEL_WINDOW: Duration = Duration::from_millis(150);
+
+/// Speeds up scrolling while an arrow key is held down.
+/// Key repeats arriving within `SCROLL_ACCEL_WINDOW` build a streak that grows
+/// the step (1, 2, 4, 8 lines); a pause or a change of direction resets it.
+#[derive(Debug, Default)]
+pub struct ScrollAccel {
+    last_event: Option<Instant>,
+    last_down: bool,
+    streak: u32,
+}
+
+impl ScrollAccel {
+    /// Register a scroll event and return how many lines to move
+    pub fn step(&mut self, down: bool) -> u16 {
+        let now = Instant::now();
+        let continuing = down == self.last_down
+            && self
+                .last_event
+                .is_some_and(|last| now.duration_since(last) <= SCROLL_ACCEL_WINDOW);
+
+        self.streak = if continuing { self.streak + 1 } else { 0 };
+        self.last_event = Some(now);
+        self.last_down = down;
+
+        match self.streak {
+            0..=5 => 1,
+            6..=11 => 2,
+            12..=19 => 4,
+            _ => 8,
+        }
+    }
+}
+
+/// Smallest and largest share of the width `--split-ratio` may give the content
+pub const SPLIT_RATIO_RANGE: std::ops::RangeInclusive<u16> = 40..=90;
+
+/// Split `area` into the content column (`content_percent` of the width) and
+/// the links column. Scroll math measures the content column with this too,
+/// so it always matches what is drawn.
+pub fn split_content(area: Rect, content_percent: u16) -> Rc<[Rect]> {
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(content_percent),
+            Constraint::Percentage(100 - content_percent),
+        ])
+        .split(area)
+}
+
+/// Width of the content column of a `width` cells wide split
+pub fn content_width(width: u16, content_percent: u16) -> u16 {
+    split_content(Rect::new(0, 0, width, 1), content_percent)[0].width
+}
+
+/// Scroll down by `step` lines without passing `max_scroll`
+pub fn scroll_down_clamped(scroll_pos: u16, step: u16, max_scroll: u16) -> u16 {
+    if scroll_pos >= max_scroll {
+        scroll_pos
+    } else {
+        scroll_pos.saturating_add(step).min(max_scroll)
+    }
+}
+
+/// Shorten `text` to at most `width` columns by replacing its middle with
+/// an ellipsis, so both the start and the end of a long URL stay visible
+pub fn truncate_middle(text: &str, width: usize) -> String {
+    if display_width(text) <= width {
+        return text.to_string();
+    }
+    if width == 0 {
+        return String::new();
+    }
+
+    let keep = width - 1;
+    let head = take_width(text, keep.div_ceil(2));
+    let mut tail_budget = keep - display_width(head);
+    let mut tail_start = text.len();
+    for (index, grapheme) in text.grapheme_indices(true).rev() {
+        let Some(rest) = tail_budget.checked_sub(display_width(grapheme)) else {
+            break;
+        };
+        tail_budget = rest;
+        tail_start = index;
+    }
+    format!("{}…{}", head, &text[tail_start..])
+}
+
+/// Shown in place of the history list before any page has been visited
+pub const EMPTY_HISTORY: &str = "No history yet — press any key to enter a URL";
+
+/// Shown in place of the bookmarks list when nothing is bookmarked yet
+pub const EMPTY_BOOKMARKS: &str = "No bookmarks yet — press a on a page to bookmark it";
+
+/// Shown in place of the bookmarks list when no bookmark matches the filter
+pub const NO_MATCHING_BOOKMARKS: &str = "No bookmark has all of these tags";
+
+/// Key hints at the foot of the bookmarks view
+pub const BOOKMARKS_HELP: &str =
+    "Type tags to filter · ↑↓ select · Enter open · Del remove · Esc back";
+
+/// Placeholder for the bookmarks list when `filter` leaves it empty
+pub fn empty_bookmarks(filter: &str) -> &'static str {
+    if filter.trim().is_empty() {
+        EMPTY_BOOKMARKS
+    } else {
+        NO_MATCHING_BOOKMARKS
+    }
+}
+
+/// Narrowest `--max-width` accepted; less makes most words wrap
+pub const MIN_TEXT_WIDTH: u16 = 20;
+
+/// Wrap width for text in a pane `available` columns wide, at most
+/// `max_width`, and the indent that centres text of that width in the pane
+pub fn text_width(available: usize, max_width: Option<u16>) -> (usize, usize) {
+    let width = max_width.map_or(available, |max| available.min(max as usize));
+    (width, (available - width) / 2)
+}
+
+/// Shift `lines` right by `indent` columns
+pub fn indent_lines(lines: Vec<Line<'static>>, indent: usize) -> Vec<Line<'static>> {
+    if indent == 0 {
+        return lines;
+    }
+    lines
+        .into_iter()
+        .map(|mut line| {
+            line.spans.insert(0, Span::raw(" ".repeat(indent)));
+            line
+        })
+        .collect()
+}
+
+/// Columns left for a header URL once the status label after it is drawn
+pub fn url_width(available: u16, status: Option<&str>) -> usize {
+    let status_len = status.map_or(0, |s| display_width(s) + 2);
+    (available as usize).saturating_sub(status_len)
+}
+
+/// `input` as spans fitting `width` columns, with the character under the
+/// cursor (a space past the end) reversed as a caret. Text scrolls sideways
+/// so the caret stays in view.
+pub fn input_spans(input: &TextInput, width: usize, style: Style) -> Vec<Span<'static>> {
+    let chars: Vec<char> = input.text.chars().collect();
+    let cursor = input.cursor.min(chars.len());
+    let start = (cursor + 1).saturating_sub(width.max(1));
+    let end = chars.len().min(start + width.max(1));
+
+    let caret = chars.get(cursor).copied().unwrap_or(' ');
+    let before: String = chars[start..cursor].iter().collect();
+    let after: String = chars[(cursor + 1).min(end)..end].iter().collect();
+    vec![
+        Span::styled(before, style),
+        Span::styled(caret.to_string(), style.add_modifier(Modifier::REVERSED)),
+        Span::styled(after, style),
+    ]
+}
 
 /// Calculate scroll bounds safely to prevent crashes
 pub fn calculate_scroll_bounds(
@@ -24,34 +198,65 @@ pub fn calculate_scroll_bounds(
     (start_index, end_index, max_scroll)
 }
 
-/// Calculate max scroll for markdown content with given dimensions
+/// `summary` as shown: whole when `expanded` or short enough, otherwise the
+/// lines that fit the `preview` of that many characters (`--summary-preview`)
+/// and a hint on how to expand it. Without a preview it is always whole.
+pub fn shown_summary(summary: &str, expanded: bool, preview: Option<usize>) -> Cow<'_, str> {
+    let limit = preview.unwrap_or(0);
+    if expanded || limit == 0 || summary.chars().count() <= limit {
+        return Cow::Borrowed(summary);
+    }
+    // Whole lines, so no markup is left open; only a first line longer than
+    // the whole preview is cut inside
+    let mut preview = String::new();
+    for line in summary.lines() {
+        if preview.chars().count() + line.chars().count() > limit {
+            break;
+        }
+        preview.push_str(line);
+        preview.push('\n');
+    }
+    if preview.trim().is_empty() {
+        let first = summary.trim_start().lines().next().unwrap_or_default();
+        preview = truncate_chars_at_word_boundary(first, limit);
+    }
+    Cow::Owned(format!("{}\n\n*… press v to expand*", preview.trim_end()))
+}
+
+/// Calculate max scroll for markdown content with given dimensions; `raw`
+/// measures the markdown itself rather than its render. `line_spacing` puts a
+/// blank line after every block (`--line-spacing`); every summary measure
+/// below takes it, so scrolling stays consistent.
 pub fn calculate_max_scroll_for_markdown<F>(
     summary: &str,
     width: usize,
+    line_spacing: bool,
     visible_height: usize,
+    raw: bool,
     styler: F,
 ) -> u16
 where
     F: Fn(&MarkdownElement) -> Style,
 {
-    let parsed_lines = parse_markdown_to_structured(summary, width);
-    let lines = render_structured_to_lines(&parsed_lines, styler);
+    let lines = summary_lines(summary, width, line_spacing, raw, styler);
     lines.len().saturating_sub(visible_height) as u16
 }
 
-/// Get visible lines from markdown content with safe bounds checking
+/// Get visible lines from markdown content with safe bounds checking; `raw`
+/// shows the markdown itself, unstyled, instead of rendering it
 pub fn get_visible_markdown_lines<F>(
     summary: &str,
     width: usize,
+    line_spacing: bool,
     scroll_pos: u16,
     visible_height: usize,
+    raw: bool,
     styler: F,
 ) -> Vec<Line<'static>>
 where
     F: Fn(&MarkdownElement) -> Style,
 {
-    let parsed_lines = parse_markdown_to_structured(summary, width);
-    let lines = render_structured_to_lines(&parsed_lines, styler);
+    let lines = summary_lines(summary, width, line_spacing, raw, styler);
 
     if lines.is_empty() {
         return Vec::new();
@@ -67,6 +272,218 @@ where
     }
 }
 
+/// Lines of the summary as drawn: rendered markdown, or when `raw` the
+/// markdown as it is, only wrapped at `width`
+fn summary_lines<F>(
+    summary: &str,
+    width: usize,
+    line_spacing: bool,
+    raw: bool,
+    styler: F,
+) -> Vec<Line<'static>>
+where
+    F: Fn(&MarkdownElement) -> Style,
+{
+    if !raw {
+        return render_structured_to_lines(
+            &parse_markdown_to_structured(summary, width, line_spacing),
+            styler,
+        );
+    }
+    let mut lines = Vec::new();
+    for line in summary.lines() {
+        if line.trim().is_empty() {
+            lines.push(Line::default());
+            continue;
+        }
+        lines.extend(
+            textwrap::wrap(line, width.max(1))
+                .into_iter()
+                .map(|part| Line::from(part.into_owned())),
+        );
+    }
+    lines
+}
+
+/// Visible lines of a summary pane `width` cells wide and `visible_height`
+/// lines tall scrolled to `scroll_pos`, and the pane's max scroll
+pub fn markdown_pane<F>(
+    summary: &str,
+    width: usize,
+    line_spacing: bool,
+    scroll_pos: u16,
+    visible_height: usize,
+    styler: F,
+) -> (Vec<Line<'static>>, u16)
+where
+    F: Fn(&MarkdownElement) -> Style,
+{
+    let parsed_lines = parse_markdown_to_structured(summary, width, line_spacing);
+    let lines = render_structured_to_lines(&parsed_lines, styler);
+    let (start_index, end_index, max_scroll) =
+        calculate_scroll_bounds(lines.len(), visible_height, scroll_pos);
+    (lines[start_index..end_index].to_vec(), max_scroll)
+}
+
+/// How a theme dresses the two panes of the compare view
+pub struct CompareStyle<B, S> {
+    /// Base style of the summary text
+    pub text: Style,
+    /// Block around a pane, given its label and whether it has focus
+    pub block: B,
+    /// Style of each markdown element, as on the page view
+    pub markdown: S,
+}
+
+/// Draw the two summaries of the compare view side by side in `area`,
+/// scrolled to `positions`, and return each pane's max scroll. Themes draw
+/// their own header and key hints around it.
+pub fn render_compare_panes<B, S>(
+    f: &mut Frame,
+    area: Rect,
+    panes: [&ComparePane; 2],
+    focus: usize,
+    positions: [u16; 2],
+    line_spacing: bool,
+    style: &CompareStyle<B, S>,
+) -> [u16; 2]
+where
+    B: Fn(&str, bool) -> Block<'static>,
+    S: Fn(&MarkdownElement) -> Style,
+{
+    let pane_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(area);
+
+    let mut max_scroll = [0; 2];
+    for (i, pane) in panes.iter().enumerate() {
+        let block = (style.block)(&pane.label, i == focus);
+        let inner = block.inner(pane_chunks[i]);
+        let (lines, max) = markdown_pane(
+            &pane.summary,
+            inner.width.saturating_sub(2) as usize,
+            line_spacing,
+            positions[i],
+            inner.height as usize,
+            &style.markdown,
+        );
+        max_scroll[i] = max;
+        f.render_widget(
+            Paragraph::new(lines)
+                .style(style.text)
+                .wrap(Wrap { trim: false })
+                .block(block),
+            pane_chunks[i],
+        );
+    }
+    max_scroll
+}
+
+/// Scroll positions of the two compare panes. The panes are only measured
+/// while rendering, so scrolling is clamped to the bounds of the last render.
+#[derive(Debug, Default)]
+pub struct PaneScroll {
+    positions: [u16; 2],
+    max: [u16; 2],
+}
+
+impl PaneScroll {
+    pub fn positions(&self) -> [u16; 2] {
+        self.positions
+    }
+
+    /// Move `pane` by `lines`, negative for up
+    pub fn scroll(&mut self, pane: usize, lines: i32) {
+        let Some(position) = self.positions.get_mut(pane) else {
+            return;
+        };
+        let moved = (*position as i32 + lines).clamp(0, self.max[pane] as i32);
+        *position = moved as u16;
+    }
+
+    /// Record the panes' max scroll after a render
+    pub fn set_max(&mut self, max: [u16; 2]) {
+        self.max = max;
+        for (position, max) in self.positions.iter_mut().zip(max) {
+            *position = (*position).min(max);
+        }
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// Index of the first rendered summary line containing `needle`, ignoring
+/// case, when the summary is wrapped at `width`
+pub fn find_text_line(
+    summary: &str,
+    width: usize,
+    line_spacing: bool,
+    needle: &str,
+) -> Option<u16> {
+    let needle = needle.trim().to_lowercase();
+    if needle.is_empty() {
+        return None;
+    }
+
+    parse_markdown_to_structured(summary, width.max(1), line_spacing)
+        .iter()
+        .position(|line| {
+            let text: String = line.elements.iter().map(element_text).collect();
+            text.to_lowercase().contains(&needle)
+        })
+        .map(|index| index as u16)
+}
+
+/// Rendered line of the first header below `position` when `forward`, else
+/// of the last header above it, when the summary is wrapped at `width`
+pub fn section_line(
+    summary: &str,
+    width: usize,
+    line_spacing: bool,
+    position: u16,
+    forward: bool,
+) -> Option<u16> {
+    let headers = header_lines(summary, width.max(1), line_spacing);
+    let mut lines = headers.iter().map(|&(line, _)| line as u16);
+    if forward {
+        lines.find(|&line| line > position)
+    } else {
+        lines.rfind(|&line| line < position)
+    }
+}
+
+/// Markdown of the section at the top of the summary scrolled to `position`:
+/// from the last header at or above it to the next header. The text before
+/// the first header counts as a section too. `None` without any headers.
+pub fn section_at(
+    summary: &str,
+    width: usize,
+    line_spacing: bool,
+    position: u16,
+) -> Option<String> {
+    let headers = header_lines(summary, width.max(1), line_spacing);
+    if headers.is_empty() {
+        return None;
+    }
+    let current = headers
+        .iter()
+        .rposition(|&(line, _)| line as u16 <= position);
+    let start = current.map_or(0, |index| headers[index].1);
+    let end = headers
+        .get(current.map_or(0, |index| index + 1))
+        .map_or(usize::MAX, |&(_, source)| source);
+
+    let lines: Vec<&str> = summary
+        .lines()
+        .skip(start)
+        .take(end.saturating_sub(start))
+        .collect();
+    Some(lines.join("\n").trim().to_string()).filter(|section| !section.is_empty())
+}
+
 /// Update links scroll position to keep selected link visible
 pub fn update_links_scroll(
     selected_link: usize,
@@ -85,3 +502,63 @@ pub fn update_links_scroll(
         current_scroll
     }
 }
+
+/// One line of the links panel, ready to draw
+#[derive(Debug, Clone)]
+pub struct LinkLine {
+    /// Number shown as `[n]`, `None` for host headers
+    pub number: Option<usize>,
+    pub text: String,
+    /// Host header in grouping mode, drawn emphasized
+    pub header: bool,
+    /// Where the link points, `None` for host headers
+    pub url: Option<String>,
+}
+
+impl LinkLine {
+    /// "[n] text" for links, the bare text for host headers
+    pub fn label(&self) -> String {
+        match self.number {
+            Some(number) => format!("[{}] {}", number, self.text),
+            None => self.text.clone(),
+        }
+    }
+}
+
+/// Lines for the links panel: the links themselves, or the host groups when
+/// grouping is on, with the links of expanded hosts indented under them
+pub fn link_lines(links: &[Link], groups: Option<&[LinkRow]>) -> Vec<LinkLine> {
+    let Some(groups) = groups else {
+        return links
+            .iter()
+            .map(|link| LinkLine {
+                number: Some(link.index),
+                text: link.text.clone(),
+                header: false,
+                url: Some(link.url.clone()),
+            })
+            .collect();
+    };
+
+    groups
+        .iter()
+        .map(|row| match row {
+            LinkRow::Host {
+                host,
+                count,
+                expanded,
+            } => LinkLine {
+                number: None,
+                text: format!("{} {} ({})", if *expanded { "▾" } else { "▸" }, host, count),
+                header: true,
+                url: None,
+            },
+            LinkRow::Link(link) => LinkLine {
+                number: Some(link.index),
+                text: format!("  {}", link.text),
+                header: false,
+                url: Some(link.url.clone()),
+            },
+        })
+        .collect()
+}
@@ -0,0 +1,295 @@
+// Runtime configuration shared by the browser components
+// Built once at startup from the config file and the command-line arguments
+
+use crate::{cache, client, crawl, history, http, openai, paths};
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Default number of characters of raw page text shown when summarization fails
+pub const DEFAULT_FALLBACK_TEXT_LENGTH: usize = 1000;
+/// Default number of characters of page text summarized in quick look mode
+pub const DEFAULT_QUICK_LOOK_CHARS: usize = 2000;
+/// Default search URL for text typed in the URL bar that isn't an address
+pub const DEFAULT_SEARCH_ENGINE: &str = "https://duckduckgo.com/html/?q={}";
+/// Default word count below which a page is shown as raw text instead of summarized
+pub const DEFAULT_MIN_CONTENT_WORDS: usize = 20;
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// Maximum length of the raw text excerpt shown when the summary fails
+    pub fallback_text_length: usize,
+    /// Start in quick look mode, summarizing only the start of each page
+    pub quick_look: bool,
+    /// Characters of page text summarized in quick look mode
+    pub quick_look_chars: usize,
+    /// Ask for summaries as a nested bullet outline, without prose
+    pub outline: bool,
+    /// Explain JSON responses with the LLM as they load instead of only showing them
+    pub explain_json: bool,
+    /// Pages with fewer words than this skip the LLM and show their raw text
+    pub min_content_words: usize,
+    /// Ask for confirmation before quitting
+    pub confirm_quit: bool,
+    /// Keep the scroll position and selected link when refreshing a page;
+    /// following links and going back or forward always start at the top
+    pub keep_scroll_on_refresh: bool,
+    /// Show at most this many of the page's links, `None` for all
+    pub max_links: Option<usize>,
+    /// Keep at most this many links per host when extracting, `None` for no limit
+    pub max_links_per_host: Option<usize>,
+    /// Keep links to sections of the page itself, scrolled to instead of fetched
+    pub anchor_links: bool,
+    /// Schemes of non-web links (`mailto`, `tel`, `magnet`...) kept
+    /// in the links panel and opened with the system's handler
+    pub link_schemes: Vec<String>,
+    /// Only offer URL suggestions that answer a quick reachability probe
+    pub verify_suggestions: bool,
+    /// Host -> CSS selector for the main content, tried before the built-in list
+    pub site_selectors: HashMap<String, String>,
+    /// CSS selectors for the main content on every site, tried in order after
+    /// the site's own and before the built-in list
+    pub content_selectors: Vec<String>,
+    /// CSS selectors of elements left out of the extracted text on every site
+    pub exclude_selectors: Vec<String>,
+    /// Host (or `*`) -> proxy URL or `direct`, see `http::ProxyRules`
+    pub proxies: HashMap<String, String>,
+    /// Only these host patterns may be visited when non-empty, see `domains::DomainFilter`
+    pub allow_domains: Vec<String>,
+    /// Host patterns that are never visited
+    pub deny_domains: Vec<String>,
+    /// MIME types read as pages; HTML is summarized from its text, JSON
+    /// pretty-printed and anything else read as plain text
+    pub accept: Vec<String>,
+    /// `Accept` header sent for pages; built from `accept` when `None`
+    pub accept_header: Option<String>,
+    /// Include the page title in the text sent for summarization
+    pub summarize_title: bool,
+    /// Chat model used for summaries
+    pub model: String,
+    /// Token budget for a summary
+    pub summary_max_tokens: u32,
+    /// Sampling temperature for summaries
+    pub temperature: f32,
+    /// Token budget of the second summary in the compare view, `None` for the current one
+    pub compare_max_tokens: Option<u32>,
+    /// Temperature of the second summary in the compare view, `None` for the current one
+    pub compare_temperature: Option<f32>,
+    /// Model (or model name prefix) -> price, for the token cost estimates
+    pub prices: HashMap<String, openai::ModelPrice>,
+    /// Most pages kept in the back/forward history
+    pub history_size: usize,
+    /// Most pages kept to go back and forward to without refetching, 0 for none
+    pub cache_entries: usize,
+    /// Longest page title kept in the history, cut with an ellipsis
+    pub history_title_chars: usize,
+    /// Search URL for typed text that isn't an address; `{}` is the query
+    pub search_engine: String,
+    /// Where settings changed at runtime are saved
+    pub config_path: Option<PathBuf>,
+    /// Append this many of the page's links to the summary as numbered references
+    pub references: Option<usize>,
+    /// Directory for HTML exports, the working directory when `None`
+    pub export_dir: Option<PathBuf>,
+    /// Percent of the page width given to the summary, `None` for the theme's own split
+    pub split_ratio: Option<u16>,
+    /// Widest the summary text wraps, `None` for the full pane width
+    pub max_width: Option<u16>,
+    /// Blank line after every paragraph, header and list item of the summary
+    pub line_spacing: bool,
+    /// Characters of a summary shown until expanded, `None` to show it whole
+    pub summary_preview: Option<usize>,
+    /// Draw the page's lead image on terminals with inline graphics support
+    pub images: bool,
+    /// Never contact an AI service, and fetch only what the user asks for
+    /// (pages, sitemaps, crawls): no address probes or lead images
+    pub local_only: bool,
+    /// Show how each page's main text was found once it loads
+    pub debug_extract: bool,
+    /// Make links clickable with OSC 8 where the terminal supports it
+    pub hyperlinks: bool,
+    /// Name of the UI theme in use, shown on the info screen
+    pub theme: String,
+    /// Where logs are written, `None` when logging is off or goes to stderr
+    pub log_file: Option<PathBuf>,
+    /// Most HTTP requests (page fetches, probes, API calls) in flight at once
+    pub max_concurrency: usize,
+    /// Least time between two page requests to the same host
+    pub host_delay: Duration,
+    /// Fetch and summarize the page shown again this often, `None` for never
+    pub auto_refresh: Option<Duration>,
+    /// Most pages summarized at once by `--crawl`
+    pub summarize_concurrency: usize,
+    /// Most pages visited by a crawl started from the UI
+    pub crawl_pages: usize,
+    /// Save each page load's HTML, text, prompts and responses under this directory
+    pub record_dir: Option<PathBuf>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            fallback_text_length: DEFAULT_FALLBACK_TEXT_LENGTH,
+            quick_look: false,
+            quick_look_chars: DEFAULT_QUICK_LOOK_CHARS,
+            outline: false,
+            explain_json: false,
+            min_content_words: DEFAULT_MIN_CONTENT_WORDS,
+            confirm_quit: false,
+            keep_scroll_on_refresh: true,
+            max_links: None,
+            max_links_per_host: None,
+            anchor_links: false,
+            link_schemes: Vec::new(),
+            verify_suggestions: true,
+            site_selectors: HashMap::new(),
+            content_selectors: Vec::new(),
+            exclude_selectors: Vec::new(),
+            proxies: HashMap::new(),
+            allow_domains: Vec::new(),
+            deny_domains: Vec::new(),
+            accept: client::DEFAULT_ACCEPT
+                .iter()
+                .map(|mime| mime.to_string())
+                .collect(),
+            accept_header: None,
+            summarize_title: true,
+            model: openai::OPENAI_MODEL.to_string(),
+            summary_max_tokens: openai::MAX_TOKENS,
+            temperature: openai::TEMPERATURE,
+            compare_max_tokens: None,
+            compare_temperature: None,
+            prices: HashMap::new(),
+            history_size: history::DEFAULT_HISTORY_SIZE,
+            cache_entries: cache::DEFAULT_CACHE_ENTRIES,
+            history_title_chars: history::DEFAULT_MAX_TITLE_CHARS,
+            search_engine: DEFAULT_SEARCH_ENGINE.to_string(),
+            config_path: default_config_path(),
+            references: None,
+            export_dir: None,
+            split_ratio: None,
+            max_width: None,
+            line_spacing: false,
+            summary_preview: None,
+            images: false,
+            local_only: false,
+            debug_extract: false,
+            hyperlinks: true,
+            theme: "default".to_string(),
+            log_file: None,
+            max_concurrency: http::DEFAULT_MAX_CONCURRENCY,
+            host_delay: Duration::ZERO,
+            auto_refresh: None,
+            summarize_concurrency: crawl::DEFAULT_SUMMARIZE_CONCURRENCY,
+            crawl_pages: crawl::DEFAULT_CRAWL_PAGES,
+            record_dir: None,
+        }
+    }
+}
+
+impl Config {
+    /// Write the settings-screen options to the config file, keeping any
+    /// other keys already in it
+    pub fn save_settings(&self) -> Result<PathBuf> {
+        let path = self
+            .config_path
+            .clone()
+            .context("No config file location available")?;
+
+        let mut table = match std::fs::read_to_string(&path) {
+            Ok(contents) => contents
+                .parse::<toml::Table>()
+                .with_context(|| format!("Invalid config file {}", path.display()))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => toml::Table::new(),
+            // Anything else would overwrite a file that could not be read
+            Err(e) => {
+                return Err(e)
+                    .with_context(|| format!("Failed to read config file {}", path.display()));
+            }
+        };
+
+        table.insert("model".into(), self.model.clone().into());
+        table.insert(
+            "summary_max_tokens".into(),
+            i64::from(self.summary_max_tokens).into(),
+        );
+        // Round so 0.3f32 is written as 0.3 rather than 0.30000001192092896
+        let temperature = (f64::from(self.temperature) * 100.0).round() / 100.0;
+        table.insert("temperature".into(), temperature.into());
+        table.insert("summarize_title".into(), self.summarize_title.into());
+        table.insert("verify_suggestions".into(), self.verify_suggestions.into());
+        table.insert("confirm_quit".into(), self.confirm_quit.into());
+
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        std::fs::write(&path, toml::to_string_pretty(&table)?)
+            .with_context(|| format!("Failed to write config file {}", path.display()))?;
+        Ok(path)
+    }
+}
+
+/// Settings read from the TOML config file. Every field is optional.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct FileConfig {
+    pub site_selectors: HashMap<String, String>,
+    pub content_selectors: Vec<String>,
+    pub exclude_selectors: Vec<String>,
+    pub proxies: HashMap<String, String>,
+    pub prices: HashMap<String, openai::ModelPrice>,
+    pub allow_domains: Vec<String>,
+    pub deny_domains: Vec<String>,
+    pub accept: Option<Vec<String>>,
+    pub accept_header: Option<String>,
+    pub model: Option<String>,
+    pub summary_max_tokens: Option<u32>,
+    pub temperature: Option<f32>,
+    pub summarize_title: Option<bool>,
+    pub verify_suggestions: Option<bool>,
+    pub confirm_quit: Option<bool>,
+    pub keep_scroll_on_refresh: Option<bool>,
+    pub export_dir: Option<PathBuf>,
+    pub history_size: Option<usize>,
+    pub cache_entries: Option<usize>,
+    pub crawl_pages: Option<usize>,
+    pub history_title_chars: Option<usize>,
+    pub search_engine: Option<String>,
+    pub anchor_links: Option<bool>,
+    pub link_schemes: Vec<String>,
+    pub split_ratio: Option<u16>,
+    pub max_width: Option<u16>,
+    pub line_spacing: Option<bool>,
+    pub explain_json: Option<bool>,
+    pub summary_preview: Option<usize>,
+}
+
+impl FileConfig {
+    /// Load the config file at `path`, or from the default location when `None`.
+    /// A missing default file is not an error; a missing explicit file is.
+    pub fn load(path: Option<&Path>) -> Result<Self> {
+        let (path, explicit) = match path {
+            Some(path) => (path.to_path_buf(), true),
+            None => match default_config_path() {
+                Some(path) => (path, false),
+                None => return Ok(Self::default()),
+            },
+        };
+
+        if !explicit && !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let contents = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read config file {}", path.display()))?;
+        toml::from_str(&contents).with_context(|| format!("Invalid config file {}", path.display()))
+    }
+}
+
+/// `config.toml` in the config directory, see `paths::config_dir`
+pub fn default_config_path() -> Option<PathBuf> {
+    paths::config_dir().map(|dir| dir.join("config.toml"))
+}
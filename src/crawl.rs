@@ -0,0 +1,281 @@
+// Batch crawl: summarize a site's pages without the TUI
+// Pages are fetched breadth-first from the start URL, staying on its host, and
+// summarized up to `--summarize-concurrency` at a time while the crawl goes on.
+// Results arrive over a channel as they finish, so they can be shown at once,
+// printed by `--crawl` or listed live in the UI (`W`).
+
+use crate::{
+    browser::{document_title, insufficient_content, normalize_url, unsummarized, Browser},
+    client::WebClient,
+    config::Config,
+    domains::DomainFilter,
+    extractor::{self, ExtractedText, TextExtractor},
+    http::Http,
+    links::LinkExtractor,
+    openai::{OpenAIClient, SummaryHints},
+};
+use anyhow::{anyhow, Result};
+use std::collections::{HashSet, VecDeque};
+use std::sync::Arc;
+use tokio::sync::mpsc::error::TryRecvError;
+use tokio::sync::{mpsc, Semaphore};
+use tokio::task::{JoinHandle, JoinSet};
+use tracing::{debug, warn};
+use url::Url;
+
+/// Default number of pages summarized at once during a crawl
+pub const DEFAULT_SUMMARIZE_CONCURRENCY: usize = 4;
+/// Default number of pages visited by a crawl started from the UI
+pub const DEFAULT_CRAWL_PAGES: usize = 20;
+
+/// Something that happened during a crawl, in the order it happened
+#[derive(Debug)]
+pub enum CrawlEvent {
+    /// A page was fetched; `queued` more are waiting to be
+    Fetched {
+        url: String,
+        queued: usize,
+    },
+    Summarized(CrawledPage),
+}
+
+#[derive(Debug)]
+pub struct CrawledPage {
+    pub url: String,
+    pub title: String,
+    pub summary: String,
+}
+
+pub struct Crawler {
+    client: WebClient,
+    extractor: TextExtractor,
+    link_extractor: LinkExtractor,
+    domains: DomainFilter,
+    openai: Option<Arc<OpenAIClient>>,
+    /// Summaries in flight at once; API calls also count against `--max-concurrency`
+    summarize_limit: Arc<Semaphore>,
+    max_pages: usize,
+    outline: bool,
+    min_content_words: usize,
+    fallback_text_length: usize,
+}
+
+impl Crawler {
+    /// A crawler visiting at most `max_pages` pages
+    pub fn new(config: &Config, http: Http, max_pages: usize) -> Result<Self> {
+        Ok(Self {
+            openai: Browser::build_openai(config, http.clone())?.map(Arc::new),
+            client: WebClient::new(http)
+                .with_host_delay(config.host_delay)
+                .with_accept(&config.accept)
+                .with_accept_header(config.accept_header.as_deref()),
+            extractor: Browser::build_extractor(config),
+            link_extractor: LinkExtractor::new().with_max_links_per_host(config.max_links_per_host),
+            domains: DomainFilter::new(&config.allow_domains, &config.deny_domains),
+            summarize_limit: Arc::new(Semaphore::new(config.summarize_concurrency.max(1))),
+            max_pages,
+            outline: config.outline,
+            min_content_words: config.min_content_words,
+            fallback_text_length: config.fallback_text_length,
+        })
+    }
+
+    /// Crawl from `start`, sending what happens to `events`. Returns once
+    /// every fetched page has been summarized.
+    pub async fn run(self, start: &str, events: mpsc::UnboundedSender<CrawlEvent>) -> Result<()> {
+        let start = Url::parse(&normalize_url(start)?)?;
+        let host = start
+            .host_str()
+            .ok_or_else(|| anyhow!("{} has no host to crawl", start))?
+            .to_string();
+
+        let mut queue = VecDeque::from([start.to_string()]);
+        let mut seen = HashSet::from([without_fragment(&start)]);
+        let mut fetched = 0;
+        let mut summaries = JoinSet::new();
+
+        while let Some(url) = queue.pop_front() {
+            if fetched >= self.max_pages {
+                break;
+            }
+            let page = match self.client.fetch(&url).await {
+                Ok(page) => page,
+                Err(e) => {
+                    warn!(url, error = %e, "skipping page");
+                    continue;
+                }
+            };
+            fetched += 1;
+
+            let (title, extracted, links) = if page.is_html {
+                // One page that can't be read costs that page, not the crawl
+                let read = self
+                    .extractor
+                    .extract_text(&page.body, &url)
+                    .and_then(|text| {
+                        Ok((text, self.link_extractor.extract_links(&page.body, &url)?))
+                    });
+                match read {
+                    Ok((text, links)) => (extractor::page_title(&page.body), text, links),
+                    Err(e) => {
+                        warn!(url, error = %e, "skipping unreadable page");
+                        continue;
+                    }
+                }
+            } else {
+                let text = ExtractedText {
+                    text: page.body,
+                    ..Default::default()
+                };
+                (document_title(&url), text, Vec::new())
+            };
+            for link in links {
+                let Ok(parsed) = Url::parse(&link.url) else {
+                    continue;
+                };
+                if parsed.host_str() == Some(host.as_str())
+                    && self.domains.blocked_reason(&parsed).is_none()
+                    && seen.insert(without_fragment(&parsed))
+                {
+                    queue.push_back(link.url);
+                }
+            }
+            let _ = events.send(CrawlEvent::Fetched {
+                url: url.clone(),
+                queued: queue.len().min(self.max_pages - fetched),
+            });
+
+            let summarize = self.summarize(url, title, extracted, events.clone());
+            summaries.spawn(summarize);
+        }
+
+        debug!(fetched, "crawl finished fetching");
+        while let Some(result) = summaries.join_next().await {
+            result?;
+        }
+        Ok(())
+    }
+
+    /// Summary of one page, waiting for a free slot first, sent to `events`
+    fn summarize(
+        &self,
+        url: String,
+        title: String,
+        extracted: ExtractedText,
+        events: mpsc::UnboundedSender<CrawlEvent>,
+    ) -> impl std::future::Future<Output = ()> + Send + 'static {
+        let openai = self.openai.clone();
+        let limit = self.summarize_limit.clone();
+        let (min_words, excerpt_length) = (self.min_content_words, self.fallback_text_length);
+        let outline = self.outline;
+        async move {
+            let text = extracted.text;
+            let summary = match (
+                insufficient_content(&text, min_words, excerpt_length),
+                openai,
+            ) {
+                (Some(note), _) => note,
+                (None, None) => unsummarized(&text),
+                (None, Some(openai)) => {
+                    let _permit = limit.acquire().await.expect("limit is never closed");
+                    let hints = SummaryHints {
+                        possibly_partial: extracted.paywalled,
+                        published: extracted
+                            .published
+                            .map(|date| date.format("%Y-%m-%d").to_string()),
+                        outline,
+                        ..Default::default()
+                    };
+                    match openai
+                        .summarize(&text, &url, &hints, openai.options())
+                        .await
+                    {
+                        Ok(completion) => completion.text,
+                        Err(e) => format!("*Failed to generate summary: {}*", e),
+                    }
+                }
+            };
+            let page = CrawledPage {
+                url,
+                title,
+                summary,
+            };
+            let _ = events.send(CrawlEvent::Summarized(page));
+        }
+    }
+}
+
+/// A crawl going on in the background, followed from the UI a few events at
+/// a time. Dropping it stops the crawl.
+pub struct RunningCrawl {
+    events: mpsc::UnboundedReceiver<CrawlEvent>,
+    task: JoinHandle<Result<()>>,
+}
+
+impl RunningCrawl {
+    /// Start `crawler` on its own task, from `start`
+    pub fn spawn(crawler: Crawler, start: &str) -> Self {
+        let (events, received) = mpsc::unbounded_channel();
+        let start = start.to_string();
+        Self {
+            events: received,
+            task: tokio::spawn(async move { crawler.run(&start, events).await }),
+        }
+    }
+
+    /// Events sent since the last call, without waiting for more, and
+    /// whether the crawl has finished sending them
+    pub fn drain(&mut self) -> (Vec<CrawlEvent>, bool) {
+        let mut events = Vec::new();
+        loop {
+            match self.events.try_recv() {
+                Ok(event) => events.push(event),
+                Err(TryRecvError::Empty) => return (events, false),
+                Err(TryRecvError::Disconnected) => return (events, true),
+            }
+        }
+    }
+
+    /// How the crawl ended, once `drain` says it finished
+    pub async fn finish(&mut self) -> Result<()> {
+        (&mut self.task).await?
+    }
+}
+
+impl Drop for RunningCrawl {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Crawl up to `max_pages` pages from `start` and print each summary as
+/// markdown once it is ready, with progress on stderr
+pub async fn print(config: &Config, http: Http, start: &str, max_pages: usize) -> Result<()> {
+    let crawler = Crawler::new(config, http, max_pages)?;
+    let (events, mut received) = mpsc::unbounded_channel();
+    let start = start.to_string();
+    let crawl = tokio::spawn(async move { crawler.run(&start, events).await });
+
+    let (mut fetched, mut summarized) = (0, 0);
+    while let Some(event) = received.recv().await {
+        match event {
+            CrawlEvent::Fetched { url, queued } => {
+                fetched += 1;
+                eprintln!("Fetched {} ({} queued)", url, queued);
+            }
+            CrawlEvent::Summarized(page) => {
+                summarized += 1;
+                eprintln!("[{}/{}] Summarized {}", summarized, fetched, page.url);
+                println!("# {}\n\n<{}>\n\n{}\n", page.title, page.url, page.summary);
+            }
+        }
+    }
+    crawl.await?
+}
+
+/// `url` without its `#fragment`, so sections of a page count as the page
+fn without_fragment(url: &Url) -> String {
+    let mut url = url.clone();
+    url.set_fragment(None);
+    url.to_string()
+}
@@ -0,0 +1,62 @@
+// Domain allow/deny rules for navigation
+// Checked before a page is fetched so blocked sites are never contacted
+
+use url::Url;
+
+/// Allow and deny lists of host patterns. A pattern is a host (`example.com`,
+/// matching only that host), a wildcard (`*.example.com`, matching any of its
+/// subdomains) or `*` for every host. Deny wins over allow, and a non-empty
+/// allow list blocks every host it does not match.
+#[derive(Debug, Clone, Default)]
+pub struct DomainFilter {
+    allow: Vec<String>,
+    deny: Vec<String>,
+}
+
+impl DomainFilter {
+    pub fn new(allow: &[String], deny: &[String]) -> Self {
+        let normalize = |patterns: &[String]| -> Vec<String> {
+            patterns
+                .iter()
+                .map(|p| p.trim().trim_end_matches('.').to_lowercase())
+                .filter(|p| !p.is_empty())
+                .collect()
+        };
+        Self {
+            allow: normalize(allow),
+            deny: normalize(deny),
+        }
+    }
+
+    /// Why navigating to `url` is not allowed, or `None` when it is
+    pub fn blocked_reason(&self, url: &Url) -> Option<String> {
+        let host = url.host_str()?.trim_end_matches('.').to_lowercase();
+
+        if let Some(pattern) = self.deny.iter().find(|p| host_matches(p, &host)) {
+            return Some(format!(
+                "{} is blocked by the deny rule '{}'",
+                host, pattern
+            ));
+        }
+        if !self.allow.is_empty() && !self.allow.iter().any(|p| host_matches(p, &host)) {
+            return Some(format!(
+                "{} is not in the allowed domains ({})",
+                host,
+                self.allow.join(", ")
+            ));
+        }
+        None
+    }
+}
+
+fn host_matches(pattern: &str, host: &str) -> bool {
+    if pattern == "*" {
+        return true;
+    }
+    match pattern.strip_prefix("*.") {
+        Some(domain) => host
+            .strip_suffix(domain)
+            .is_some_and(|prefix| prefix.len() > 1 && prefix.ends_with('.')),
+        None => host == pattern,
+    }
+}
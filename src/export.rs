@@ -0,0 +1,79 @@
+// Standalone HTML export of a page summary, for sharing outside the terminal
+
+use crate::common::markdown::{
+    escape_html, parse_markdown_to_structured, render_structured_to_html,
+};
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+/// Longest file name stem taken from a page title
+const MAX_STEM_CHARS: usize = 60;
+
+const STYLE: &str = "body{max-width:46rem;margin:2rem auto;padding:0 1rem;\
+font:16px/1.6 system-ui,sans-serif;color:#1c1c1e}\
+h1,h2,h3,h4{line-height:1.25}a{color:#0058d0}\
+code{background:#eee;padding:0 .25em;border-radius:3px}\
+dl{display:grid;grid-template-columns:max-content auto;gap:.25em 1em}\
+dt{font-weight:600}dd{margin:0}\
+header{border-bottom:1px solid #ddd;margin-bottom:1.5rem}\
+.source{color:#636366;font-size:.9em}";
+
+/// Write the summary as `<dir>/<title-slug>.html`, never overwriting an
+/// existing file, and return the path written
+pub fn export_html(dir: &Path, url: &str, title: &str, summary: &str) -> Result<PathBuf> {
+    let body = render_structured_to_html(&parse_markdown_to_structured(summary, usize::MAX, false));
+    let document = format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+        <title>{title}</title>\n<style>{style}</style>\n</head>\n<body>\n<header>\n\
+        <h1>{title}</h1>\n<p class=\"source\">Summary of <a href=\"{url}\">{url}</a>, \
+        exported {date} by bbow</p>\n</header>\n{body}</body>\n</html>\n",
+        title = escape_html(title),
+        style = STYLE,
+        url = escape_html(url),
+        date = chrono::Local::now().format("%Y-%m-%d %H:%M"),
+        body = body,
+    );
+
+    std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let path = unused_path(dir, &file_stem(title));
+    std::fs::write(&path, document)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(path)
+}
+
+/// Lowercase ASCII letters and digits from the title, words joined by dashes
+pub fn file_stem(title: &str) -> String {
+    let words: Vec<String> = title
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_ascii_lowercase)
+        .collect();
+
+    let mut stem = String::new();
+    for word in words {
+        if stem.len() + word.len() + 1 > MAX_STEM_CHARS {
+            break;
+        }
+        if !stem.is_empty() {
+            stem.push('-');
+        }
+        stem.push_str(&word);
+    }
+
+    if stem.is_empty() {
+        "summary".to_string()
+    } else {
+        stem
+    }
+}
+
+/// `stem.html`, or `stem-2.html`, `stem-3.html`, ... if taken
+fn unused_path(dir: &Path, stem: &str) -> PathBuf {
+    let mut path = dir.join(format!("{}.html", stem));
+    let mut n = 2;
+    while path.exists() {
+        path = dir.join(format!("{}-{}.html", stem, n));
+        n += 1;
+    }
+    path
+}
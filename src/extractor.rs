@@ -1,17 +1,167 @@
+use crate::links::document_base;
 use anyhow::Result;
-use scraper::{Html, Selector};
+use chrono::{DateTime, NaiveDate, Utc};
+use scraper::{ElementRef, Html, Selector};
+use std::collections::{HashMap, HashSet};
+use tracing::{debug, warn};
+use url::Url;
 
-pub struct TextExtractor;
+/// Paywall markers in class/id names or data attributes
+const PAYWALL_SELECTORS: &str = "[class*='paywall'], [id*='paywall'], [data-paywall], \
+    [class*='subscriber-only'], [class*='premium-content'], .piano-offer, .tp-modal";
+const PAYWALL_PHRASES: &[&str] = &[
+    "subscribe to continue",
+    "subscribe to read",
+    "to continue reading",
+    "already a subscriber",
+    "already subscribed",
+    "subscribers only",
+    "create a free account to continue",
+    "sign in to continue reading",
+];
+/// Main content shorter than this with a subscribe prompt is treated as a teaser
+const PAYWALL_TEASER_CHARS: usize = 2000;
+
+/// Where pages declare when they were written, most specific first
+const DATE_SOURCES: &[(&str, &str)] = &[
+    ("meta[property='article:published_time']", "content"),
+    ("meta[property='article:modified_time']", "content"),
+    ("meta[itemprop='datePublished']", "content"),
+    ("meta[name='date']", "content"),
+    ("time[datetime]", "datetime"),
+];
+
+/// Where pages name their lead image
+const IMAGE_SOURCES: &[&str] = &[
+    "meta[property='og:image']",
+    "meta[name='twitter:image']",
+    "meta[property='og:image:url']",
+];
+
+/// Text pulled from a page plus what the extractor noticed about it
+#[derive(Default)]
+pub struct ExtractedText {
+    pub text: String,
+    pub paywalled: bool,
+    /// Publication (or last modification) date, when the page declares a parseable one
+    pub published: Option<DateTime<Utc>>,
+    /// Absolute URL of the page's lead image (`og:image`), if it names one
+    pub image: Option<String>,
+    /// Absolute URL the page names as its preferred address (`rel="canonical"`)
+    pub canonical: Option<String>,
+    /// Absolute URL of the page's AMP variant (`rel="amphtml"`)
+    pub amp: Option<String>,
+    /// Where the main content was found, for `--debug-extract`
+    pub source: ContentSource,
+}
+
+/// Which selector found a page's main content and what it yielded
+#[derive(Debug, Clone, Default)]
+pub struct ContentSource {
+    /// The selector that matched, or what was read when none did
+    pub selector: String,
+    /// Where that selector comes from: "site", "configured", "built-in" or "fallback"
+    pub origin: &'static str,
+    /// Selectors tried first that matched nothing, in order
+    pub missed: Vec<String>,
+    /// Characters of text the matched element yielded, before cleaning
+    pub chars: usize,
+}
+
+pub struct TextExtractor {
+    /// Per-host main content selectors (host, text, parsed), longest host
+    /// first, consulted before the others
+    site_selectors: Vec<(String, String, Selector)>,
+    /// Main content selectors for every site, tried before `MAIN_SELECTORS`
+    content_selectors: Vec<(String, Selector)>,
+    /// Elements whose text is left out wherever they appear, e.g. `#comments`
+    exclude_selectors: Vec<Selector>,
+    /// Prepend the page `<title>` as a heading to the extracted text
+    include_title: bool,
+}
 
 impl TextExtractor {
     pub fn new() -> Self {
-        Self
+        Self {
+            site_selectors: Vec::new(),
+            content_selectors: Vec::new(),
+            exclude_selectors: Vec::new(),
+            include_title: true,
+        }
+    }
+
+    /// Leave the `<title>` out of the extracted text, for sites with junk titles
+    pub fn with_title(mut self, include: bool) -> Self {
+        self.include_title = include;
+        self
     }
 
-    pub fn extract_text(&self, html: &str) -> Result<String> {
+    /// Use custom content selectors for specific hosts. Invalid selectors are
+    /// logged and skipped so one typo doesn't break extraction everywhere.
+    pub fn with_site_selectors(mut self, selectors: &HashMap<String, String>) -> Self {
+        self.site_selectors = selectors
+            .iter()
+            .filter_map(|(host, selector)| match Selector::parse(selector) {
+                Ok(parsed) => Some((host.to_lowercase(), selector.clone(), parsed)),
+                Err(e) => {
+                    warn!(host, selector, error = ?e, "skipping invalid site selector");
+                    None
+                }
+            })
+            .collect();
+        // Most specific host first, so docs.example.com beats example.com
+        // whatever order the map iterates in
+        self.site_selectors
+            .sort_by(|(a, ..), (b, ..)| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+        self
+    }
+
+    /// Try `selectors` in order for the main content of every page, after
+    /// any site selector and before the built-in ones. Invalid selectors are
+    /// logged and skipped like site selectors.
+    pub fn with_content_selectors(mut self, selectors: &[String]) -> Self {
+        self.content_selectors = selectors
+            .iter()
+            .filter_map(|selector| match Selector::parse(selector) {
+                Ok(parsed) => Some((selector.clone(), parsed)),
+                Err(e) => {
+                    warn!(selector, error = ?e, "skipping invalid content selector");
+                    None
+                }
+            })
+            .collect();
+        self
+    }
+
+    /// Leave out the text of elements matching any of `selectors`. Invalid
+    /// selectors are logged and skipped like site selectors.
+    pub fn with_exclude_selectors(mut self, selectors: &[String]) -> Self {
+        self.exclude_selectors = selectors
+            .iter()
+            .filter_map(|selector| match Selector::parse(selector) {
+                Ok(parsed) => Some(parsed),
+                Err(e) => {
+                    warn!(selector, error = ?e, "skipping invalid exclude selector");
+                    None
+                }
+            })
+            .collect();
+        self
+    }
+
+    pub fn extract_text(&self, html: &str, url: &str) -> Result<ExtractedText> {
         let doc = Html::parse_document(html);
-        let title = self.extract_title(&doc);
-        let content = self.extract_main_content(&doc);
+        let title = if self.include_title {
+            self.extract_title(&doc)
+        } else {
+            String::new()
+        };
+        let (content, source) = self.extract_main_content(&doc, url);
+        let paywalled = self.looks_paywalled(&doc, html, &content);
+        let published = self.extract_published(&doc);
+        let image = self.extract_image(&doc, url);
+        let canonical = self.extract_link_rel(&doc, url, "canonical");
+        let amp = self.extract_link_rel(&doc, url, "amphtml");
 
         let result = if title.is_empty() {
             content
@@ -19,7 +169,86 @@ impl TextExtractor {
             format!("# {}\n\n{}", title, content)
         };
 
-        Ok(self.clean_text(&result))
+        let cleaned = self.clean_text(&result);
+        debug!(
+            chars = cleaned.len(),
+            paywalled,
+            ?published,
+            ?canonical,
+            "extracted text"
+        );
+        Ok(ExtractedText {
+            text: cleaned,
+            paywalled,
+            published,
+            image,
+            canonical,
+            amp,
+            source,
+        })
+    }
+
+    /// Relative image URLs resolve like the page's links, `<base href>` included
+    fn extract_image(&self, document: &Html, url: &str) -> Option<String> {
+        let base = document_base(document, Url::parse(url).ok()?);
+        IMAGE_SOURCES.iter().find_map(|selector| {
+            let selector = Selector::parse(selector).ok()?;
+            let content = document.select(&selector).next()?.value().attr("content")?;
+            base.join(content.trim()).ok().map(String::from)
+        })
+    }
+
+    /// Target of the first `<link>` whose `rel` includes `rel`, resolved like `extract_image`
+    fn extract_link_rel(&self, document: &Html, url: &str, rel: &str) -> Option<String> {
+        let base = document_base(document, Url::parse(url).ok()?);
+        let selector = Selector::parse("link[rel][href]").ok()?;
+        document
+            .select(&selector)
+            .find(|link| {
+                link.value().attr("rel").is_some_and(|rels| {
+                    rels.split_whitespace().any(|r| r.eq_ignore_ascii_case(rel))
+                })
+            })
+            .and_then(|link| base.join(link.value().attr("href")?.trim()).ok())
+            .map(String::from)
+    }
+
+    /// First parseable date from `DATE_SOURCES`; unparseable values are skipped
+    fn extract_published(&self, document: &Html) -> Option<DateTime<Utc>> {
+        DATE_SOURCES.iter().find_map(|(selector, attr)| {
+            let selector = Selector::parse(selector).ok()?;
+            document
+                .select(&selector)
+                .filter_map(|el| el.value().attr(attr))
+                .find_map(parse_date)
+        })
+    }
+
+    /// Heuristic only: explicit paywall markup or structured data, or a short
+    /// body that ends in a subscribe prompt
+    fn looks_paywalled(&self, document: &Html, html: &str, content: &str) -> bool {
+        let compact_html: String = html
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect::<String>()
+            .to_lowercase();
+        if compact_html.contains("\"isaccessibleforfree\":false")
+            || compact_html.contains("\"isaccessibleforfree\":\"false\"")
+        {
+            return true;
+        }
+
+        if let Ok(selector) = Selector::parse(PAYWALL_SELECTORS) {
+            if document.select(&selector).next().is_some() {
+                return true;
+            }
+        }
+
+        let content_lower = content.to_lowercase();
+        content.len() < PAYWALL_TEASER_CHARS
+            && PAYWALL_PHRASES
+                .iter()
+                .any(|phrase| content_lower.contains(phrase))
     }
 
     fn extract_title(&self, document: &Html) -> String {
@@ -31,7 +260,60 @@ impl TextExtractor {
             .unwrap_or_default()
     }
 
-    fn extract_main_content(&self, document: &Html) -> String {
+    /// The configured selector for `url`'s host, matching subdomains too
+    fn site_selector(&self, url: &str) -> Option<(&str, &Selector)> {
+        let host = Url::parse(url).ok()?.host_str()?.to_lowercase();
+        self.site_selectors
+            .iter()
+            .find(|(site, ..)| host == *site || host.ends_with(&format!(".{}", site)))
+            .map(|(_, text, selector)| (text.as_str(), selector))
+    }
+
+    /// Text of the page's main content and the selector it came from. The
+    /// site selector goes first, then the configured ones, then the built-in
+    /// ones, in order; the first that matches anything wins.
+    fn extract_main_content(&self, document: &Html, url: &str) -> (String, ContentSource) {
+        let mut missed = Vec::new();
+        let found = |selector: &str, origin, missed: &mut Vec<String>, text: String| {
+            let source = ContentSource {
+                selector: selector.to_string(),
+                origin,
+                missed: std::mem::take(missed),
+                chars: text.chars().count(),
+            };
+            (text, source)
+        };
+
+        if let Some((text, selector)) = self.site_selector(url) {
+            if let Some(element) = document.select(selector).next() {
+                debug!(url, "using site-specific content selector");
+                return found(
+                    text,
+                    "site",
+                    &mut missed,
+                    self.extract_text_from_element(element),
+                );
+            }
+            debug!(
+                url,
+                "site-specific selector matched nothing, using defaults"
+            );
+            missed.push(text.to_string());
+        }
+
+        for (text, selector) in &self.content_selectors {
+            if let Some(element) = document.select(selector).next() {
+                debug!(selector = text, "using configured content selector");
+                return found(
+                    text,
+                    "configured",
+                    &mut missed,
+                    self.extract_text_from_element(element),
+                );
+            }
+            missed.push(text.clone());
+        }
+
         const MAIN_SELECTORS: &[&str] = &[
             "main",
             "article",
@@ -46,19 +328,34 @@ impl TextExtractor {
         for &selector_str in MAIN_SELECTORS {
             if let Ok(selector) = Selector::parse(selector_str) {
                 if let Some(element) = document.select(&selector).next() {
-                    return self.extract_text_from_element(element);
+                    debug!(selector = selector_str, "using main content container");
+                    return found(
+                        selector_str,
+                        "built-in",
+                        &mut missed,
+                        self.extract_text_from_element(element),
+                    );
                 }
             }
+            missed.push(selector_str.to_string());
         }
 
         // Fallback to body, then root
         if let Ok(body_selector) = Selector::parse("body") {
             if let Some(body) = document.select(&body_selector).next() {
-                return self.extract_text_from_element(body);
+                debug!("no main content container matched, falling back to <body>");
+                return found(
+                    "body",
+                    "fallback",
+                    &mut missed,
+                    self.extract_text_from_element(body),
+                );
             }
         }
 
-        document.root_element().text().collect::<String>()
+        debug!("no <body> found, falling back to document root");
+        let text = document.root_element().text().collect::<String>();
+        found(":root", "fallback", &mut missed, text)
     }
 
     fn extract_text_from_element(&self, element: scraper::ElementRef) -> String {
@@ -66,6 +363,15 @@ impl TextExtractor {
             "script", "style", "nav", "header", "footer", "aside", "noscript",
         ];
 
+        // Everything under an excluded element is dropped, not just the element
+        let excluded: HashSet<_> = self
+            .exclude_selectors
+            .iter()
+            .flat_map(|selector| element.select(selector))
+            .map(|matched| matched.id())
+            .collect();
+
+        // Each text with the heading or list item it is part of, if any
         let mut text_parts = Vec::new();
 
         for node in element.descendants() {
@@ -76,26 +382,190 @@ impl TextExtractor {
             }
 
             if let Some(text_node) = node.value().as_text() {
-                let text = text_node.trim();
+                if !excluded.is_empty() && node.ancestors().any(|a| excluded.contains(&a.id())) {
+                    continue;
+                }
+                // Line breaks are kept for the blocks around headings and list items only
+                let text = text_node.split_whitespace().collect::<Vec<_>>().join(" ");
                 if !text.is_empty() {
-                    text_parts.push(text.to_string());
+                    let block = node
+                        .ancestors()
+                        .filter_map(ElementRef::wrap)
+                        .find_map(|ancestor| Some((ancestor.id(), block_prefix(ancestor)?)));
+                    text_parts.push((block, text));
                 }
             }
         }
 
-        text_parts.join(" ")
+        // Headings and list items become markdown on lines of their own. Text
+        // of an item after a list nested in it continues that item on a line
+        // indented to its text, without a second marker.
+        let mut content = String::new();
+        let mut current_block = None;
+        let mut started = HashSet::new();
+        for (block, text) in text_parts {
+            let id = block.as_ref().map(|(id, _)| *id);
+            if id != current_block {
+                if current_block.is_some() || block.is_some() {
+                    content.push('\n');
+                }
+                if let Some((id, prefix)) = &block {
+                    if started.insert(*id) {
+                        content.push_str(prefix);
+                    } else {
+                        content.push_str(&" ".repeat(prefix.len()));
+                    }
+                }
+                current_block = id;
+            }
+            if !content.is_empty() && !content.ends_with('\n') {
+                content.push(' ');
+            }
+            content.push_str(&text);
+        }
+        content
     }
 
+    /// Collapse whitespace within each line and put a blank line between
+    /// lines, so headings stay blocks of their own. List items keep their
+    /// indentation and stay together as one list, as do indented lines
+    /// continuing an item.
     fn clean_text(&self, text: &str) -> String {
-        // Single pass optimization: combine operations
-        text.lines()
-            .map(|line| line.trim())
-            .filter(|line| !line.is_empty())
-            .collect::<Vec<_>>()
-            .join("\n")
-            .replace('\t', " ")
-            .split_whitespace()
-            .collect::<Vec<_>>()
-            .join(" ")
+        let mut cleaned = String::new();
+        let mut in_list = false;
+        for line in text.lines() {
+            let collapsed = line.split_whitespace().collect::<Vec<_>>().join(" ");
+            if collapsed.is_empty() {
+                continue;
+            }
+            let is_item = is_list_item(&collapsed);
+            let continues_item = in_list && !is_item && line.starts_with(' ');
+            if !cleaned.is_empty() {
+                cleaned.push_str(if in_list && is_item { "\n" } else { "\n\n" });
+            }
+            if is_item || continues_item {
+                cleaned.push_str(&line[..line.len() - line.trim_start_matches(' ').len()]);
+            }
+            cleaned.push_str(&collapsed);
+            in_list = is_item || continues_item;
+        }
+        cleaned
+    }
+}
+
+/// Markdown starting the line of a heading or list item element: `##` for an
+/// `<h2>`, `-` or `3.` for an `<li>`, indented under the items it is nested in
+fn block_prefix(element: ElementRef) -> Option<String> {
+    if let Some(level) = heading_level(element.value().name()) {
+        return Some("#".repeat(level));
+    }
+    if element.value().name() != "li" {
+        return None;
+    }
+    let indent: usize = element
+        .ancestors()
+        .filter_map(ElementRef::wrap)
+        .filter(|ancestor| ancestor.value().name() == "li")
+        .map(|item| list_marker(item).len() + 1)
+        .sum();
+    Some(format!("{}{}", " ".repeat(indent), list_marker(element)))
+}
+
+/// `1.`, `2.`, ... for items of an `<ol>` (from its `start`), `-` otherwise
+fn list_marker(item: ElementRef) -> String {
+    let Some(list) = item.parent().and_then(ElementRef::wrap) else {
+        return "-".to_string();
+    };
+    if list.value().name() != "ol" {
+        return "-".to_string();
+    }
+    let start: usize = list
+        .value()
+        .attr("start")
+        .and_then(|start| start.trim().parse().ok())
+        .unwrap_or(1);
+    let before = item
+        .prev_siblings()
+        .filter_map(ElementRef::wrap)
+        .filter(|sibling| sibling.value().name() == "li")
+        .count();
+    format!("{}.", start + before)
+}
+
+/// Whether `line` (without its indentation) starts like a markdown list item
+fn is_list_item(line: &str) -> bool {
+    if line.starts_with("- ") {
+        return true;
+    }
+    let digits = line.chars().take_while(char::is_ascii_digit).count();
+    digits > 0 && line[digits..].starts_with(". ")
+}
+
+/// Markdown header level of an `<h1>`–`<h6>` element name
+fn heading_level(name: &str) -> Option<usize> {
+    match name {
+        "h1" => Some(1),
+        "h2" => Some(2),
+        "h3" => Some(3),
+        "h4" => Some(4),
+        "h5" => Some(5),
+        "h6" => Some(6),
+        _ => None,
+    }
+}
+
+/// Text of the page's `<title>`, or "Untitled"
+pub fn page_title(html: &str) -> String {
+    let document = Html::parse_document(html);
+    let title_selector = Selector::parse("title").unwrap();
+
+    document
+        .select(&title_selector)
+        .next()
+        .map(|el| el.text().collect::<String>())
+        .unwrap_or_else(|| "Untitled".to_string())
+        .trim()
+        .to_string()
+}
+
+/// Parse an RFC 3339 timestamp or a bare `YYYY-MM-DD` date
+fn parse_date(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+    if let Ok(date) = DateTime::parse_from_rfc3339(value) {
+        return Some(date.with_timezone(&Utc));
+    }
+    let date = NaiveDate::parse_from_str(value.get(..10)?, "%Y-%m-%d").ok()?;
+    Some(date.and_hms_opt(0, 0, 0)?.and_utc())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn extract(body: &str) -> String {
+        let html = format!("<html><body><main>{}</main></body></html>", body);
+        TextExtractor::new()
+            .with_title(false)
+            .extract_text(&html, "https://example.com/")
+            .unwrap()
+            .text
+    }
+
+    #[test]
+    fn headings_become_markdown_headers() {
+        let text = extract(
+            "<h1>Title</h1><p>Intro text.</p><h2>Section</h2><p>Body.</p>\
+             <h3>Detail</h3><p>More.</p>",
+        );
+        assert_eq!(
+            text,
+            "# Title\n\nIntro text.\n\n## Section\n\nBody.\n\n### Detail\n\nMore."
+        );
+    }
+
+    #[test]
+    fn nested_list_text_continues_its_item() {
+        let text = extract("<ul><li>A<ul><li>B</li></ul>C</li><li>D</li></ul>");
+        assert_eq!(text, "- A\n  - B\n\n  C\n- D");
     }
 }
@@ -1,35 +1,85 @@
+use crate::common::text::{display_width, truncate_at_word_boundary};
+use chrono::{DateTime, Utc};
 use std::collections::VecDeque;
 
-const MAX_HISTORY_SIZE: usize = 100;
+/// Default number of pages kept in the back/forward history
+pub const DEFAULT_HISTORY_SIZE: usize = 100;
+/// Default longest page title kept in the history, in columns
+pub const DEFAULT_MAX_TITLE_CHARS: usize = 200;
+/// Pages dropped from the forward branch that can still be reopened
+const CLOSED_CAPACITY: usize = 10;
 
 #[derive(Debug, Clone)]
 pub struct HistoryEntry {
     pub url: String,
     pub title: String,
+    pub visited_at: DateTime<Utc>,
 }
 
 pub struct History {
     entries: VecDeque<HistoryEntry>,
     current_index: Option<usize>,
+    capacity: usize,
+    /// Longer titles are cut, so a pathological `<title>` can't bloat the
+    /// history or break the history list's layout
+    max_title_chars: usize,
+    /// Entries cut off the forward branch, most recently dropped last
+    closed: Vec<HistoryEntry>,
 }
 
 impl History {
-    pub fn new() -> Self {
+    /// History keeping at most `capacity` pages (at least one); the oldest
+    /// are dropped first
+    pub fn with_capacity(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
         Self {
-            entries: VecDeque::with_capacity(MAX_HISTORY_SIZE),
+            entries: VecDeque::with_capacity(capacity.min(DEFAULT_HISTORY_SIZE)),
             current_index: None,
+            capacity,
+            max_title_chars: DEFAULT_MAX_TITLE_CHARS,
+            closed: Vec::new(),
         }
     }
 
+    /// Cut titles longer than `max_chars` columns (at least one) to that
+    /// width, ellipsis included
+    pub fn with_max_title_chars(mut self, max_chars: usize) -> Self {
+        self.max_title_chars = max_chars.max(1);
+        self
+    }
+
+    /// Make `url` the current page. Loading the current page again (a
+    /// refresh, or going back or forward to it) updates its entry instead.
     pub fn add(&mut self, url: String, title: String) {
+        let title = shorten_title(&title, self.max_title_chars);
+        if let Some(entry) = self
+            .current_index
+            .and_then(|i| self.entries.get_mut(i))
+            .filter(|entry| entry.url == url)
+        {
+            entry.title = title;
+            entry.visited_at = Utc::now();
+            return;
+        }
+
         if let Some(current) = self.current_index {
-            self.entries.truncate(current + 1);
+            // The page right after the current one ends up on top of the stack
+            let dropped: Vec<_> = self.entries.drain(current + 1..).rev().collect();
+            self.closed.extend(dropped);
+        }
+        self.closed.retain(|entry| entry.url != url);
+        if self.closed.len() > CLOSED_CAPACITY {
+            self.closed.drain(..self.closed.len() - CLOSED_CAPACITY);
         }
 
-        self.entries.push_back(HistoryEntry { url, title });
+        self.entries.push_back(HistoryEntry {
+            url,
+            title,
+            visited_at: Utc::now(),
+        });
         self.current_index = Some(self.entries.len() - 1);
 
-        if self.entries.len() > MAX_HISTORY_SIZE {
+        while self.entries.len() > self.capacity {
             self.entries.pop_front();
             if let Some(ref mut current) = self.current_index {
                 *current = current.saturating_sub(1);
@@ -68,7 +118,155 @@ impl History {
         self.current_index.and_then(|i| self.entries.get(i))
     }
 
+    /// Position of the current entry in `list()`
+    pub fn current_index(&self) -> Option<usize> {
+        self.current_index
+    }
+
+    /// Take the most recently dropped forward entry, to reopen it
+    pub fn pop_closed(&mut self) -> Option<HistoryEntry> {
+        self.closed.pop()
+    }
+
     pub fn list(&self) -> Vec<&HistoryEntry> {
         self.entries.iter().collect()
     }
 }
+
+/// `title` cut at a word boundary to at most `max_chars` columns, the
+/// ellipsis marking the cut included
+pub fn shorten_title(title: &str, max_chars: usize) -> String {
+    if display_width(title) <= max_chars {
+        return title.to_string();
+    }
+    truncate_at_word_boundary(title, max_chars.saturating_sub(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn huge_titles_are_cut_with_an_ellipsis() {
+        let mut history = History::with_capacity(10).with_max_title_chars(200);
+        let title = "word ".repeat(2000);
+        history.add("https://example.com/".to_string(), title);
+
+        let stored = &history.current().unwrap().title;
+        assert!(stored.ends_with('…'), "{}", stored);
+        assert!(display_width(stored) <= 200, "{}", display_width(stored));
+        assert!(stored.starts_with("word word"));
+    }
+
+    #[test]
+    fn refreshing_a_page_cuts_its_new_title_too() {
+        let mut history = History::with_capacity(10).with_max_title_chars(10);
+        history.add("https://example.com/".to_string(), "Short".to_string());
+        history.add("https://example.com/".to_string(), "x".repeat(5000));
+
+        assert_eq!(history.list().len(), 1);
+        assert_eq!(
+            history.current().unwrap().title,
+            format!("{}…", "x".repeat(9))
+        );
+    }
+
+    fn visit(history: &mut History, pages: &[&str]) {
+        for page in pages {
+            history.add(format!("https://example.com/{}", page), page.to_string());
+        }
+    }
+
+    fn titles(entries: impl IntoIterator<Item = HistoryEntry>) -> Vec<String> {
+        entries.into_iter().map(|entry| entry.title).collect()
+    }
+
+    #[test]
+    fn truncating_the_forward_branch_fills_closed() {
+        let mut history = History::with_capacity(10);
+        visit(&mut history, &["a", "b", "c", "d"]);
+        history.go_back();
+        history.go_back();
+        visit(&mut history, &["e"]);
+
+        assert_eq!(titles(history.list().into_iter().cloned()), ["a", "b", "e"]);
+        // The page right after the one navigated from comes back first
+        let reopened = std::iter::from_fn(|| history.pop_closed());
+        assert_eq!(titles(reopened), ["c", "d"]);
+    }
+
+    #[test]
+    fn pop_closed_is_last_in_first_out() {
+        let mut history = History::with_capacity(10);
+        visit(&mut history, &["a", "b"]);
+        history.go_back();
+        visit(&mut history, &["c"]);
+        history.go_back();
+        visit(&mut history, &["d"]);
+
+        assert_eq!(history.pop_closed().unwrap().title, "c");
+        assert_eq!(history.pop_closed().unwrap().title, "b");
+        assert!(history.pop_closed().is_none());
+    }
+
+    #[test]
+    fn revisiting_a_closed_page_takes_it_off_the_stack() {
+        let mut history = History::with_capacity(10);
+        visit(&mut history, &["a", "b"]);
+        history.go_back();
+        visit(&mut history, &["c", "b"]);
+
+        assert!(history.pop_closed().is_none());
+    }
+
+    #[test]
+    fn closed_keeps_the_most_recent_entries_up_to_its_capacity() {
+        let mut history = History::with_capacity(100);
+        let pages: Vec<String> = (0..CLOSED_CAPACITY + 5).map(|i| i.to_string()).collect();
+        history.add("https://example.com/".to_string(), "start".to_string());
+        for page in &pages {
+            visit(&mut history, &[page]);
+            history.go_back();
+        }
+        visit(&mut history, &["end"]);
+
+        let reopened = titles(std::iter::from_fn(|| history.pop_closed()));
+        let expected: Vec<String> = pages.iter().rev().take(CLOSED_CAPACITY).cloned().collect();
+        assert_eq!(reopened, expected);
+    }
+
+    #[test]
+    fn capacity_one_keeps_only_the_current_page() {
+        let mut history = History::with_capacity(1);
+        visit(&mut history, &["a", "b", "c"]);
+
+        assert_eq!(titles(history.list().into_iter().cloned()), ["c"]);
+        assert_eq!(history.current_index(), Some(0));
+        assert!(!history.can_go_back());
+        assert!(!history.can_go_forward());
+    }
+
+    #[test]
+    fn capacity_zero_counts_as_one() {
+        let mut history = History::with_capacity(0);
+        visit(&mut history, &["a", "b"]);
+
+        assert_eq!(titles(history.list().into_iter().cloned()), ["b"]);
+    }
+
+    #[test]
+    fn large_capacity_keeps_every_page_and_drops_the_oldest_beyond_it() {
+        let capacity = 5000;
+        let mut history = History::with_capacity(capacity);
+        for i in 0..capacity + 10 {
+            visit(&mut history, &[&i.to_string()]);
+        }
+
+        let list = history.list();
+        assert_eq!(list.len(), capacity);
+        assert_eq!(list[0].title, "10");
+        assert_eq!(history.current_index(), Some(capacity - 1));
+        assert_eq!(history.current().unwrap().title, (capacity + 9).to_string());
+        assert_eq!(history.go_back().unwrap().title, (capacity + 8).to_string());
+    }
+}
@@ -1,11 +1,17 @@
+use crate::common::text::take_width;
 use anyhow::Result;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
 use scraper::{Html, Selector};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use tracing::debug;
 use url::Url;
 
 const MIN_LINK_TEXT_LENGTH: usize = 2;
 const MAX_URL_LENGTH: usize = 200;
 const MAX_LINK_TEXT_LENGTH: usize = 100;
+/// Marks the text of links to a section of the current page
+pub const ANCHOR_LABEL: &str = "§ ";
 
 #[derive(Debug, Clone)]
 pub struct Link {
@@ -14,26 +20,86 @@ pub struct Link {
     pub index: usize,
 }
 
-pub struct LinkExtractor;
+pub struct LinkExtractor {
+    /// Keep at most this many links per host, `None` for no limit. Links are
+    /// counted by host only, not by path prefix within a host.
+    max_links_per_host: Option<usize>,
+    /// Keep links to sections of the page itself (`#anchor`)
+    keep_anchors: bool,
+    /// Schemes of links bbow can't load (e.g. `mailto`) kept for the system to open
+    link_schemes: Vec<String>,
+}
 
 impl LinkExtractor {
     pub fn new() -> Self {
-        Self
+        Self {
+            max_links_per_host: None,
+            keep_anchors: false,
+            link_schemes: Vec::new(),
+        }
+    }
+
+    pub fn with_anchor_links(mut self, keep: bool) -> Self {
+        self.keep_anchors = keep;
+        self
+    }
+
+    /// Keep links of these non-web schemes, which are otherwise dropped
+    pub fn with_link_schemes(mut self, schemes: &[String]) -> Self {
+        self.link_schemes = schemes
+            .iter()
+            .map(|scheme| scheme.trim_end_matches(':').to_lowercase())
+            .collect();
+        self
+    }
+
+    pub fn with_max_links_per_host(mut self, max: Option<usize>) -> Self {
+        self.max_links_per_host = max;
+        self
     }
 
     pub fn extract_links(&self, html: &str, base_url: &str) -> Result<Vec<Link>> {
         let document = Html::parse_document(html);
         let link_selector = Selector::parse("a[href]").unwrap();
-        let base = Url::parse(base_url)?;
+        let page_url = without_fragment(Url::parse(base_url)?);
+        let base = document_base(&document, page_url.clone());
 
         let mut links = Vec::new();
         let mut seen_urls = HashSet::new();
+        let mut per_host: HashMap<String, usize> = HashMap::new();
         let mut index = 1;
+        let mut candidates = 0;
 
         for element in document.select(&link_selector) {
+            candidates += 1;
             if let Some(href) = element.value().attr("href") {
                 if let Ok(absolute_url) = base.join(href) {
-                    if !absolute_url.scheme().starts_with("http") {
+                    // Labeled by scheme, since following one hands it to the system
+                    if let Some(scheme) = external_scheme(&absolute_url) {
+                        let url_str = absolute_url.to_string();
+                        if self.link_schemes.iter().any(|s| s == scheme)
+                            && seen_urls.insert(url_str.clone())
+                        {
+                            let text = self.clean_link_text(&self.extract_link_text(element));
+                            links.push(Link {
+                                text: if text.len() < MIN_LINK_TEXT_LENGTH {
+                                    url_str.clone()
+                                } else {
+                                    format!("{}: {}", scheme, text)
+                                },
+                                url: url_str,
+                                index,
+                            });
+                            index += 1;
+                        }
+                        continue;
+                    }
+
+                    // Links to a section of this page are dropped unless asked for
+                    let same_page = absolute_url.fragment().is_some()
+                        && without_fragment(absolute_url.clone()) == page_url;
+                    let is_anchor = same_page && absolute_url.fragment() != Some("");
+                    if same_page && !(is_anchor && self.keep_anchors) {
                         continue;
                     }
 
@@ -47,8 +113,24 @@ impl LinkExtractor {
                         continue;
                     }
 
+                    // Keep the first K links per host so one section can't crowd out the rest
+                    let host = absolute_url.host_str().unwrap_or_default().to_string();
+                    let host_count = per_host.entry(host).or_insert(0);
+                    if self
+                        .max_links_per_host
+                        .is_some_and(|max| *host_count >= max)
+                    {
+                        continue;
+                    }
+                    *host_count += 1;
+
+                    let text = self.clean_link_text(&text);
                     links.push(Link {
-                        text: self.clean_link_text(&text),
+                        text: if is_anchor {
+                            format!("{}{}", ANCHOR_LABEL, text)
+                        } else {
+                            text
+                        },
                         url: url_str,
                         index,
                     });
@@ -58,6 +140,13 @@ impl LinkExtractor {
             }
         }
 
+        debug!(
+            candidates,
+            kept = links.len(),
+            hosts = per_host.len(),
+            "extracted links from {}",
+            base_url
+        );
         Ok(links)
     }
 
@@ -182,10 +271,7 @@ impl LinkExtractor {
     }
 
     fn clean_link_text(&self, text: &str) -> String {
-        text.trim()
-            .chars()
-            .take(MAX_LINK_TEXT_LENGTH)
-            .collect::<String>()
+        take_width(text.trim(), MAX_LINK_TEXT_LENGTH)
             .lines()
             .map(|line| line.trim())
             .filter(|line| !line.is_empty())
@@ -193,3 +279,211 @@ impl LinkExtractor {
             .join(" ")
     }
 }
+
+fn without_fragment(mut url: Url) -> Url {
+    url.set_fragment(None);
+    url
+}
+
+/// The URL relative links resolve against: the first `<base href>`, itself
+/// resolved against the page URL, or the page URL when there is none.
+/// Bases that are not http(s) (e.g. `javascript:`) are ignored.
+pub fn document_base(document: &Html, page_url: Url) -> Url {
+    let selector = Selector::parse("base[href]").unwrap();
+    document
+        .select(&selector)
+        .next()
+        .and_then(|base| page_url.join(base.value().attr("href")?.trim()).ok())
+        .filter(|base| matches!(base.scheme(), "http" | "https"))
+        .unwrap_or(page_url)
+}
+
+/// The link to a section of the page (kept by `--anchor-links`) matching
+/// `heading` by its text or its fragment's words: exactly if one does, else
+/// one containing the other
+pub fn anchor_for_heading<'a>(links: &'a [Link], heading: &str) -> Option<&'a Link> {
+    let words = |text: &str| {
+        text.split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .map(str::to_lowercase)
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    let heading = words(heading);
+    if heading.is_empty() {
+        return None;
+    }
+    let anchors: Vec<(&Link, [String; 2])> = links
+        .iter()
+        .filter_map(|link| {
+            let text = link.text.strip_prefix(ANCHOR_LABEL)?;
+            let fragment = Url::parse(&link.url).ok()?.fragment()?.to_string();
+            Some((link, [words(text), words(&fragment)]))
+        })
+        .collect();
+    let exact = anchors.iter().find(|(_, names)| names.contains(&heading));
+    exact
+        .or_else(|| {
+            anchors.iter().find(|(_, names)| {
+                names.iter().any(|name| {
+                    name.len() > MIN_LINK_TEXT_LENGTH
+                        && (name.contains(&heading) || heading.contains(name.as_str()))
+                })
+            })
+        })
+        .map(|(link, _)| *link)
+}
+
+/// Scheme of a link bbow can't load itself, `None` for web links (including
+/// `.onion` hosts, which load through the `proxies` rules)
+fn external_scheme(url: &Url) -> Option<&str> {
+    match url.scheme() {
+        "http" | "https" => None,
+        scheme => Some(scheme),
+    }
+}
+
+/// Whether `url` is handed to the system's handler instead of loaded, like
+/// `mailto:` links
+pub fn opens_externally(url: &str) -> bool {
+    Url::parse(url).is_ok_and(|url| external_scheme(&url).is_some())
+}
+
+/// How many of `links` stay on the host of `page_url` and how many lead
+/// elsewhere; a `www.` prefix doesn't make a host different
+pub fn internal_external(page_url: &str, links: &[Link]) -> (usize, usize) {
+    let host = |url: &str| {
+        let url = Url::parse(url).ok()?;
+        let host = url.host_str()?.to_lowercase();
+        Some(
+            host.strip_prefix("www.")
+                .map(str::to_string)
+                .unwrap_or(host),
+        )
+    };
+    let page_host = host(page_url);
+    let internal = links
+        .iter()
+        .filter(|link| page_host.is_some() && host(&link.url) == page_host)
+        .count();
+    (internal, links.len() - internal)
+}
+
+/// Indices of links whose text fuzzy-matches `query`, best match first.
+/// Ties keep page order so results stay stable while typing.
+pub fn fuzzy_matches(links: &[Link], query: &str) -> Vec<usize> {
+    if query.is_empty() {
+        return Vec::new();
+    }
+
+    let matcher = SkimMatcherV2::default().ignore_case();
+    let mut scored: Vec<(usize, i64)> = links
+        .iter()
+        .enumerate()
+        .filter_map(|(i, link)| {
+            matcher
+                .fuzzy_match(&link.text, query)
+                .map(|score| (i, score))
+        })
+        .collect();
+
+    scored.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
+    scored.into_iter().map(|(i, _)| i).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn base_of(head: &str) -> String {
+        let html = format!("<html><head>{}</head><body></body></html>", head);
+        let page = Url::parse("https://example.com/docs/guide/page.html").unwrap();
+        document_base(&Html::parse_document(&html), page).to_string()
+    }
+
+    #[test]
+    fn relative_base_resolves_against_the_page() {
+        assert_eq!(
+            base_of(r#"<base href="../assets/">"#),
+            "https://example.com/docs/assets/"
+        );
+        assert_eq!(
+            base_of(r#"<base href="https://cdn.example.org/v2/">"#),
+            "https://cdn.example.org/v2/"
+        );
+    }
+
+    #[test]
+    fn non_http_base_is_ignored() {
+        assert_eq!(
+            base_of(r#"<base href="javascript:void(0)">"#),
+            "https://example.com/docs/guide/page.html"
+        );
+    }
+
+    #[test]
+    fn page_url_is_the_base_without_a_base_element() {
+        assert_eq!(base_of(""), "https://example.com/docs/guide/page.html");
+        assert_eq!(
+            base_of("<base target=\"_blank\">"),
+            "https://example.com/docs/guide/page.html"
+        );
+    }
+
+    /// A page linking `per_host` times to each of `hosts`, interleaved
+    fn crowded_page(hosts: &[&str], per_host: usize) -> String {
+        let mut body = String::new();
+        for i in 0..per_host {
+            for host in hosts {
+                body.push_str(&format!(
+                    "<a href=\"https://{}/section/{}\">Article number {} on {}</a>",
+                    host, i, i, host
+                ));
+            }
+        }
+        format!("<html><body>{}</body></html>", body)
+    }
+
+    #[test]
+    fn max_links_per_host_keeps_the_first_links_of_each_host() {
+        let html = crowded_page(&["example.com", "other.org"], 20);
+        let links = LinkExtractor::new()
+            .with_max_links_per_host(Some(3))
+            .extract_links(&html, "https://example.com/")
+            .unwrap();
+
+        let urls: Vec<&str> = links.iter().map(|link| link.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            [
+                "https://example.com/section/0",
+                "https://other.org/section/0",
+                "https://example.com/section/1",
+                "https://other.org/section/1",
+                "https://example.com/section/2",
+                "https://other.org/section/2",
+            ]
+        );
+        let indexes: Vec<usize> = links.iter().map(|link| link.index).collect();
+        assert_eq!(indexes, [1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn links_are_not_capped_by_default() {
+        let html = crowded_page(&["example.com"], 50);
+        let links = LinkExtractor::new()
+            .extract_links(&html, "https://example.com/")
+            .unwrap();
+        assert_eq!(links.len(), 50);
+    }
+
+    #[test]
+    fn links_resolve_against_the_base() {
+        let html = r#"<html><head><base href="/root/"></head><body>
+            <a href="intro.html">Introduction to the guide</a></body></html>"#;
+        let links = LinkExtractor::new()
+            .extract_links(html, "https://example.com/docs/page.html")
+            .unwrap();
+        assert_eq!(links[0].url, "https://example.com/root/intro.html");
+    }
+}
@@ -1,41 +1,517 @@
+mod bookmarks;
 mod browser;
+mod cache;
 mod client;
 mod common;
+mod config;
+mod crawl;
+mod domains;
+mod export;
 mod extractor;
 mod history;
+mod http;
 mod links;
 mod openai;
+mod paths;
+mod record;
+mod settings;
+mod sitemap;
 mod ui;
 
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, Context, Result};
 use browser::Browser;
 use clap::Parser;
+use config::{
+    Config, FileConfig, DEFAULT_FALLBACK_TEXT_LENGTH, DEFAULT_MIN_CONTENT_WORDS,
+    DEFAULT_QUICK_LOOK_CHARS,
+};
+use http::Http;
+use std::{
+    fs::{File, OpenOptions},
+    io::IsTerminal,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::Duration,
+};
+use tracing::Level;
 
 // Import UI traits and implementations
-use ui::{default::UI as DefaultUI, expi::ExpiUI, jony::JonyUI, robocop::RobocopUI, UIInterface};
+use ui::{
+    default::UI as DefaultUI, expi::ExpiUI, jony::JonyUI, light::LightUI, palette::Palette,
+    robocop::RobocopUI, UIInterface,
+};
 
 #[derive(Parser)]
 #[command(name = "bbow", about = "A CLI browser with AI-powered summaries")]
 struct Args {
-    #[arg(help = "Initial URL to visit")]
+    #[arg(help = "Initial URL to visit, or - to read HTML from stdin")]
     url: Option<String>,
 
     #[arg(long, help = "UI theme to use", default_value = "default")]
     ui: String,
+
+    #[arg(
+        short,
+        long,
+        action = clap::ArgAction::Count,
+        help = "Increase log verbosity (-v, -vv, -vvv)"
+    )]
+    verbose: u8,
+
+    #[arg(long, help = "Write logs to this file instead of stderr")]
+    log_file: Option<PathBuf>,
+
+    #[arg(
+        long,
+        help = "Characters of raw page text to show when summarization fails",
+        default_value_t = DEFAULT_FALLBACK_TEXT_LENGTH
+    )]
+    fallback_text_length: usize,
+
+    #[arg(
+        long,
+        help = "Start in quick look mode: summarize only the start of each page (Q toggles)"
+    )]
+    quick_look: bool,
+
+    #[arg(
+        long,
+        value_name = "CHARS",
+        help = "Characters of page text summarized in quick look mode",
+        default_value_t = DEFAULT_QUICK_LOOK_CHARS,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    quick_look_chars: usize,
+
+    #[arg(
+        long,
+        help = "Summarize as a nested bullet outline of the page, without prose (O toggles)"
+    )]
+    outline: bool,
+
+    #[arg(
+        long,
+        help = "Have the LLM explain JSON responses as they load, above the pretty-printed JSON"
+    )]
+    explain_json: bool,
+
+    #[arg(
+        long,
+        value_name = "N",
+        help = "Show pages under N words as raw text instead of summarizing (0 = always summarize)",
+        default_value_t = DEFAULT_MIN_CONTENT_WORDS
+    )]
+    min_content_words: usize,
+
+    #[arg(long, help = "Ask for confirmation before quitting")]
+    confirm_quit: bool,
+
+    #[arg(
+        long,
+        help = "Scroll back to the top when refreshing a page (default: keep your place)"
+    )]
+    refresh_to_top: bool,
+
+    #[arg(
+        long,
+        value_name = "N",
+        help = "Show at most N links per page (off by default)",
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    max_links: Option<usize>,
+
+    #[arg(
+        long,
+        value_name = "N",
+        help = "Keep at most N links per host (off by default)",
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    max_links_per_host: Option<usize>,
+
+    #[arg(
+        long,
+        help = "Keep links to sections of the current page (#anchor) and scroll to them"
+    )]
+    anchor_links: bool,
+
+    #[arg(
+        long = "link-scheme",
+        value_name = "SCHEME",
+        help = "Keep links of this scheme (mailto, tel, magnet...) and open them with \
+                the system's handler (repeatable)"
+    )]
+    link_schemes: Vec<String>,
+
+    #[arg(long, help = "Offer URL suggestions without checking they are reachable")]
+    no_verify_suggestions: bool,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Config file to use (default: ~/.config/bbow/config.toml)"
+    )]
+    config: Option<PathBuf>,
+
+    #[arg(
+        long,
+        help = "Leave the page title out of the summarization input (still shown in the header)"
+    )]
+    no_title_in_summary: bool,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Color palette file (TOML or JSON) for the light layout"
+    )]
+    theme_file: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "PERCENT",
+        help = "Percent of the page width given to the summary, 40-90 (default: per theme)",
+        value_parser = clap::builder::RangedU64ValueParser::<u16>::new().range(40..=90)
+    )]
+    split_ratio: Option<u16>,
+
+    #[arg(
+        long,
+        value_name = "COLUMNS",
+        help = "Widest the summary text wraps; wider panes centre it (default: the pane width)",
+        value_parser = clap::builder::RangedU64ValueParser::<u16>::new()
+            .range(common::ui::MIN_TEXT_WIDTH as u64..)
+    )]
+    max_width: Option<u16>,
+
+    #[arg(
+        long,
+        help = "Put a blank line between the summary's paragraphs and list items"
+    )]
+    line_spacing: bool,
+
+    #[arg(
+        long,
+        value_name = "CHARS",
+        help = "Show longer summaries cut to their first lines until v expands them"
+    )]
+    summary_preview: Option<usize>,
+
+    #[arg(
+        long,
+        help = "After loading the initial URL, list its site's sitemap in the links panel"
+    )]
+    sitemap: bool,
+
+    #[arg(
+        long,
+        value_name = "N",
+        help = "Maximum number of HTTP requests in flight at once",
+        default_value_t = http::DEFAULT_MAX_CONCURRENCY,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    max_concurrency: usize,
+
+    #[arg(
+        long,
+        value_name = "MS",
+        help = "Least time between two requests to the same host, in milliseconds",
+        default_value_t = 0
+    )]
+    host_delay_ms: u64,
+
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        help = "Fetch and summarize the page shown again every SECONDS, for live pages",
+        value_parser = clap::builder::RangedU64ValueParser::<u64>::new().range(1..)
+    )]
+    auto_refresh: Option<u64>,
+
+    #[arg(
+        long,
+        value_name = "PAGES",
+        help = "Instead of browsing, summarize up to PAGES pages of the URL's site and print them",
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    crawl: Option<usize>,
+
+    #[arg(
+        long,
+        value_name = "N",
+        help = "Summarize up to N pages at once with --crawl",
+        default_value_t = crawl::DEFAULT_SUMMARIZE_CONCURRENCY,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    summarize_concurrency: usize,
+
+    #[arg(
+        long,
+        help = "Show the page's lead image in the header (kitty, iTerm2 and WezTerm only)"
+    )]
+    images: bool,
+
+    #[arg(
+        long,
+        help = "Don't make links clickable with OSC 8 sequences (for terminals that print them)"
+    )]
+    no_osc8: bool,
+
+    #[arg(
+        long,
+        help = "Show ASCII instead of emoji in titles and labels (for fonts without them)"
+    )]
+    no_emoji: bool,
+
+    #[arg(
+        long,
+        help = "Never send page content to an AI service: show page text unsummarized. \
+                Only pages, sitemaps and crawls you ask for are fetched, without address \
+                probes or images"
+    )]
+    local_only: bool,
+
+    #[arg(
+        long,
+        help = "After each page loads, show which selector found its main text and what it \
+                yielded (any key returns to the page)"
+    )]
+    debug_extract: bool,
+
+    #[arg(
+        long,
+        value_name = "DIR",
+        help = "Directory for HTML exports made with 'e' (default: current directory)"
+    )]
+    export_dir: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "URL",
+        help = "Search URL for text typed in place of an address, {} marking the query \
+                (default: DuckDuckGo)"
+    )]
+    search_engine: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "DIR",
+        help = "Save each page's HTML, extracted text, and LLM prompts and responses in a \
+                timestamped directory under DIR, for bug reports (the API key is redacted)"
+    )]
+    record: Option<PathBuf>,
+
+    #[arg(
+        long,
+        help = "Start at the URL prompt (pre-filled with URL, if given) instead of loading a page"
+    )]
+    url_input: bool,
+
+    #[arg(
+        long,
+        value_name = "N",
+        help = "End summaries with the page's first N links as numbered references"
+    )]
+    references: Option<usize>,
+
+    #[arg(
+        long,
+        value_name = "N",
+        help = "Token budget of the second summary when comparing with C (default: current)"
+    )]
+    compare_max_tokens: Option<u32>,
+
+    #[arg(
+        long,
+        value_name = "T",
+        help = "Temperature (0-2) of the second summary when comparing with C (default: current)"
+    )]
+    compare_temperature: Option<f32>,
+
+    #[arg(
+        long = "allow-domain",
+        value_name = "DOMAIN",
+        help = "Only visit this domain (repeatable, *.example.com matches subdomains)"
+    )]
+    allow_domains: Vec<String>,
+
+    #[arg(
+        long = "deny-domain",
+        value_name = "DOMAIN",
+        help = "Never visit this domain (repeatable, *.example.com matches subdomains)"
+    )]
+    deny_domains: Vec<String>,
+
+    #[arg(
+        long,
+        value_name = "TYPES",
+        value_delimiter = ',',
+        help = "MIME types to read, comma-separated (default: text/html); JSON is \
+                pretty-printed, other types are read as plain text"
+    )]
+    accept: Vec<String>,
+
+    #[arg(
+        long,
+        value_name = "VALUE",
+        help = "Accept header sent for pages (default: built from --accept, HTML first)"
+    )]
+    accept_header: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "N",
+        help = "Keep at most N pages in the back/forward history (default: 100)",
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    history_size: Option<usize>,
+
+    #[arg(
+        long,
+        value_name = "N",
+        help = "Keep the last N pages left for going back and forward without refetching \
+                (default: 20, 0 turns this off)"
+    )]
+    cache_entries: Option<usize>,
+
+    #[arg(
+        long,
+        value_name = "URL",
+        help = "Resolve relative links in HTML read from stdin against this URL"
+    )]
+    base_url: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        conflicts_with = "url",
+        help = "Summarize the plain text in this file instead of loading a page"
+    )]
+    text_file: Option<PathBuf>,
 }
 
+/// URL argument that reads the page from stdin instead of fetching it
+const STDIN_ARG: &str = "-";
+
 const AVAILABLE_UIS: &[(&str, &str)] = &[
     ("default", "Original terminal UI with borders and colors"),
     ("expi", "Traditional static browser interface with statistics"),
     ("jony", "Minimalist Jony Ive-inspired UI"),
+    ("light", "Clean bordered UI for light terminal backgrounds"),
     ("robocop", "1987 cyberpunk corporate terminal interface"),
 ];
 
-fn create_ui(ui_name: &str) -> Result<Box<dyn UIInterface>> {
+fn build_config(args: &Args) -> Result<Config> {
+    let file = FileConfig::load(args.config.as_deref())?;
+    if let Some(ratio) = file.split_ratio {
+        if !common::ui::SPLIT_RATIO_RANGE.contains(&ratio) {
+            return Err(anyhow!("split_ratio in the config file must be between 40 and 90"));
+        }
+    }
+    if file.max_width.is_some_and(|w| w < common::ui::MIN_TEXT_WIDTH) {
+        return Err(anyhow!(
+            "max_width in the config file must be at least {}",
+            common::ui::MIN_TEXT_WIDTH
+        ));
+    }
+    if let Some(temperature) = args.compare_temperature {
+        if !(0.0..=2.0).contains(&temperature) {
+            return Err(anyhow!("--compare-temperature must be between 0 and 2"));
+        }
+    }
+    let defaults = Config::default();
+
+    // Command-line switches win over the config file, which wins over the defaults
+    Ok(Config {
+        fallback_text_length: args.fallback_text_length,
+        quick_look: args.quick_look,
+        quick_look_chars: args.quick_look_chars,
+        outline: args.outline,
+        explain_json: args.explain_json || file.explain_json.unwrap_or(defaults.explain_json),
+        min_content_words: args.min_content_words,
+        confirm_quit: args.confirm_quit || file.confirm_quit.unwrap_or(defaults.confirm_quit),
+        keep_scroll_on_refresh: !args.refresh_to_top
+            && file
+                .keep_scroll_on_refresh
+                .unwrap_or(defaults.keep_scroll_on_refresh),
+        max_links: args.max_links,
+        max_links_per_host: args.max_links_per_host,
+        anchor_links: args.anchor_links || file.anchor_links.unwrap_or(defaults.anchor_links),
+        link_schemes: [file.link_schemes, args.link_schemes.clone()].concat(),
+        verify_suggestions: !args.no_verify_suggestions
+            && file.verify_suggestions.unwrap_or(defaults.verify_suggestions),
+        site_selectors: file.site_selectors,
+        content_selectors: file.content_selectors,
+        exclude_selectors: file.exclude_selectors,
+        proxies: file.proxies,
+        allow_domains: [file.allow_domains, args.allow_domains.clone()].concat(),
+        deny_domains: [file.deny_domains, args.deny_domains.clone()].concat(),
+        accept: if args.accept.is_empty() {
+            file.accept.unwrap_or(defaults.accept)
+        } else {
+            args.accept.clone()
+        },
+        accept_header: args.accept_header.clone().or(file.accept_header),
+        summarize_title: !args.no_title_in_summary
+            && file.summarize_title.unwrap_or(defaults.summarize_title),
+        model: file.model.unwrap_or(defaults.model),
+        summary_max_tokens: file.summary_max_tokens.unwrap_or(defaults.summary_max_tokens),
+        temperature: file.temperature.unwrap_or(defaults.temperature),
+        compare_max_tokens: args.compare_max_tokens,
+        compare_temperature: args.compare_temperature,
+        prices: file.prices,
+        history_size: args
+            .history_size
+            .or(file.history_size.filter(|&n| n > 0))
+            .unwrap_or(defaults.history_size),
+        cache_entries: args
+            .cache_entries
+            .or(file.cache_entries)
+            .unwrap_or(defaults.cache_entries),
+        history_title_chars: file
+            .history_title_chars
+            .filter(|&n| n > 0)
+            .unwrap_or(defaults.history_title_chars),
+        config_path: args.config.clone().or(defaults.config_path),
+        references: args.references.filter(|&n| n > 0),
+        export_dir: args.export_dir.clone().or(file.export_dir),
+        search_engine: args
+            .search_engine
+            .clone()
+            .or(file.search_engine)
+            .unwrap_or(defaults.search_engine),
+        split_ratio: args.split_ratio.or(file.split_ratio),
+        max_width: args.max_width.or(file.max_width),
+        line_spacing: args.line_spacing || file.line_spacing.unwrap_or(defaults.line_spacing),
+        summary_preview: args
+            .summary_preview
+            .or(file.summary_preview)
+            .filter(|&chars| chars > 0),
+        // The lead image is a request of its own, which local-only mode avoids
+        images: args.images && !args.local_only,
+        local_only: args.local_only,
+        debug_extract: args.debug_extract,
+        hyperlinks: !args.no_osc8,
+        // Both settled in main: a theme file can switch the theme to light
+        theme: args.ui.clone(),
+        log_file: None,
+        max_concurrency: args.max_concurrency,
+        host_delay: Duration::from_millis(args.host_delay_ms),
+        auto_refresh: args.auto_refresh.map(Duration::from_secs),
+        summarize_concurrency: args.summarize_concurrency,
+        crawl_pages: file.crawl_pages.unwrap_or(defaults.crawl_pages).max(1),
+        record_dir: args.record.clone(),
+    })
+}
+
+fn create_ui(ui_name: &str, palette: Option<Palette>) -> Result<Box<dyn UIInterface>> {
+    if let Some(palette) = palette {
+        return Ok(Box::new(LightUI::with_palette(palette)?));
+    }
+
     match ui_name {
         "default" => Ok(Box::new(DefaultUI::new()?)),
         "expi" => Ok(Box::new(ExpiUI::new()?)),
         "jony" => Ok(Box::new(JonyUI::new()?)),
+        "light" => Ok(Box::new(LightUI::new()?)),
         "robocop" => Ok(Box::new(RobocopUI::new()?)),
         _ => {
             let available: Vec<String> = AVAILABLE_UIS
@@ -51,9 +527,89 @@ fn create_ui(ui_name: &str) -> Result<Box<dyn UIInterface>> {
     }
 }
 
+/// Set up logging for the requested verbosity. Logs never go to the TUI:
+/// they are written to `--log-file`, to stderr when it is redirected, or to
+/// a file in the cache directory when stderr is the terminal. Returns the log
+/// file, if logs go to one.
+fn init_logging(verbose: u8, log_file: Option<&Path>, emoji: bool) -> Result<Option<PathBuf>> {
+    let level = match verbose {
+        0 => return Ok(None),
+        1 => Level::INFO,
+        2 => Level::DEBUG,
+        _ => Level::TRACE,
+    };
+
+    let builder = tracing_subscriber::fmt()
+        .with_max_level(level)
+        .with_target(false)
+        .with_ansi(false);
+
+    let log = match log_file {
+        Some(path) => Some((path.to_path_buf(), open_log_file(path, false)?)),
+        None if std::io::stderr().is_terminal() => Some(default_log_file()?),
+        None => None,
+    };
+
+    match log {
+        Some((path, file)) => {
+            builder.with_writer(Mutex::new(file)).init();
+            println!(
+                "{} Logging to {}",
+                common::emoji::chrome("📝", emoji),
+                path.display()
+            );
+            Ok(Some(path))
+        }
+        None => {
+            builder.with_writer(std::io::stderr).init();
+            Ok(None)
+        }
+    }
+}
+
+/// Open `path` for a fresh log, created readable by the user only since
+/// logs can hold URLs, page text and request details. With `create_new`,
+/// a file already at `path` is an error rather than overwritten, as anyone
+/// can leave one (or a symlink) in a shared directory.
+fn open_log_file(path: &Path, create_new: bool) -> Result<File> {
+    let mut options = OpenOptions::new();
+    if create_new {
+        options.write(true).create_new(true);
+    } else {
+        options.write(true).create(true).truncate(true);
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options
+        .open(path)
+        .map_err(|e| anyhow!("Failed to open log file {}: {}", path.display(), e))
+}
+
+/// `bbow.log` in the cache directory. When there is no cache directory or it
+/// cannot be created, a new `bbow-<pid>.log` in the shared temp directory,
+/// never a file that is already there.
+fn default_log_file() -> Result<(PathBuf, File)> {
+    match paths::cache_dir().filter(|dir| std::fs::create_dir_all(dir).is_ok()) {
+        Some(dir) => {
+            let path = dir.join("bbow.log");
+            let file = open_log_file(&path, false)?;
+            Ok((path, file))
+        }
+        None => {
+            let path = std::env::temp_dir().join(format!("bbow-{}.log", std::process::id()));
+            let file = open_log_file(&path, true)?;
+            Ok((path, file))
+        }
+    }
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     let args = Args::parse();
+    let log_file = init_logging(args.verbose, args.log_file.as_deref(), !args.no_emoji)?;
 
     // Validate UI selection
     if !AVAILABLE_UIS.iter().any(|(name, _)| *name == args.ui) {
@@ -64,13 +620,82 @@ async fn main() -> Result<()> {
         std::process::exit(1);
     }
 
-    println!("🎨 Using '{}' UI theme", args.ui);
+    // Read piped HTML before the UI starts; keys still come from the terminal
+    let piped_html = match args.url.as_deref() {
+        Some(STDIN_ARG) if !args.url_input => Some(
+            std::io::read_to_string(std::io::stdin()).context("Failed to read HTML from stdin")?,
+        ),
+        _ => None,
+    };
+    // Titled by file name, since plain text has no <title>
+    let text_file = match &args.text_file {
+        Some(path) => {
+            let text = std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            let title = path.file_name().map_or_else(
+                || path.display().to_string(),
+                |name| name.to_string_lossy().into_owned(),
+            );
+            Some((title, text))
+        }
+        None => None,
+    };
 
-    let ui = create_ui(&args.ui)?;
-    let mut browser = Browser::new(ui)?;
+    let mut config = build_config(&args)?;
+    config.log_file = log_file;
+    // Built before the UI takes over the terminal so bad proxy rules print cleanly
+    let http = Http::new(config.max_concurrency, &config.proxies)?;
 
-    if let Some(url) = args.url {
+    // A crawl prints its summaries and exits without starting the UI
+    if let Some(max_pages) = args.crawl {
+        let url = args.url.as_deref().context("--crawl needs a URL to start from")?;
+        return crawl::print(&config, http, url, max_pages).await;
+    }
+
+    // A theme file recolors the palette-driven light layout
+    let palette = args.theme_file.as_deref().map(Palette::load).transpose()?;
+    let ui_name = match (&palette, args.ui.as_str()) {
+        (None, name) => name,
+        (Some(_), "default" | "light") => "light",
+        (Some(_), name) => {
+            return Err(anyhow!(
+                "--theme-file only applies to the light UI, not '{}'",
+                name
+            ))
+        }
+    };
+
+    println!(
+        "{} Using '{}' UI theme",
+        common::emoji::chrome("🎨", !args.no_emoji),
+        ui_name
+    );
+    config.theme = ui_name.to_string();
+
+    let mut ui = create_ui(ui_name, palette)?;
+    if let Some(ratio) = config.split_ratio {
+        ui.set_split_ratio(ratio);
+    }
+    if let Some(columns) = config.max_width {
+        ui.set_max_width(columns);
+    }
+    ui.set_line_spacing(config.line_spacing);
+    ui.set_summary_preview(config.summary_preview);
+    ui.set_hyperlinks(config.hyperlinks && common::hyperlink::supported());
+    ui.set_emoji(!args.no_emoji);
+    let mut browser = Browser::new(ui, config, http)?;
+
+    if args.url_input {
+        browser.prefill_url_input(args.url.as_deref());
+    } else if let Some((title, text)) = text_file {
+        browser.show_text(&title, text).await?;
+    } else if let Some(html) = piped_html {
+        browser.show_html(args.base_url.as_deref(), html).await?;
+    } else if let Some(url) = args.url {
         browser.navigate(&url).await?;
+        if args.sitemap {
+            browser.show_sitemap().await?;
+        }
     }
 
     browser.run().await
@@ -1,12 +1,36 @@
+use crate::{http::Http, record::Recorder};
 use anyhow::{anyhow, Result};
-use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::env;
+use std::ops::AddAssign;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
 
-const OPENAI_MODEL: &str = "gpt-4.1-mini";
+pub const OPENAI_MODEL: &str = "gpt-4.1-mini";
 const OPENAI_API_URL: &str = "https://api.openai.com/v1/chat/completions";
-const MAX_TOKENS: u32 = 500;
-const TEMPERATURE: f32 = 0.3;
+pub const MAX_TOKENS: u32 = 500;
+pub const TEMPERATURE: f32 = 0.3;
+/// Completions can take much longer than a page fetch
+const OPENAI_TIMEOUT_SECS: u64 = 120;
+/// How much larger the token budget of an outline is than that of a summary
+const OUTLINE_EXTRA_TOKENS_PERCENT: u32 = 25;
+
+const SUMMARY_SYSTEM_MESSAGE: &str = "You are a helpful assistant that summarizes web content. \
+    Format your response as clean markdown with appropriate headers, bullet points, \
+    **bold** text for emphasis, and *italic* text for quotes or special terms. \
+    Use ## for main sections and - for bullet points. Keep it structured and readable.";
+const OUTLINE_SYSTEM_MESSAGE: &str = "You are a helpful assistant that outlines web content. \
+    Respond only with a nested markdown bullet list: - for bullets, indented by two spaces per \
+    level, following the structure of the content. Keep each bullet to a short phrase. \
+    No headers, no paragraphs, no introduction and no closing remarks.";
+
+const JSON_SYSTEM_MESSAGE: &str = "You are a helpful assistant that explains JSON API \
+    responses to developers. Describe what the response represents, its overall structure, \
+    the meaning of notable fields and any values that stand out, such as errors, pagination \
+    or empty results. Format your response as clean markdown with ## headers and - bullet \
+    points, and put field names in `backticks`.";
 
 #[derive(Serialize)]
 struct OpenAIRequest {
@@ -14,9 +38,18 @@ struct OpenAIRequest {
     messages: Vec<Message>,
     max_tokens: u32,
     temperature: f32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_format: Option<ResponseFormat>,
+}
+
+/// Asks the API for a syntactically valid JSON object instead of free text
+#[derive(Serialize)]
+struct ResponseFormat {
+    #[serde(rename = "type")]
+    kind: &'static str,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize)]
 struct Message {
     role: String,
     content: String,
@@ -25,29 +58,234 @@ struct Message {
 #[derive(Deserialize)]
 struct OpenAIResponse {
     choices: Vec<Choice>,
+    /// Missing from some OpenAI-compatible backends
+    usage: Option<TokenUsage>,
+}
+
+/// Tokens the API billed for one completion
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+pub struct TokenUsage {
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    #[serde(default)]
+    pub total_tokens: u64,
 }
 
+impl TokenUsage {
+    /// `total_tokens` as reported, or the sum of the parts when it was left out
+    pub fn total(&self) -> u64 {
+        if self.total_tokens > 0 {
+            self.total_tokens
+        } else {
+            self.prompt_tokens + self.completion_tokens
+        }
+    }
+}
+
+impl AddAssign for TokenUsage {
+    fn add_assign(&mut self, other: Self) {
+        self.total_tokens = self.total() + other.total();
+        self.prompt_tokens += other.prompt_tokens;
+        self.completion_tokens += other.completion_tokens;
+    }
+}
+
+/// A model's reply and the tokens it took, when the backend reports them
+pub struct Completion {
+    pub text: String,
+    pub usage: Option<TokenUsage>,
+}
+
+/// Completions requested since startup and what they cost
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SessionUsage {
+    pub requests: u32,
+    pub tokens: TokenUsage,
+    /// Estimated from list prices, for the requests whose model has one
+    pub cost_usd: f64,
+    /// Requests left out of `cost_usd`: unknown model or no usage reported
+    pub unpriced_requests: u32,
+}
+
+/// US dollars per million prompt and completion tokens
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct ModelPrice {
+    pub prompt: f64,
+    pub completion: f64,
+}
+
+impl ModelPrice {
+    const fn new(prompt: f64, completion: f64) -> Self {
+        Self { prompt, completion }
+    }
+
+    fn cost(&self, usage: TokenUsage) -> f64 {
+        (usage.prompt_tokens as f64 * self.prompt
+            + usage.completion_tokens as f64 * self.completion)
+            / 1_000_000.0
+    }
+}
+
+/// List prices of common models, used unless the config file sets a price.
+/// More specific names come first, as they share prefixes.
+const PRICES: &[(&str, ModelPrice)] = &[
+    ("gpt-4.1-nano", ModelPrice::new(0.10, 0.40)),
+    ("gpt-4.1-mini", ModelPrice::new(0.40, 1.60)),
+    ("gpt-4.1", ModelPrice::new(2.00, 8.00)),
+    ("gpt-4o-mini", ModelPrice::new(0.15, 0.60)),
+    ("gpt-4o", ModelPrice::new(2.50, 10.00)),
+];
+
 #[derive(Deserialize)]
 struct Choice {
-    message: Message,
+    message: ResponseMessage,
+    /// Why generation stopped: "stop", "length", "content_filter", ...
+    finish_reason: Option<String>,
+}
+
+/// Message of a choice; `content` is null when the model produced nothing
+#[derive(Deserialize)]
+struct ResponseMessage {
+    content: Option<String>,
+}
+
+impl Choice {
+    /// The generated text, unless it is missing or only whitespace
+    fn text(&self) -> Option<&str> {
+        self.message
+            .content
+            .as_deref()
+            .filter(|content| !content.trim().is_empty())
+    }
+
+    /// Whether asking again might produce text: not when the model ran out of
+    /// tokens or its output was filtered
+    fn worth_retrying(&self) -> bool {
+        !matches!(
+            self.finish_reason.as_deref(),
+            Some("length" | "content_filter")
+        )
+    }
+}
+
+/// Extra context about the page that changes how it should be summarized
+#[derive(Debug, Default)]
+pub struct SummaryHints {
+    /// Only part of the content may have been available (e.g. a paywall teaser)
+    pub possibly_partial: bool,
+    /// Publication date of the page as `YYYY-MM-DD`, if known
+    pub published: Option<String>,
+    /// (text, URL) of links the summary may cite inline as `[1]`, `[2]`, ...
+    pub references: Vec<(String, String)>,
+    /// One-off instruction from the reader, e.g. "focus on the security implications"
+    pub instruction: Option<String>,
+    /// The content is only the start of the page, cut short for a quick look
+    pub excerpt: bool,
+    /// Answer with a nested bullet outline only, no prose
+    pub outline: bool,
+    /// The content is a JSON API response, to be explained rather than summarized
+    pub json: bool,
+}
+
+/// Length and sampling options for one summary
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SummaryOptions {
+    pub max_tokens: u32,
+    pub temperature: f32,
+}
+
+impl SummaryOptions {
+    /// Short description such as "500 tokens · temperature 0.3"
+    pub fn label(&self) -> String {
+        format!(
+            "{} tokens · temperature {}",
+            self.max_tokens, self.temperature
+        )
+    }
 }
 
 pub struct OpenAIClient {
-    client: Client,
+    http: Http,
     api_key: String,
+    model: String,
+    max_tokens: u32,
+    temperature: f32,
+    /// Model -> price from the config file, tried before `PRICES`
+    prices: HashMap<String, ModelPrice>,
+    session_usage: Mutex<SessionUsage>,
+    /// Where each request and response is saved with `--record`
+    recorder: Option<Arc<Recorder>>,
 }
 
 impl OpenAIClient {
-    pub fn new() -> Result<Self> {
+    pub fn new(http: Http) -> Result<Self> {
         let api_key = env::var("OPENAI_API_KEY")
             .map_err(|_| anyhow!("OPENAI_API_KEY environment variable not set"))?;
 
         Ok(Self {
-            client: Client::new(),
+            http,
             api_key,
+            model: OPENAI_MODEL.to_string(),
+            max_tokens: MAX_TOKENS,
+            temperature: TEMPERATURE,
+            prices: HashMap::new(),
+            session_usage: Mutex::new(SessionUsage::default()),
+            recorder: None,
         })
     }
 
+    /// Model currently used for summaries
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    /// Length and sampling options currently used for summaries
+    pub fn options(&self) -> SummaryOptions {
+        SummaryOptions {
+            max_tokens: self.max_tokens,
+            temperature: self.temperature,
+        }
+    }
+
+    /// Tokens used by every completion so far
+    pub fn session_usage(&self) -> SessionUsage {
+        *self
+            .session_usage
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Price models by `prices` (model name or name prefix -> price) before
+    /// the built-in list
+    pub fn set_prices(&mut self, prices: HashMap<String, ModelPrice>) {
+        self.prices = prices;
+    }
+
+    /// Estimated cost of `usage` on the current model, or `None` for a model
+    /// without a known price
+    pub fn estimated_cost(&self, usage: TokenUsage) -> Option<f64> {
+        let configured = self
+            .prices
+            .iter()
+            .filter(|(name, _)| self.model.starts_with(name.as_str()))
+            .max_by_key(|(name, _)| name.len())
+            .map(|(_, price)| price);
+        let price = configured.or_else(|| {
+            PRICES
+                .iter()
+                .find(|(name, _)| self.model.starts_with(name))
+                .map(|(_, price)| price)
+        })?;
+        Some(price.cost(usage))
+    }
+
+    /// Change the model and sampling options used for summaries
+    pub fn set_options(&mut self, model: &str, max_tokens: u32, temperature: f32) {
+        self.model = model.to_string();
+        self.max_tokens = max_tokens;
+        self.temperature = temperature;
+    }
+
     pub async fn suggest_urls(&self, failed_url: &str, error_message: &str) -> Result<Vec<String>> {
         let prompt = format!(
             "The user tried to access '{}' but got error: {}. \
@@ -55,20 +293,23 @@ impl OpenAIClient {
             Each URL must be a valid, complete URL with protocol and domain (e.g., https://www.example.com). \
             Consider common typos, missing protocols, popular websites, and logical alternatives. \
             For single words like 'wired', suggest the actual website like 'https://www.wired.com'. \
-            Respond with ONLY a JSON array of complete URL strings, no other text or explanation.",
+            Respond with ONLY a JSON object of the form {{\"urls\": [\"https://...\"]}}, \
+            no other text or explanation.",
             failed_url, error_message
         );
 
-        let response_text = self
+        let completion = self
             .call_openai(
-                "You are a helpful URL suggestion assistant. Always respond with valid JSON array of URL strings.",
+                "You are a helpful URL suggestion assistant. Always respond with a valid JSON object \
+                with a \"urls\" array of URL strings.",
                 &prompt,
                 200,
+                self.temperature,
+                true,
             )
             .await?;
 
-        let suggestions: Vec<String> = serde_json::from_str(&response_text)
-            .map_err(|e| anyhow!("Failed to parse URL suggestions as JSON: {}", e))?;
+        let suggestions = parse_url_list(&completion.text)?;
 
         Ok(suggestions
             .into_iter()
@@ -77,23 +318,93 @@ impl OpenAIClient {
             .collect())
     }
 
-    pub async fn summarize(&self, text: &str, url: &str) -> Result<String> {
+    /// Save every request and response to `recorder`, with the API key redacted
+    pub fn set_recorder(&mut self, recorder: Arc<Recorder>) {
+        recorder.redact(&self.api_key);
+        self.recorder = Some(recorder);
+    }
+
+    /// Summarize `text`; `options` are usually `self.options()`, but the
+    /// compare view passes others
+    pub async fn summarize(
+        &self,
+        text: &str,
+        url: &str,
+        hints: &SummaryHints,
+        options: SummaryOptions,
+    ) -> Result<Completion> {
         if text.trim().is_empty() {
-            return Ok("No content to summarize.".to_string());
+            return Ok(Completion {
+                text: "No content to summarize.".to_string(),
+                usage: None,
+            });
         }
 
-        let prompt = format!(
-            "Please provide a concise but comprehensive summary of the following web page content from {}:\n\n{}",
-            url, text
-        );
+        let mut prompt = if hints.json {
+            format!(
+                "Please explain the following JSON response from the API endpoint {}:\n\n{}",
+                url, text
+            )
+        } else {
+            format!(
+                "Please provide a concise but comprehensive summary of the following web page content from {}:\n\n{}",
+                url, text
+            )
+        };
+        if let Some(published) = &hints.published {
+            prompt.push_str(&format!(
+                "\n\nNote: this page was published on {} and today is {}. If the content \
+                is likely outdated, briefly say so.",
+                published,
+                chrono::Utc::now().format("%Y-%m-%d")
+            ));
+        }
+        if !hints.references.is_empty() {
+            prompt.push_str(
+                "\n\nThese links from the page will be listed after your summary. Where a \
+                point is backed by one of them, cite it inline by number, like [1]. Don't list \
+                the links yourself.\n",
+            );
+            for (i, (text, link)) in hints.references.iter().enumerate() {
+                prompt.push_str(&format!("\n[{}] {} ({})", i + 1, text, link));
+            }
+        }
+        if let Some(instruction) = &hints.instruction {
+            prompt.push_str(&format!(
+                "\n\nThe reader asked for this summary: {}",
+                instruction
+            ));
+        }
+        if hints.excerpt {
+            prompt.push_str(
+                "\n\nNote: this is only the beginning of the page, for a quick look. Summarize \
+                what is there and don't guess at the rest.",
+            );
+        }
+        if hints.possibly_partial {
+            prompt.push_str(
+                "\n\nNote: this page appears to be behind a paywall, so the content above may be \
+                only a teaser. Say so briefly and don't present it as the full article.",
+            );
+        }
 
+        // Outlines spend tokens on structure, so they get a little more room
+        let (system_message, max_tokens) = if hints.json {
+            (JSON_SYSTEM_MESSAGE, options.max_tokens)
+        } else if hints.outline {
+            (
+                OUTLINE_SYSTEM_MESSAGE,
+                options.max_tokens + options.max_tokens * OUTLINE_EXTRA_TOKENS_PERCENT / 100,
+            )
+        } else {
+            (SUMMARY_SYSTEM_MESSAGE, options.max_tokens)
+        };
         self.call_openai(
-            "You are a helpful assistant that summarizes web content. \
-            Format your response as clean markdown with appropriate headers, bullet points, \
-            **bold** text for emphasis, and *italic* text for quotes or special terms. \
-            Use ## for main sections and - for bullet points. Keep it structured and readable.",
+            system_message,
             &prompt,
-            MAX_TOKENS,
+            max_tokens,
+            options.temperature,
+            false,
         )
         .await
     }
@@ -103,9 +414,11 @@ impl OpenAIClient {
         system_message: &str,
         user_prompt: &str,
         max_tokens: u32,
-    ) -> Result<String> {
+        temperature: f32,
+        json_response: bool,
+    ) -> Result<Completion> {
         let request = OpenAIRequest {
-            model: OPENAI_MODEL.to_string(),
+            model: self.model.clone(),
             messages: vec![
                 Message {
                     role: "system".to_string(),
@@ -117,35 +430,117 @@ impl OpenAIClient {
                 },
             ],
             max_tokens,
-            temperature: TEMPERATURE,
+            temperature,
+            response_format: json_response.then_some(ResponseFormat {
+                kind: "json_object",
+            }),
         };
 
+        let (mut choice, mut usage) = self.complete(&request, user_prompt.len()).await?;
+        if choice.text().is_none() && choice.worth_retrying() {
+            warn!(reason = ?choice.finish_reason, "model returned no content, retrying once");
+            let (retried, retry_usage) = self.complete(&request, user_prompt.len()).await?;
+            choice = retried;
+            usage = match (usage, retry_usage) {
+                (Some(mut usage), Some(retry_usage)) => {
+                    usage += retry_usage;
+                    Some(usage)
+                }
+                (usage, retry_usage) => usage.or(retry_usage),
+            };
+        }
+
+        match choice.text() {
+            Some(text) => Ok(Completion {
+                text: text.to_string(),
+                usage,
+            }),
+            None => Err(anyhow!(
+                "Model returned no content (reason: {})",
+                choice.finish_reason.as_deref().unwrap_or("unknown")
+            )),
+        }
+    }
+
+    /// Send one chat completion request and return its first choice and the
+    /// tokens it took
+    async fn complete(
+        &self,
+        request: &OpenAIRequest,
+        prompt_chars: usize,
+    ) -> Result<(Choice, Option<TokenUsage>)> {
+        let started = Instant::now();
+        debug!(
+            model = %self.model,
+            max_tokens = request.max_tokens,
+            prompt_chars,
+            "sending chat completion request"
+        );
+
+        if let Some(recorder) = &self.recorder {
+            recorder.save("prompt.json", &serde_json::to_string_pretty(request)?);
+        }
+
+        let _permit = self.http.permit().await;
         let response = self
-            .client
+            .http
+            .client()
             .post(OPENAI_API_URL)
+            .timeout(Duration::from_secs(OPENAI_TIMEOUT_SECS))
             .header("Authorization", format!("Bearer {}", self.api_key))
             .header("Content-Type", "application/json")
-            .json(&request)
+            .json(request)
             .send()
             .await
             .map_err(|e| anyhow!("Failed to send request to OpenAI: {}", e))?;
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response.text().await.unwrap_or_default();
+        let status = response.status();
+        let body = response.text().await;
+        if let (Some(recorder), Ok(body)) = (&self.recorder, &body) {
+            recorder.save("response.json", body);
+        }
+
+        if !status.is_success() {
+            let error_text = body.unwrap_or_default();
+            warn!(status = status.as_u16(), "OpenAI request failed");
             return Err(anyhow!("OpenAI API error {}: {}", status, error_text));
         }
 
-        let openai_response: OpenAIResponse = response
-            .json()
-            .await
+        let openai_response: OpenAIResponse = body
+            .map_err(anyhow::Error::from)
+            .and_then(|body| Ok(serde_json::from_str(&body)?))
             .map_err(|e| anyhow!("Failed to parse OpenAI response: {}", e))?;
 
-        openai_response
+        info!(
+            model = %self.model,
+            elapsed_ms = started.elapsed().as_millis() as u64,
+            "received chat completion"
+        );
+
+        let usage = openai_response.usage;
+        self.record_usage(usage);
+        let choice = openai_response
             .choices
-            .first()
-            .map(|choice| choice.message.content.clone())
-            .ok_or_else(|| anyhow!("No response from OpenAI"))
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("No response from OpenAI"))?;
+        Ok((choice, usage))
+    }
+
+    fn record_usage(&self, usage: Option<TokenUsage>) {
+        let mut session = self
+            .session_usage
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        session.requests += 1;
+        let cost = usage.and_then(|usage| {
+            session.tokens += usage;
+            self.estimated_cost(usage)
+        });
+        match cost {
+            Some(cost) => session.cost_usd += cost,
+            None => session.unpriced_requests += 1,
+        }
     }
 
     fn is_valid_url(url: &str) -> bool {
@@ -164,3 +559,99 @@ impl OpenAIClient {
         }
     }
 }
+
+/// Pull a list of URL strings out of a model reply. Accepts a bare JSON array,
+/// an object holding an array (`{"urls": [...]}`), either one wrapped in
+/// ```json fences, or an array embedded in surrounding prose.
+fn parse_url_list(response: &str) -> Result<Vec<String>> {
+    let trimmed = strip_code_fences(response.trim());
+
+    if let Ok(value) = serde_json::from_str::<serde_json::Value>(trimmed) {
+        let array = match value {
+            serde_json::Value::Array(items) => Some(items),
+            serde_json::Value::Object(map) => map.into_iter().find_map(|(_, v)| match v {
+                serde_json::Value::Array(items) => Some(items),
+                _ => None,
+            }),
+            _ => None,
+        };
+        if let Some(items) = array {
+            return Ok(items
+                .into_iter()
+                .filter_map(|item| item.as_str().map(str::to_string))
+                .collect());
+        }
+    }
+
+    let start = trimmed.find('[');
+    let end = trimmed.rfind(']');
+    match (start, end) {
+        (Some(start), Some(end)) if start < end => serde_json::from_str(&trimmed[start..=end])
+            .map_err(|e| anyhow!("Failed to parse URL suggestions as JSON: {}", e)),
+        _ => {
+            warn!(response, "no JSON array in URL suggestion reply");
+            Err(anyhow!(
+                "Failed to parse URL suggestions: no JSON array found"
+            ))
+        }
+    }
+}
+
+/// Remove a surrounding Markdown code fence (```json ... ```), if any
+fn strip_code_fences(text: &str) -> &str {
+    let Some(rest) = text.strip_prefix("```") else {
+        return text;
+    };
+    // Drop the info string ("json") on the opening fence line
+    let body = rest.split_once('\n').map_or("", |(_, body)| body);
+    body.trim_end().trim_end_matches("```").trim()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn urls(response: &str) -> Vec<String> {
+        parse_url_list(response).unwrap()
+    }
+
+    #[test]
+    fn parses_bare_arrays_and_url_objects() {
+        let expected = ["https://www.wired.com", "https://wired.co.uk"];
+        assert_eq!(
+            urls(r#"["https://www.wired.com", "https://wired.co.uk"]"#),
+            expected
+        );
+        assert_eq!(
+            urls(r#"{"urls": ["https://www.wired.com", "https://wired.co.uk"]}"#),
+            expected
+        );
+    }
+
+    #[test]
+    fn parses_fenced_replies() {
+        let expected = ["https://www.rust-lang.org"];
+        assert_eq!(
+            urls("```json\n{\"urls\": [\"https://www.rust-lang.org\"]}\n```"),
+            expected
+        );
+        assert_eq!(urls("```\n[\"https://www.rust-lang.org\"]\n```"), expected);
+    }
+
+    #[test]
+    fn finds_the_array_inside_prose() {
+        assert_eq!(
+            urls(
+                "Sure! Here are some guesses:\n[\"https://example.com\", \"https://example.org\"]\n\
+                 Let me know if you need more."
+            ),
+            ["https://example.com", "https://example.org"]
+        );
+    }
+
+    #[test]
+    fn replies_without_an_array_are_errors() {
+        assert!(parse_url_list("I could not think of any URLs.").is_err());
+        assert!(parse_url_list("Try [this one] maybe").is_err());
+    }
+}
@@ -0,0 +1,65 @@
+// Where bbow keeps its files, following the XDG base directory layout
+// Every component that reads or writes files finds its directory here
+
+use directories::ProjectDirs;
+use std::path::{Path, PathBuf};
+
+const APP_NAME: &str = "bbow";
+
+/// Settings: `config.toml`. Where the platform default is elsewhere (like
+/// `~/Library/Application Support` on macOS), an existing `~/.config/bbow`
+/// from older versions is still used.
+pub fn config_dir() -> Option<PathBuf> {
+    let legacy = std::env::var_os("HOME")
+        .map(|home| PathBuf::from(home).join(".config").join(APP_NAME))
+        .filter(|dir| dir.is_dir());
+    resolve(
+        "BBOW_CONFIG_DIR",
+        "XDG_CONFIG_HOME",
+        legacy,
+        ProjectDirs::config_dir,
+    )
+}
+
+/// Files bbow keeps across runs, such as history and bookmarks
+pub fn data_dir() -> Option<PathBuf> {
+    resolve(
+        "BBOW_DATA_DIR",
+        "XDG_DATA_HOME",
+        None,
+        ProjectDirs::data_dir,
+    )
+}
+
+/// Files that can be deleted at any time, such as cached pages
+pub fn cache_dir() -> Option<PathBuf> {
+    resolve(
+        "BBOW_CACHE_DIR",
+        "XDG_CACHE_HOME",
+        None,
+        ProjectDirs::cache_dir,
+    )
+}
+
+/// `$<override_var>` as is, else `$<xdg_var>/bbow`, else `existing`, else
+/// the platform default (`~/.config/bbow` and so on on Linux). Relative
+/// paths in either variable are ignored, as the XDG spec asks.
+fn resolve(
+    override_var: &str,
+    xdg_var: &str,
+    existing: Option<PathBuf>,
+    platform_dir: fn(&ProjectDirs) -> &Path,
+) -> Option<PathBuf> {
+    env_dir(override_var)
+        .or_else(|| env_dir(xdg_var).map(|dir| dir.join(APP_NAME)))
+        .or(existing)
+        .or_else(|| {
+            ProjectDirs::from("", "", APP_NAME).map(|dirs| platform_dir(&dirs).to_path_buf())
+        })
+}
+
+fn env_dir(var: &str) -> Option<PathBuf> {
+    std::env::var_os(var)
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+}
@@ -0,0 +1,86 @@
+// Reproducible bundles of what each navigation did, for bug reports and prompt tuning
+// With `--record DIR`, every page visited gets a timestamped directory holding
+// the fetched HTML, the extracted text, and each prompt and response sent to
+// and from the LLM, numbered in the order they happened.
+
+use crate::export::file_stem;
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+use tracing::{debug, warn};
+
+/// What secrets are replaced with in recorded files
+const REDACTED: &str = "[REDACTED]";
+
+pub struct Recorder {
+    dir: PathBuf,
+    /// Text never written out as is, like the API key
+    secrets: Mutex<Vec<String>>,
+    /// Directory of the navigation being recorded and files written to it so far
+    current: Mutex<Option<(PathBuf, usize)>>,
+}
+
+impl Recorder {
+    /// A recorder writing under `dir`, created if missing
+    pub fn new(dir: &Path) -> Result<Self> {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            secrets: Mutex::new(Vec::new()),
+            current: Mutex::new(None),
+        })
+    }
+
+    /// Never write `secret` to a recording
+    pub fn redact(&self, secret: &str) {
+        if !secret.is_empty() {
+            lock(&self.secrets).push(secret.to_string());
+        }
+    }
+
+    /// Start recording a navigation to `url`; later files go to its own directory
+    pub fn begin(&self, url: &str) {
+        let name = format!(
+            "{}-{}",
+            chrono::Local::now().format("%Y%m%d-%H%M%S%.3f"),
+            file_stem(url)
+        );
+        let dir = self.dir.join(name);
+        match std::fs::create_dir_all(&dir) {
+            Ok(()) => {
+                debug!(dir = %dir.display(), "recording navigation");
+                *lock(&self.current) = Some((dir, 0));
+            }
+            Err(e) => {
+                warn!(dir = %dir.display(), error = %e, "not recording navigation");
+                *lock(&self.current) = None;
+            }
+        }
+    }
+
+    /// Write `contents` as the next file of the current navigation, e.g.
+    /// `03-prompt.json` for `name` "prompt.json". Nothing is written before
+    /// the first navigation; failures are logged, never shown.
+    pub fn save(&self, name: &str, contents: &str) {
+        let path = {
+            let mut current = lock(&self.current);
+            let Some((dir, count)) = current.as_mut() else {
+                return;
+            };
+            *count += 1;
+            dir.join(format!("{:02}-{}", count, name))
+        };
+        let mut contents = contents.to_string();
+        for secret in lock(&self.secrets).iter() {
+            contents = contents.replace(secret.as_str(), REDACTED);
+        }
+        if let Err(e) = std::fs::write(&path, contents) {
+            warn!(path = %path.display(), error = %e, "failed to write recording");
+        }
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
@@ -0,0 +1,96 @@
+// Runtime-tunable settings shown on the settings screen
+// Each setting cycles through a fixed set of values so it can be changed with the arrow keys
+
+use crate::config::Config;
+
+const MODELS: &[&str] = &[
+    "gpt-4.1-mini",
+    "gpt-4.1-nano",
+    "gpt-4.1",
+    "gpt-4o-mini",
+    "gpt-4o",
+];
+const SUMMARY_TOKENS: &[u32] = &[250, 500, 1000, 2000];
+const TEMPERATURES: &[f32] = &[0.0, 0.3, 0.7, 1.0];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Setting {
+    Model,
+    SummaryLength,
+    Temperature,
+    TitleInSummary,
+    VerifySuggestions,
+    ConfirmQuit,
+}
+
+impl Setting {
+    pub const ALL: &'static [Setting] = &[
+        Setting::Model,
+        Setting::SummaryLength,
+        Setting::Temperature,
+        Setting::TitleInSummary,
+        Setting::VerifySuggestions,
+        Setting::ConfirmQuit,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Setting::Model => "Model",
+            Setting::SummaryLength => "Summary length (max tokens)",
+            Setting::Temperature => "Temperature",
+            Setting::TitleInSummary => "Include title in summary input",
+            Setting::VerifySuggestions => "Verify URL suggestions",
+            Setting::ConfirmQuit => "Confirm before quitting",
+        }
+    }
+
+    pub fn value(self, config: &Config) -> String {
+        match self {
+            Setting::Model => config.model.clone(),
+            Setting::SummaryLength => config.summary_max_tokens.to_string(),
+            Setting::Temperature => format!("{:.1}", config.temperature),
+            Setting::TitleInSummary => on_off(config.summarize_title),
+            Setting::VerifySuggestions => on_off(config.verify_suggestions),
+            Setting::ConfirmQuit => on_off(config.confirm_quit),
+        }
+    }
+
+    /// Step to the next (or previous) value, wrapping around
+    pub fn cycle(self, config: &mut Config, forward: bool) {
+        match self {
+            Setting::Model => {
+                let current = MODELS.iter().position(|m| *m == config.model);
+                config.model = MODELS[step(current, MODELS.len(), forward)].to_string();
+            }
+            Setting::SummaryLength => {
+                let current = SUMMARY_TOKENS
+                    .iter()
+                    .position(|t| *t == config.summary_max_tokens);
+                config.summary_max_tokens =
+                    SUMMARY_TOKENS[step(current, SUMMARY_TOKENS.len(), forward)];
+            }
+            Setting::Temperature => {
+                let current = TEMPERATURES
+                    .iter()
+                    .position(|t| (*t - config.temperature).abs() < f32::EPSILON);
+                config.temperature = TEMPERATURES[step(current, TEMPERATURES.len(), forward)];
+            }
+            Setting::TitleInSummary => config.summarize_title = !config.summarize_title,
+            Setting::VerifySuggestions => config.verify_suggestions = !config.verify_suggestions,
+            Setting::ConfirmQuit => config.confirm_quit = !config.confirm_quit,
+        }
+    }
+}
+
+fn on_off(value: bool) -> String {
+    if value { "on" } else { "off" }.to_string()
+}
+
+/// Index after moving one step from `current`; an unknown current value starts at the first entry
+fn step(current: Option<usize>, len: usize, forward: bool) -> usize {
+    match current {
+        None => 0,
+        Some(i) if forward => (i + 1) % len,
+        Some(i) => (i + len - 1) % len,
+    }
+}
@@ -0,0 +1,89 @@
+use crate::client::WebClient;
+use anyhow::{anyhow, Result};
+use tracing::{debug, warn};
+use url::Url;
+
+/// Stop collecting once this many page URLs are found
+const MAX_SITEMAP_URLS: usize = 500;
+/// Follow at most this many child sitemaps of a sitemap index
+const MAX_CHILD_SITEMAPS: usize = 10;
+
+/// Page URLs listed in a `urlset`, or child sitemaps listed in a `sitemapindex`
+#[derive(Debug, Default)]
+pub struct Sitemap {
+    pub urls: Vec<String>,
+    pub children: Vec<String>,
+}
+
+/// Pull the `<loc>` entries out of a sitemap document
+pub fn parse_sitemap(xml: &str) -> Sitemap {
+    let locs = extract_locs(xml);
+    if xml.contains("<sitemapindex") {
+        Sitemap {
+            urls: Vec::new(),
+            children: locs,
+        }
+    } else {
+        Sitemap {
+            urls: locs,
+            children: Vec::new(),
+        }
+    }
+}
+
+/// Fetch `/sitemap.xml` for the host of `page_url`, following a sitemap index
+/// one level deep, and return the page URLs it lists
+pub async fn fetch_sitemap_urls(client: &WebClient, page_url: &str) -> Result<Vec<String>> {
+    let root = Url::parse(page_url)?.join("/sitemap.xml")?;
+    let sitemap = parse_sitemap(&client.fetch_text(root.as_str()).await?);
+
+    let mut urls = sitemap.urls;
+    for child in sitemap.children.iter().take(MAX_CHILD_SITEMAPS) {
+        if urls.len() >= MAX_SITEMAP_URLS {
+            break;
+        }
+        match client.fetch_text(child).await {
+            Ok(xml) => urls.extend(parse_sitemap(&xml).urls),
+            Err(e) => warn!(sitemap = %child, error = %e, "skipping child sitemap"),
+        }
+    }
+
+    urls.truncate(MAX_SITEMAP_URLS);
+    debug!(url = %root, count = urls.len(), "collected sitemap URLs");
+
+    if urls.is_empty() {
+        return Err(anyhow!("No URLs found in {}", root));
+    }
+    Ok(urls)
+}
+
+fn extract_locs(xml: &str) -> Vec<String> {
+    let mut locs = Vec::new();
+    let mut rest = xml;
+
+    while let Some(start) = rest.find("<loc>") {
+        rest = &rest[start + "<loc>".len()..];
+        let Some(end) = rest.find("</loc>") else {
+            break;
+        };
+        let loc = rest[..end]
+            .trim()
+            .trim_start_matches("<![CDATA[")
+            .trim_end_matches("]]>")
+            .trim();
+        if !loc.is_empty() {
+            locs.push(unescape_xml(loc));
+        }
+        rest = &rest[end..];
+    }
+
+    locs
+}
+
+fn unescape_xml(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
@@ -1,9 +1,18 @@
-use super::{BrowserState, HistoryEntry, UIInterface, UserAction};
-use crate::common::{markdown::MarkdownElement, ui as ui_common};
-use crate::links::Link;
+use super::{
+    BookmarkEntry, BrowserState, ComparePane, HistoryEntry, LinkFind, SettingsItem, TextInput,
+    UIInterface, UserAction,
+};
+use crate::common::{
+    diff, emoji,
+    hyperlink::{self, Hyperlink},
+    keys,
+    markdown::MarkdownElement,
+    text::truncate_at_word_boundary,
+    ui::{self as ui_common, LinkLine},
+};
 use anyhow::Result;
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyModifiers},
+    event::{DisableMouseCapture, EnableMouseCapture},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
@@ -13,20 +22,45 @@ use ratatui::{
     style::{Color, Modifier, Style},
     text::{Line, Span},
     widgets::{
-        Block, Borders, Clear, Gauge, List, ListItem, Paragraph, Scrollbar, ScrollbarOrientation,
-        ScrollbarState, Wrap,
+        Block, Borders, Clear, Gauge, List, ListItem, ListState, Paragraph, Scrollbar,
+        ScrollbarOrientation, ScrollbarState, Wrap,
     },
     Frame, Terminal,
 };
 use std::io::{self, Stdout};
+use std::time::Duration;
 use textwrap::fill;
 
+/// Share of the page width given to the summary unless `--split-ratio` is set
+const CONTENT_PERCENT: u16 = 80;
+
 pub struct UI {
     terminal: Terminal<CrosstermBackend<Stdout>>,
     scroll_position: u16,
     selected_link: usize,
     links_scroll: usize,
     max_scroll: u16,
+    /// Summary wrap width from the last render, for locating text in it
+    summary_width: usize,
+    scroll_accel: ui_common::ScrollAccel,
+    pane_scroll: ui_common::PaneScroll,
+    /// Share of the page width given to the summary
+    content_percent: u16,
+    /// Widest the summary text wraps, centred in its pane when narrower
+    max_width: Option<u16>,
+    /// Blank line after every block of a summary
+    line_spacing: bool,
+    /// Characters of a summary shown until it is expanded
+    summary_preview: Option<usize>,
+    /// Make links clickable with OSC 8 escape sequences
+    hyperlinks: bool,
+    /// Show emoji in the chrome rather than their ASCII stand-ins
+    emoji: bool,
+    /// Links of the panel as last drawn, for finding the one under the mouse
+    links_drawn: Vec<Hyperlink>,
+    /// URL of the link under the mouse, shown in place of the key hints
+    hovered: Option<String>,
+    input: keys::InputReader,
 }
 
 impl UIInterface for UI {
@@ -43,6 +77,18 @@ impl UIInterface for UI {
             selected_link: 0,
             links_scroll: 0,
             max_scroll: 0,
+            summary_width: 0,
+            scroll_accel: ui_common::ScrollAccel::default(),
+            pane_scroll: ui_common::PaneScroll::default(),
+            content_percent: CONTENT_PERCENT,
+            max_width: None,
+            line_spacing: false,
+            summary_preview: None,
+            hyperlinks: false,
+            emoji: true,
+            links_drawn: Vec::new(),
+            hovered: None,
+            input: keys::InputReader::default(),
         })
     }
 
@@ -57,28 +103,97 @@ impl UIInterface for UI {
         Ok(())
     }
 
+    fn clear(&mut self) -> Result<()> {
+        self.terminal.clear()?;
+        Ok(())
+    }
+
     fn render(&mut self, state: &BrowserState) -> Result<()> {
         self.render_internal(state)
     }
 
-    fn get_user_input(&mut self, state: &BrowserState) -> Result<UserAction> {
-        self.get_user_input_internal(state)
+    fn get_user_input(
+        &mut self,
+        state: &BrowserState,
+        timeout: Option<Duration>,
+    ) -> Result<UserAction> {
+        self.get_user_input_internal(state, timeout)
     }
 
     fn reset_scroll(&mut self) {
         self.scroll_position = 0;
         self.selected_link = 0;
         self.links_scroll = 0;
+        self.hovered = None;
+    }
+
+    fn scroll_position(&self) -> u16 {
+        self.scroll_position
+    }
+
+    fn summary_width(&self) -> usize {
+        self.summary_width
+    }
+
+    fn set_scroll_position(&mut self, position: u16) {
+        self.scroll_position = position;
+    }
+
+    fn max_scroll(&self) -> u16 {
+        self.max_scroll
+    }
+
+    fn scroll_to_text(&mut self, summary: &str, text: &str) -> bool {
+        match ui_common::find_text_line(summary, self.summary_width, self.line_spacing, text) {
+            Some(line) => {
+                self.scroll_position = line;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn scroll_pane(&mut self, pane: usize, lines: i32) {
+        self.pane_scroll.scroll(pane, lines);
+    }
+
+    fn reset_pane_scroll(&mut self) {
+        self.pane_scroll.reset();
+    }
+
+    fn set_split_ratio(&mut self, content_percent: u16) {
+        self.content_percent = content_percent;
+    }
+
+    fn set_max_width(&mut self, columns: u16) {
+        self.max_width = Some(columns);
+    }
+
+    fn set_line_spacing(&mut self, enabled: bool) {
+        self.line_spacing = enabled;
+    }
+
+    fn set_summary_preview(&mut self, chars: Option<usize>) {
+        self.summary_preview = chars;
+    }
+
+    fn set_hyperlinks(&mut self, enabled: bool) {
+        self.hyperlinks = enabled;
+    }
+
+    fn set_emoji(&mut self, enabled: bool) {
+        self.emoji = enabled;
     }
 
     fn scroll_up(&mut self) {
-        self.scroll_position = self.scroll_position.saturating_sub(1);
+        let step = self.scroll_accel.step(false);
+        self.scroll_position = self.scroll_position.saturating_sub(step);
     }
 
     fn scroll_down(&mut self) {
-        if self.scroll_position < self.max_scroll {
-            self.scroll_position += 1;
-        }
+        let step = self.scroll_accel.step(true);
+        self.scroll_position =
+            ui_common::scroll_down_clamped(self.scroll_position, step, self.max_scroll);
     }
 
     fn select_prev_link(&mut self, links_len: usize) {
@@ -98,6 +213,22 @@ impl UIInterface for UI {
     fn get_selected_link(&self) -> usize {
         self.selected_link
     }
+
+    fn select_link(&mut self, index: usize) {
+        self.selected_link = index;
+        self.update_links_scroll();
+    }
+
+    fn link_at(&self, column: u16, row: u16) -> Option<String> {
+        hyperlink::at(&self.links_drawn, column, row).map(|link| link.url.clone())
+    }
+
+    fn hover(&mut self, column: u16, row: u16) -> bool {
+        let hovered = self.link_at(column, row);
+        let changed = hovered != self.hovered;
+        self.hovered = hovered;
+        changed
+    }
 }
 
 impl UI {
@@ -107,41 +238,79 @@ impl UI {
     }
 
     fn render_internal(&mut self, state: &BrowserState) -> Result<()> {
+        let emoji = self.emoji;
         match state {
             BrowserState::Loading {
                 url,
                 progress,
                 stage,
+                thinking,
             } => {
-                let (url, progress, stage) = (url.clone(), *progress, stage.clone());
-                self.terminal
-                    .draw(|f| Self::render_loading(f, &url, progress, &stage))?;
+                self.terminal.draw(|f| {
+                    Self::render_loading(
+                        f,
+                        url,
+                        *progress,
+                        stage.label(),
+                        thinking.as_deref(),
+                        emoji,
+                    )
+                })?;
             }
             BrowserState::Page {
                 url,
                 title,
                 summary,
                 links,
+                meta,
+                link_find,
+                link_groups,
+                notice,
+                diff_from,
+                raw,
+                expanded,
             } => {
-                let (url, title, summary, links) =
-                    (url.clone(), title.clone(), summary.clone(), links.clone());
+                let links_note = meta.links_note(links.len());
+                let summary = ui_common::shown_summary(summary, *expanded, self.summary_preview);
+                let (url, title, summary, links, link_find, notice) = (
+                    url.clone(),
+                    title.clone(),
+                    diff::summary_with_changes(&summary, diff_from.as_deref().filter(|_| !*raw)),
+                    ui_common::link_lines(links, link_groups.as_deref()),
+                    link_find.clone(),
+                    notice.clone(),
+                );
+                let status = meta.header_label(emoji);
+                let (content_percent, max_width, line_spacing) =
+                    (self.content_percent, self.max_width, self.line_spacing);
                 let (scroll_pos, selected_link, links_scroll) =
                     (self.scroll_position, self.selected_link, self.links_scroll);
+                let hovered = self.hovered.clone();
 
-                self.terminal.draw(|f| {
+                self.links_drawn = hyperlink::draw(&mut self.terminal, self.hyperlinks, |f| {
                     Self::render_page(
                         f,
+                        content_percent,
+                        max_width,
+                        line_spacing,
                         &url,
+                        status.as_deref(),
                         &title,
                         &summary,
+                        *raw,
                         &links,
                         scroll_pos,
                         selected_link,
                         links_scroll,
-                    );
+                        link_find.as_ref(),
+                        links_note.as_deref(),
+                        notice.as_deref(),
+                        hovered.as_deref(),
+                        emoji,
+                    )
                 })?;
 
-                self.update_max_scroll(&summary);
+                self.update_max_scroll(&summary, *raw);
                 self.update_links_scroll_with_height(
                     self.terminal.size()?.height.saturating_sub(8) as usize,
                 );
@@ -152,11 +321,27 @@ impl UI {
             } => {
                 let (entries, current_index) = (entries.clone(), *current_index);
                 self.terminal
-                    .draw(|f| Self::render_history(f, &entries, current_index))?;
+                    .draw(|f| Self::render_history(f, &entries, current_index, emoji))?;
+            }
+            BrowserState::Bookmarks {
+                entries,
+                filter,
+                selected,
+            } => {
+                self.terminal
+                    .draw(|f| Self::render_bookmarks(f, entries, filter, *selected, emoji))?;
             }
             BrowserState::URLInput { input } => {
                 let input = input.clone();
-                self.terminal.draw(|f| Self::render_url_input(f, &input))?;
+                self.terminal.draw(|f| {
+                    Self::render_input(f, &emoji::chrome("🌐 ", emoji), "Enter URL", &input)
+                })?;
+            }
+            BrowserState::Prompt { title, input, .. } => {
+                let (title, input) = (title.clone(), input.clone());
+                self.terminal.draw(|f| {
+                    Self::render_input(f, &emoji::chrome("💬 ", emoji), &title, &input)
+                })?;
             }
             BrowserState::URLSuggestions {
                 original_url,
@@ -177,18 +362,83 @@ impl UI {
                         &error_message,
                         &suggestions,
                         selected_index,
+                        emoji,
                     );
                 })?;
             }
             BrowserState::Error { message } => {
                 let message = message.clone();
-                self.terminal.draw(|f| Self::render_error(f, &message))?;
+                self.terminal
+                    .draw(|f| Self::render_error(f, &message, emoji))?;
+            }
+            BrowserState::Confirm { message, .. } => {
+                let message = message.clone();
+                self.terminal
+                    .draw(|f| Self::render_confirm(f, &message, emoji))?;
+            }
+            BrowserState::Settings {
+                items,
+                selected,
+                status,
+            } => {
+                let (items, selected, status) = (items.clone(), *selected, status.clone());
+                self.terminal.draw(|f| {
+                    Self::render_settings(f, &items, selected, status.as_deref(), emoji)
+                })?;
+            }
+            BrowserState::Info { items } => {
+                let items = items.clone();
+                self.terminal.draw(|f| {
+                    Self::render_info(f, &emoji::chrome("ℹ️ About bbow", emoji), &items)
+                })?;
+            }
+            BrowserState::Stats { items } => {
+                let items = items.clone();
+                self.terminal.draw(|f| {
+                    Self::render_info(f, &emoji::chrome("📊 Session stats", emoji), &items)
+                })?;
+            }
+            BrowserState::Extraction { items } => {
+                let items = items.clone();
+                self.terminal.draw(|f| {
+                    Self::render_info(f, &emoji::chrome("🔍 Extraction", emoji), &items)
+                })?;
+            }
+            BrowserState::Compare {
+                url,
+                title,
+                left,
+                right,
+                focus,
+            } => {
+                let (positions, line_spacing) = (self.pane_scroll.positions(), self.line_spacing);
+                let mut max_scroll = [0; 2];
+                self.terminal.draw(|f| {
+                    max_scroll = Self::render_compare(
+                        f,
+                        url,
+                        title,
+                        [left, right],
+                        *focus,
+                        positions,
+                        line_spacing,
+                        emoji,
+                    );
+                })?;
+                self.pane_scroll.set_max(max_scroll);
             }
         }
         Ok(())
     }
 
-    fn render_loading(f: &mut Frame, url: &str, progress: u16, stage: &str) {
+    fn render_loading(
+        f: &mut Frame,
+        url: &str,
+        progress: u16,
+        stage: &str,
+        thinking: Option<&str>,
+        emoji: bool,
+    ) {
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .margin(2)
@@ -197,11 +447,12 @@ impl UI {
                 Constraint::Length(4),
                 Constraint::Length(3),
                 Constraint::Length(3),
+                Constraint::Length(3),
             ])
             .split(f.size());
 
         f.render_widget(
-            Paragraph::new("🌐 Loading...")
+            Paragraph::new(emoji::chrome("🌐 Loading...", emoji))
                 .style(
                     Style::default()
                         .fg(Color::Cyan)
@@ -235,19 +486,42 @@ impl UI {
                 .block(Block::default().borders(Borders::ALL).title("Status")),
             chunks[3],
         );
+
+        if let Some(thinking) = thinking {
+            f.render_widget(
+                Paragraph::new(thinking.to_string())
+                    .style(
+                        Style::default()
+                            .fg(Color::Magenta)
+                            .add_modifier(Modifier::BOLD),
+                    )
+                    .block(Block::default().borders(Borders::ALL).title("AI")),
+                chunks[4],
+            );
+        }
     }
 
     #[allow(clippy::too_many_arguments)]
     fn render_page(
         f: &mut Frame,
+        content_percent: u16,
+        max_width: Option<u16>,
+        line_spacing: bool,
         url: &str,
+        status: Option<&str>,
         title: &str,
         summary: &str,
-        links: &[Link],
+        raw: bool,
+        links: &[LinkLine],
         scroll_pos: u16,
         selected_link: usize,
         links_scroll: usize,
-    ) {
+        link_find: Option<&LinkFind>,
+        links_note: Option<&str>,
+        notice: Option<&str>,
+        hovered: Option<&str>,
+        emoji: bool,
+    ) -> Vec<Hyperlink> {
         let main_chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
@@ -257,61 +531,185 @@ impl UI {
             ])
             .split(f.size());
 
-        Self::render_header(f, main_chunks[0], url, title);
+        Self::render_header(f, main_chunks[0], url, status, title, emoji);
+
+        let content_chunks = ui_common::split_content(main_chunks[1], content_percent);
+
+        Self::render_summary(
+            f,
+            content_chunks[0],
+            summary,
+            scroll_pos,
+            max_width,
+            line_spacing,
+            raw,
+            emoji,
+        );
+        Self::render_help(f, main_chunks[2], notice, hovered, emoji);
+        Self::render_links(
+            f,
+            content_chunks[1],
+            links,
+            selected_link,
+            links_scroll,
+            link_find,
+            links_note,
+            emoji,
+        )
+    }
 
-        let content_chunks = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([Constraint::Percentage(80), Constraint::Percentage(20)])
-            .split(main_chunks[1]);
+    /// Draw both compare panes and return their max scroll
+    #[allow(clippy::too_many_arguments)]
+    fn render_compare(
+        f: &mut Frame,
+        url: &str,
+        title: &str,
+        panes: [&ComparePane; 2],
+        focus: usize,
+        positions: [u16; 2],
+        line_spacing: bool,
+        emoji: bool,
+    ) -> [u16; 2] {
+        let main_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(5),
+                Constraint::Min(10),
+                Constraint::Length(3),
+            ])
+            .split(f.size());
+
+        Self::render_header(f, main_chunks[0], url, None, title, emoji);
+
+        let style = ui_common::CompareStyle {
+            text: Style::default(),
+            block: move |label: &str, focused: bool| {
+                let border = if focused {
+                    Color::Cyan
+                } else {
+                    Color::DarkGray
+                };
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(border))
+                    .title(format!("{} {}", emoji::chrome("📄", emoji), label))
+            },
+            markdown: Self::style_markdown_element,
+        };
+        let max_scroll = ui_common::render_compare_panes(
+            f,
+            main_chunks[1],
+            panes,
+            focus,
+            positions,
+            line_spacing,
+            &style,
+        );
+
+        let key = Style::default()
+            .fg(Color::Cyan)
+            .add_modifier(Modifier::BOLD);
+        f.render_widget(
+            Paragraph::new(Line::from(vec![
+                Span::styled("Tab", key),
+                Span::raw(" Switch Pane  "),
+                Span::styled("↑↓", key),
+                Span::raw(" Scroll  "),
+                Span::styled("Esc", key),
+                Span::raw(" Back"),
+            ]))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(emoji::chrome("⌨️ Controls", emoji).into_owned()),
+            ),
+            main_chunks[2],
+        );
 
-        Self::render_summary(f, content_chunks[0], summary, scroll_pos);
-        Self::render_links(f, content_chunks[1], links, selected_link, links_scroll);
-        Self::render_help(f, main_chunks[2]);
+        max_scroll
     }
 
-    fn render_header(f: &mut Frame, area: Rect, url: &str, title: &str) {
+    fn render_header(
+        f: &mut Frame,
+        area: Rect,
+        url: &str,
+        status: Option<&str>,
+        title: &str,
+        emoji: bool,
+    ) {
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([Constraint::Length(2), Constraint::Length(2)])
             .split(area);
 
+        // One line each: long titles end in an ellipsis, long URLs lose their middle
+        let inner_width = area.width.saturating_sub(5);
+        let title = truncate_at_word_boundary(title, inner_width as usize);
+        let url = ui_common::truncate_middle(url, ui_common::url_width(inner_width, status));
+
         f.render_widget(
-            Paragraph::new(format!("🌐 {}", title))
+            Paragraph::new(format!("{} {}", emoji::chrome("🌐", emoji), title))
                 .style(
                     Style::default()
                         .fg(Color::White)
                         .add_modifier(Modifier::BOLD),
                 )
-                .wrap(Wrap { trim: true })
                 .block(Block::default().borders(Borders::TOP | Borders::LEFT | Borders::RIGHT)),
             chunks[0],
         );
 
+        let mut url_line = vec![Span::styled(
+            format!("{} {}", emoji::chrome("📍", emoji), url),
+            Style::default().fg(Color::Blue),
+        )];
+        if let Some(status) = status {
+            url_line.push(Span::styled(
+                format!("  {}", status),
+                Style::default().fg(Color::DarkGray),
+            ));
+        }
+
         f.render_widget(
-            Paragraph::new(format!("📍 {}", url))
-                .style(Style::default().fg(Color::Blue))
-                .wrap(Wrap { trim: true })
+            Paragraph::new(Line::from(url_line))
                 .block(Block::default().borders(Borders::BOTTOM | Borders::LEFT | Borders::RIGHT)),
             chunks[1],
         );
     }
 
-    fn render_summary(f: &mut Frame, area: Rect, summary: &str, scroll_pos: u16) {
-        let width = area.width.saturating_sub(4) as usize;
+    #[allow(clippy::too_many_arguments)]
+    fn render_summary(
+        f: &mut Frame,
+        area: Rect,
+        summary: &str,
+        scroll_pos: u16,
+        max_width: Option<u16>,
+        line_spacing: bool,
+        raw: bool,
+        emoji: bool,
+    ) {
+        let (width, indent) =
+            ui_common::text_width(area.width.saturating_sub(4) as usize, max_width);
         let visible_height = area.height.saturating_sub(2) as usize;
 
-        let visible_lines = ui_common::get_visible_markdown_lines(
-            summary,
-            width,
-            scroll_pos,
-            visible_height,
-            Self::style_markdown_element,
+        let visible_lines = ui_common::indent_lines(
+            ui_common::get_visible_markdown_lines(
+                summary,
+                width,
+                line_spacing,
+                scroll_pos,
+                visible_height,
+                raw,
+                Self::style_markdown_element,
+            ),
+            indent,
         );
 
         let max_scroll = ui_common::calculate_max_scroll_for_markdown(
             summary,
             width,
+            line_spacing,
             visible_height,
+            raw,
             Self::style_markdown_element,
         );
 
@@ -323,7 +721,7 @@ impl UI {
                 .block(
                     Block::default()
                         .borders(Borders::ALL)
-                        .title("📄 Summary (Markdown)"),
+                        .title(emoji::chrome("📄 Summary (Markdown)", emoji).into_owned()),
                 )
                 .scroll((0, 0)),
             area,
@@ -364,24 +762,55 @@ impl UI {
             MarkdownElement::Code(_) => Style::default().bg(Color::DarkGray).fg(Color::White),
             MarkdownElement::Normal(_) => Style::default(),
             MarkdownElement::Empty => Style::default(),
+            MarkdownElement::TaskMarker(true) => Style::default().fg(Color::Green),
+            MarkdownElement::TaskMarker(false) => Style::default().fg(Color::Yellow),
+            MarkdownElement::DefinitionTerm => Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+            MarkdownElement::DiffAdded => Style::default()
+                .fg(Color::Green)
+                .add_modifier(Modifier::UNDERLINED),
+            MarkdownElement::DiffRemoved => Style::default()
+                .fg(Color::Red)
+                .add_modifier(Modifier::CROSSED_OUT),
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn render_links(
         f: &mut Frame,
         area: Rect,
-        links: &[Link],
+        links: &[LinkLine],
         selected_link: usize,
         links_scroll: usize,
-    ) {
+        link_find: Option<&LinkFind>,
+        links_note: Option<&str>,
+        emoji: bool,
+    ) -> Vec<Hyperlink> {
+        let title = match link_find {
+            Some(find) => match find.count_label(links.len()) {
+                Some(count) => format!(
+                    "{} Find: {}▏ {}",
+                    emoji::chrome("🔍", emoji),
+                    find.query,
+                    count
+                ),
+                None => format!("{} Find: {}▏", emoji::chrome("🔍", emoji), find.query),
+            },
+            None => match links_note {
+                Some(note) => format!("{} Links ({})", emoji::chrome("🔗", emoji), note),
+                None => emoji::chrome("🔗 Links", emoji).into_owned(),
+            },
+        };
+
         if links.is_empty() {
             f.render_widget(
                 Paragraph::new("No links found")
                     .style(Style::default().fg(Color::Gray))
-                    .block(Block::default().borders(Borders::ALL).title("🔗 Links")),
+                    .block(Block::default().borders(Borders::ALL).title(title)),
                 area,
             );
-            return;
+            return Vec::new();
         }
 
         let visible_height = area.height.saturating_sub(2) as usize;
@@ -398,11 +827,15 @@ impl UI {
                         .fg(Color::Black)
                         .bg(Color::Cyan)
                         .add_modifier(Modifier::BOLD)
+                } else if link.header {
+                    Style::default()
+                        .fg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD)
                 } else {
                     Style::default().fg(Color::White)
                 };
 
-                let content = format!("[{}] {}", link.index, link.text);
+                let content = link.label();
                 let wrapped_content = fill(&content, area.width.saturating_sub(6) as usize);
                 ListItem::new(wrapped_content).style(style)
             })
@@ -410,7 +843,7 @@ impl UI {
 
         f.render_widget(
             List::new(items)
-                .block(Block::default().borders(Borders::ALL).title("🔗 Links"))
+                .block(Block::default().borders(Borders::ALL).title(title))
                 .highlight_style(Style::default().fg(Color::Black).bg(Color::Cyan)),
             area,
         );
@@ -425,9 +858,54 @@ impl UI {
                 .position(links_scroll);
             f.render_stateful_widget(scrollbar, area, &mut scrollbar_state);
         }
+
+        hyperlink::list_links(
+            Block::default().borders(Borders::ALL).inner(area),
+            &links[start_index..end_index],
+            area.width.saturating_sub(6) as usize,
+            LinkLine::label,
+        )
     }
 
-    fn render_help(f: &mut Frame, area: Rect) {
+    fn render_help(
+        f: &mut Frame,
+        area: Rect,
+        notice: Option<&str>,
+        hovered: Option<&str>,
+        emoji: bool,
+    ) {
+        if let Some(notice) = notice {
+            f.render_widget(
+                Paragraph::new(notice.to_string())
+                    .style(Style::default().fg(Color::Yellow))
+                    .wrap(Wrap { trim: true })
+                    .block(
+                        Block::default()
+                            .borders(Borders::ALL)
+                            .title(emoji::chrome("⌨️ Controls", emoji).into_owned()),
+                    ),
+                area,
+            );
+            return;
+        }
+        if let Some(url) = hovered {
+            f.render_widget(
+                Paragraph::new(format!(
+                    "{} {}  (click to follow)",
+                    emoji::chrome("🔗", emoji),
+                    url
+                ))
+                .style(Style::default().fg(Color::Cyan))
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title(emoji::chrome("⌨️ Controls", emoji).into_owned()),
+                ),
+                area,
+            );
+            return;
+        }
+
         let help_text = vec![
             Line::from(vec![
                 Span::styled(
@@ -481,6 +959,13 @@ impl UI {
                         .add_modifier(Modifier::BOLD),
                 ),
                 Span::raw(" Refresh  "),
+                Span::styled(
+                    "'",
+                    Style::default()
+                        .fg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(" Find  "),
                 Span::styled(
                     "q",
                     Style::default()
@@ -492,23 +977,31 @@ impl UI {
         ];
 
         f.render_widget(
-            Paragraph::new(help_text)
-                .block(Block::default().borders(Borders::ALL).title("⌨️ Controls")),
+            Paragraph::new(help_text).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(emoji::chrome("⌨️ Controls", emoji).into_owned()),
+            ),
             area,
         );
     }
 
-    fn render_history(f: &mut Frame, entries: &[HistoryEntry], current_index: Option<usize>) {
+    fn render_history(
+        f: &mut Frame,
+        entries: &[HistoryEntry],
+        current_index: Option<usize>,
+        emoji: bool,
+    ) {
         let area = f.size();
 
-        let items: Vec<ListItem> = entries
+        let mut items: Vec<ListItem> = entries
             .iter()
             .enumerate()
             .map(|(i, entry)| {
                 let marker = if Some(i) == current_index {
-                    "➤ "
+                    emoji::chrome("➤ ", emoji)
                 } else {
-                    "  "
+                    "  ".into()
                 };
                 let style = if Some(i) == current_index {
                     Style::default()
@@ -518,15 +1011,30 @@ impl UI {
                     Style::default().fg(Color::White)
                 };
 
-                let content = format!("{}{} - {}", marker, entry.title, entry.url);
+                let content = format!(
+                    "{}{} - {} · {}",
+                    marker,
+                    entry.title,
+                    entry.url,
+                    entry.age_label()
+                );
                 let wrapped_content = fill(&content, area.width.saturating_sub(4) as usize);
                 ListItem::new(wrapped_content).style(style)
             })
             .collect();
+        if items.is_empty() {
+            items.push(
+                ListItem::new(ui_common::EMPTY_HISTORY).style(Style::default().fg(Color::Gray)),
+            );
+        }
 
         f.render_widget(
             List::new(items)
-                .block(Block::default().borders(Borders::ALL).title("📚 History"))
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title(emoji::chrome("📚 History", emoji).into_owned()),
+                )
                 .style(Style::default().fg(Color::White)),
             area,
         );
@@ -547,7 +1055,82 @@ impl UI {
         );
     }
 
-    fn render_url_input(f: &mut Frame, input: &str) {
+    fn render_bookmarks(
+        f: &mut Frame,
+        entries: &[BookmarkEntry],
+        filter: &str,
+        selected: usize,
+        emoji: bool,
+    ) {
+        let area = f.size();
+        let list_area = Rect {
+            height: area.height.saturating_sub(3),
+            ..area
+        };
+
+        let mut items: Vec<ListItem> = entries
+            .iter()
+            .enumerate()
+            .map(|(i, entry)| {
+                let marker = if i == selected {
+                    emoji::chrome("➤ ", emoji)
+                } else {
+                    "  ".into()
+                };
+                let style = if i == selected {
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(Color::White)
+                };
+
+                let mut content = format!("{}{} - {}", marker, entry.title, entry.url);
+                if !entry.tags.is_empty() {
+                    content.push_str(&format!(" · {}", entry.tags_label()));
+                }
+                let wrapped_content = fill(&content, area.width.saturating_sub(4) as usize);
+                ListItem::new(wrapped_content).style(style)
+            })
+            .collect();
+        let mut state = ListState::default();
+        if items.is_empty() {
+            items.push(
+                ListItem::new(ui_common::empty_bookmarks(filter))
+                    .style(Style::default().fg(Color::Gray)),
+            );
+        } else {
+            state.select(Some(selected));
+        }
+
+        let title = format!(
+            "{} Bookmarks · Filter: {}_",
+            emoji::chrome("🔖", emoji),
+            filter
+        );
+        f.render_stateful_widget(
+            List::new(items)
+                .block(Block::default().borders(Borders::ALL).title(title))
+                .style(Style::default().fg(Color::White)),
+            list_area,
+            &mut state,
+        );
+
+        let help_area = Rect {
+            x: area.x,
+            y: area.y + area.height.saturating_sub(3),
+            width: area.width,
+            height: 3.min(area.height),
+        };
+        f.render_widget(
+            Paragraph::new(ui_common::BOOKMARKS_HELP)
+                .style(Style::default().fg(Color::Gray))
+                .block(Block::default().borders(Borders::ALL)),
+            help_area,
+        );
+    }
+
+    fn render_input(f: &mut Frame, icon: &str, title: &str, input: &TextInput) {
         let area = f.size();
         let popup_area = Rect {
             x: area.width / 4,
@@ -556,11 +1139,22 @@ impl UI {
             height: 4,
         };
 
+        // Borders and the icon take 5 columns
+        let style = Style::default().fg(Color::White);
+        let mut spans = vec![Span::raw(icon.to_string())];
+        spans.extend(ui_common::input_spans(
+            input,
+            popup_area.width.saturating_sub(5) as usize,
+            style,
+        ));
+
         f.render_widget(Clear, popup_area);
         f.render_widget(
-            Paragraph::new(format!("🌐 {}", input))
-                .style(Style::default().fg(Color::White))
-                .block(Block::default().borders(Borders::ALL).title("Enter URL")),
+            Paragraph::new(Line::from(spans)).style(style).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(title.to_string()),
+            ),
             popup_area,
         );
     }
@@ -571,6 +1165,7 @@ impl UI {
         error_message: &str,
         suggestions: &[String],
         selected_index: usize,
+        emoji: bool,
     ) {
         let area = f.size();
         let popup_area = Rect {
@@ -596,7 +1191,11 @@ impl UI {
             Paragraph::new(format!("Failed to load: {}", error_message))
                 .style(Style::default().fg(Color::Red))
                 .wrap(Wrap { trim: true })
-                .block(Block::default().borders(Borders::ALL).title("❌ Error")),
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title(emoji::chrome("❌ Error", emoji).into_owned()),
+                ),
             chunks[0],
         );
 
@@ -604,7 +1203,11 @@ impl UI {
             Paragraph::new(format!("Original: {}", original_url))
                 .style(Style::default().fg(Color::Yellow))
                 .wrap(Wrap { trim: true })
-                .block(Block::default().borders(Borders::ALL).title("🔗 URL")),
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title(emoji::chrome("🔗 URL", emoji).into_owned()),
+                ),
             chunks[1],
         );
 
@@ -629,7 +1232,7 @@ impl UI {
                 .block(
                     Block::default()
                         .borders(Borders::ALL)
-                        .title("💡 Suggestions"),
+                        .title(emoji::chrome("💡 Suggestions", emoji).into_owned()),
                 )
                 .highlight_style(Style::default().fg(Color::Black).bg(Color::Cyan)),
             chunks[2],
@@ -638,12 +1241,16 @@ impl UI {
         f.render_widget(
             Paragraph::new("↑↓ Select • Enter Confirm • Esc Cancel • q Quit")
                 .style(Style::default().fg(Color::Gray))
-                .block(Block::default().borders(Borders::ALL).title("⌨️ Controls")),
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title(emoji::chrome("⌨️ Controls", emoji).into_owned()),
+                ),
             chunks[3],
         );
     }
 
-    fn render_error(f: &mut Frame, message: &str) {
+    fn render_error(f: &mut Frame, message: &str, emoji: bool) {
         let area = f.size();
         let popup_area = Rect {
             x: area.width / 8,
@@ -657,59 +1264,133 @@ impl UI {
             Paragraph::new(format!("{}\n\nPress any key to dismiss", message))
                 .style(Style::default().fg(Color::Red).add_modifier(Modifier::BOLD))
                 .wrap(Wrap { trim: true })
-                .block(Block::default().borders(Borders::ALL).title("❌ Error")),
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title(emoji::chrome("❌ Error", emoji).into_owned()),
+                ),
             popup_area,
         );
     }
 
-    fn get_user_input_internal(&mut self, state: &BrowserState) -> Result<UserAction> {
-        loop {
-            if let Event::Key(key) = event::read()? {
-                match state {
-                    BrowserState::URLInput { input } => match key.code {
-                        KeyCode::Esc => return Ok(UserAction::CancelInput),
-                        KeyCode::Enter => return Ok(UserAction::ConfirmInput(input.clone())),
-                        KeyCode::Backspace => return Ok(UserAction::Backspace),
-                        KeyCode::Char(c) => return Ok(UserAction::InputChar(c)),
-                        _ => continue,
-                    },
-                    BrowserState::History { .. } => return Ok(UserAction::GoBack),
-                    BrowserState::URLSuggestions { .. } => match key.code {
-                        KeyCode::Esc => return Ok(UserAction::CancelInput),
-                        KeyCode::Char('q') => return Ok(UserAction::Quit),
-                        KeyCode::Up => return Ok(UserAction::SelectPrevSuggestion),
-                        KeyCode::Down => return Ok(UserAction::SelectNextSuggestion),
-                        KeyCode::Enter => return Ok(UserAction::ConfirmSuggestion),
-                        _ => continue,
-                    },
-                    BrowserState::Error { .. } => return Ok(UserAction::DismissError),
-                    _ => match key.code {
-                        KeyCode::Char('q') => return Ok(UserAction::Quit),
-                        KeyCode::Char('b') => return Ok(UserAction::GoBack),
-                        KeyCode::Char('f') => return Ok(UserAction::GoForward),
-                        KeyCode::Char('h') => return Ok(UserAction::ShowHistory),
-                        KeyCode::Char('g') => return Ok(UserAction::EnterUrl),
-                        KeyCode::Char('r') => return Ok(UserAction::Refresh),
-                        KeyCode::Up if key.modifiers.contains(KeyModifiers::SHIFT) => {
-                            return Ok(UserAction::SelectPrevLink)
-                        }
-                        KeyCode::Down if key.modifiers.contains(KeyModifiers::SHIFT) => {
-                            return Ok(UserAction::SelectNextLink)
-                        }
-                        KeyCode::Up => return Ok(UserAction::ScrollUp),
-                        KeyCode::Down => return Ok(UserAction::ScrollDown),
-                        KeyCode::Enter => return Ok(UserAction::FollowSelectedLink),
-                        KeyCode::Char(c) if c.is_ascii_digit() => {
-                            let digit = c.to_digit(10).unwrap() as usize;
-                            if digit > 0 {
-                                return Ok(UserAction::FollowLink(digit));
-                            }
-                        }
-                        _ => continue,
-                    },
-                }
-            }
-        }
+    fn render_confirm(f: &mut Frame, message: &str, emoji: bool) {
+        let area = f.size();
+        let popup_area = Rect {
+            x: area.width / 4,
+            y: area.height / 2 - 3,
+            width: area.width / 2,
+            height: 6,
+        };
+
+        f.render_widget(Clear, popup_area);
+        f.render_widget(
+            Paragraph::new(format!(
+                "{}\n\nPress y to confirm, any other key to cancel",
+                message
+            ))
+            .style(
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .wrap(Wrap { trim: true })
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(emoji::chrome("❓ Confirm", emoji).into_owned()),
+            ),
+            popup_area,
+        );
+    }
+
+    fn render_settings(
+        f: &mut Frame,
+        items: &[SettingsItem],
+        selected: usize,
+        status: Option<&str>,
+        emoji: bool,
+    ) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(3), Constraint::Length(3)])
+            .split(f.size());
+
+        let list_items: Vec<ListItem> = items
+            .iter()
+            .enumerate()
+            .map(|(i, item)| {
+                let marker = if i == selected {
+                    emoji::chrome("➤ ", emoji)
+                } else {
+                    "  ".into()
+                };
+                let style = if i == selected {
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(Color::White)
+                };
+                ListItem::new(format!("{}{:<32} {}", marker, item.label, item.value)).style(style)
+            })
+            .collect();
+
+        f.render_widget(
+            List::new(list_items).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(emoji::chrome("⚙️ Settings", emoji).into_owned()),
+            ),
+            chunks[0],
+        );
+
+        let help = status.unwrap_or("↑↓ Select  ←→/Enter Change  w Save  Esc Close");
+        f.render_widget(
+            Paragraph::new(help.to_string())
+                .style(Style::default().fg(Color::Gray))
+                .block(Block::default().borders(Borders::ALL)),
+            chunks[1],
+        );
+    }
+
+    fn render_info(f: &mut Frame, title: &str, items: &[SettingsItem]) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(3), Constraint::Length(3)])
+            .split(f.size());
+
+        let list_items: Vec<ListItem> = items
+            .iter()
+            .map(|item| {
+                ListItem::new(Line::from(vec![
+                    Span::styled(
+                        format!("  {:<20} ", item.label),
+                        Style::default().fg(Color::Yellow),
+                    ),
+                    Span::styled(item.value.clone(), Style::default().fg(Color::White)),
+                ]))
+            })
+            .collect();
+
+        f.render_widget(
+            List::new(list_items).block(Block::default().borders(Borders::ALL).title(title)),
+            chunks[0],
+        );
+
+        f.render_widget(
+            Paragraph::new("Press any key to close")
+                .style(Style::default().fg(Color::Gray))
+                .block(Block::default().borders(Borders::ALL)),
+            chunks[1],
+        );
+    }
+
+    fn get_user_input_internal(
+        &mut self,
+        state: &BrowserState,
+        timeout: Option<Duration>,
+    ) -> Result<UserAction> {
+        self.input.next_action(state, timeout)
     }
 
     fn update_links_scroll(&mut self) {
@@ -721,23 +1402,29 @@ impl UI {
             ui_common::update_links_scroll(self.selected_link, self.links_scroll, visible_height);
     }
 
-    fn update_max_scroll(&mut self, summary: &str) {
+    fn update_max_scroll(&mut self, summary: &str, raw: bool) {
         let terminal_size = self
             .terminal
             .size()
             .unwrap_or(ratatui::layout::Rect::new(0, 0, 80, 24));
 
         // Match render_summary calculations exactly
-        let content_width = terminal_size.width * 80 / 100; // 80% for content area
+        let content_width = ui_common::content_width(terminal_size.width, self.content_percent);
         let width = content_width.saturating_sub(4) as usize; // same as area.width.saturating_sub(4)
         let main_content_height = terminal_size.height.saturating_sub(5 + 3); // header + footer
         let visible_height = main_content_height.saturating_sub(2) as usize; // same as area.height.saturating_sub(2)
 
+        let (width, _) = ui_common::text_width(width, self.max_width);
+        self.summary_width = width;
+
         self.max_scroll = ui_common::calculate_max_scroll_for_markdown(
             summary,
             width,
+            self.line_spacing,
             visible_height,
+            raw,
             Self::style_markdown_element,
         );
+        self.scroll_position = self.scroll_position.min(self.max_scroll);
     }
 }
@@ -2,12 +2,19 @@
 // A single-screen interface with integrated statistics panel
 // Shows original page size vs compressed summary size
 
-use super::{BrowserState, UIInterface, UserAction};
-use crate::common::{markdown::MarkdownElement, ui as ui_common};
-use crate::links::Link;
+use super::{BrowserState, ComparePane, LinkFind, SettingsItem, UIInterface, UserAction};
+use crate::common::{
+    diff, emoji,
+    hyperlink::{self, Hyperlink},
+    keys,
+    markdown::MarkdownElement,
+    text::{display_width, truncate_at_word_boundary},
+    ui::{self as ui_common, LinkLine},
+};
+use crate::links;
 use anyhow::Result;
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyModifiers},
+    event::{DisableMouseCapture, EnableMouseCapture},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
@@ -20,14 +27,35 @@ use ratatui::{
     Frame, Terminal,
 };
 use std::io::{self, Stdout};
+use std::time::Duration;
 use textwrap::fill;
 
+/// Share of the window width given to the content unless `--split-ratio` is set
+const CONTENT_PERCENT: u16 = 65;
+
 pub struct ExpiUI {
     terminal: Terminal<CrosstermBackend<Stdout>>,
     scroll_position: u16,
     selected_link: usize,
     links_scroll: usize,
     max_scroll: u16,
+    /// Summary wrap width from the last render, for locating text in it
+    summary_width: usize,
+    scroll_accel: ui_common::ScrollAccel,
+    pane_scroll: ui_common::PaneScroll,
+    /// Share of the window width given to the content
+    content_percent: u16,
+    /// Widest the content text wraps, centred in its pane when narrower
+    max_width: Option<u16>,
+    /// Blank line after every block of the content
+    line_spacing: bool,
+    /// Characters of a summary shown until it is expanded
+    summary_preview: Option<usize>,
+    /// Make links clickable with OSC 8 escape sequences
+    hyperlinks: bool,
+    /// Show emoji in the chrome rather than their ASCII stand-ins
+    emoji: bool,
+    input: keys::InputReader,
 }
 
 // Traditional browser color scheme - optimized for dark terminals
@@ -55,6 +83,16 @@ impl UIInterface for ExpiUI {
             selected_link: 0,
             links_scroll: 0,
             max_scroll: 0,
+            summary_width: 0,
+            scroll_accel: ui_common::ScrollAccel::default(),
+            pane_scroll: ui_common::PaneScroll::default(),
+            content_percent: CONTENT_PERCENT,
+            max_width: None,
+            line_spacing: false,
+            summary_preview: None,
+            hyperlinks: false,
+            emoji: true,
+            input: keys::InputReader::default(),
         })
     }
 
@@ -69,26 +107,40 @@ impl UIInterface for ExpiUI {
         Ok(())
     }
 
+    fn clear(&mut self) -> Result<()> {
+        self.terminal.clear()?;
+        Ok(())
+    }
+
     fn render(&mut self, state: &BrowserState) -> Result<()> {
         match state {
             BrowserState::Loading {
                 url,
                 progress,
                 stage,
+                thinking,
             } => {
+                let mut status = format!("Loading: {}% - {}", progress, stage.label());
+                if let Some(thinking) = thinking {
+                    status.push_str(&format!("\n\n**{}**", thinking));
+                }
                 // Even loading uses the same static interface
                 self.terminal.draw(|f| {
                     Self::render_static_browser(
                         f,
+                        self.content_percent,
+                        self.max_width,
+                        self.line_spacing,
                         url,
                         "Loading...",
-                        &format!("Loading: {}% - {}", progress, stage),
+                        &status,
                         &[],
                         self.scroll_position,
                         self.selected_link,
                         self.links_scroll,
                         None, // No stats during loading
-                    )
+                        None,
+                    );
                 })?;
             }
             BrowserState::Page {
@@ -96,10 +148,18 @@ impl UIInterface for ExpiUI {
                 title,
                 summary,
                 links,
+                meta,
+                link_find,
+                link_groups,
+                notice,
+                diff_from,
+                raw,
+                expanded,
             } => {
                 // Calculate page statistics
                 let original_size = summary.len();
                 let compressed_size = summary.split_whitespace().count() * 5; // Rough estimate
+                let (internal_links, external_links) = links::internal_external(url, links);
                 let stats = PageStats {
                     original_size,
                     compressed_size,
@@ -109,30 +169,53 @@ impl UIInterface for ExpiUI {
                         0.0
                     },
                     link_count: links.len(),
+                    internal_links,
+                    external_links,
+                    links_note: meta.links_note(links.len()),
+                    response: meta.header_label(self.emoji),
+                    notice: notice.clone(),
+                    raw: *raw,
                 };
 
-                self.terminal.draw(|f| {
+                let link_lines = ui_common::link_lines(links, link_groups.as_deref());
+                let diff_from = diff_from.as_deref().filter(|_| !*raw);
+                let summary = ui_common::shown_summary(summary, *expanded, self.summary_preview);
+                let summary = &diff::summary_with_changes(&summary, diff_from);
+
+                let (content_percent, max_width, line_spacing) =
+                    (self.content_percent, self.max_width, self.line_spacing);
+                let (scroll_pos, selected_link, links_scroll) =
+                    (self.scroll_position, self.selected_link, self.links_scroll);
+
+                hyperlink::draw(&mut self.terminal, self.hyperlinks, |f| {
                     Self::render_static_browser(
                         f,
+                        content_percent,
+                        max_width,
+                        line_spacing,
                         url,
                         title,
                         summary,
-                        links,
-                        self.scroll_position,
-                        self.selected_link,
-                        self.links_scroll,
+                        &link_lines,
+                        scroll_pos,
+                        selected_link,
+                        links_scroll,
                         Some(&stats),
+                        link_find.as_ref(),
                     )
                 })?;
 
-                self.update_max_scroll(summary);
+                self.update_max_scroll(summary, *raw);
                 self.update_links_scroll_with_height(15); // Fixed height for links area
             }
             BrowserState::URLInput { input } => {
                 self.terminal.draw(|f| {
                     Self::render_static_browser(
                         f,
-                        input,
+                        self.content_percent,
+                        self.max_width,
+                        self.line_spacing,
+                        &input.with_caret('│'),
                         "Enter URL",
                         "Type a URL and press Enter to navigate",
                         &[],
@@ -140,7 +223,27 @@ impl UIInterface for ExpiUI {
                         0,
                         0,
                         None,
-                    )
+                        None,
+                    );
+                })?;
+            }
+            BrowserState::Prompt { title, input, .. } => {
+                self.terminal.draw(|f| {
+                    Self::render_static_browser(
+                        f,
+                        self.content_percent,
+                        self.max_width,
+                        self.line_spacing,
+                        &input.with_caret('│'),
+                        title,
+                        "Type in the address bar and press Enter, or Esc to cancel",
+                        &[],
+                        0,
+                        0,
+                        0,
+                        None,
+                        None,
+                    );
                 })?;
             }
             BrowserState::URLSuggestions {
@@ -157,6 +260,9 @@ impl UIInterface for ExpiUI {
                 self.terminal.draw(|f| {
                     Self::render_static_browser(
                         f,
+                        self.content_percent,
+                        self.max_width,
+                        self.line_spacing,
                         original_url,
                         "Navigation Error",
                         &suggestion_text,
@@ -165,20 +271,43 @@ impl UIInterface for ExpiUI {
                         0,
                         0,
                         None,
-                    )
+                        None,
+                    );
                 })?;
             }
-            BrowserState::History { entries, current_index: _ } => {
+            BrowserState::History { entries, current_index } => {
                 let history_text = entries
                     .iter()
                     .enumerate()
-                    .map(|(i, entry)| format!("{}. {} - {}", i + 1, entry.title, entry.url))
+                    .map(|(i, entry)| {
+                        let marker = if Some(i) == *current_index {
+                            emoji::chrome("➤", self.emoji)
+                        } else {
+                            " ".into()
+                        };
+                        format!(
+                            "{}{}. {} - {} ({})",
+                            marker,
+                            i + 1,
+                            entry.title,
+                            entry.url,
+                            entry.age_label()
+                        )
+                    })
                     .collect::<Vec<_>>()
                     .join("\n");
+                let history_text = if entries.is_empty() {
+                    ui_common::EMPTY_HISTORY.to_string()
+                } else {
+                    history_text
+                };
                 
                 self.terminal.draw(|f| {
                     Self::render_static_browser(
                         f,
+                        self.content_percent,
+                        self.max_width,
+                        self.line_spacing,
                         "chrome://history",
                         "Browse History",
                         &history_text,
@@ -187,13 +316,62 @@ impl UIInterface for ExpiUI {
                         0,
                         0,
                         None,
-                    )
+                        None,
+                    );
+                })?;
+            }
+            BrowserState::Bookmarks { entries, filter, selected } => {
+                let mut lines = vec![
+                    format!("Filter: {}_", filter),
+                    String::new(),
+                ];
+                if entries.is_empty() {
+                    lines.push(ui_common::empty_bookmarks(filter).to_string());
+                }
+                for (i, entry) in entries.iter().enumerate() {
+                    let marker = if i == *selected {
+                        emoji::chrome("➤", self.emoji)
+                    } else {
+                        " ".into()
+                    };
+                    lines.push(format!(
+                        "{}{}. {} - {} {}",
+                        marker,
+                        i + 1,
+                        entry.title,
+                        entry.url,
+                        entry.tags_label()
+                    ));
+                }
+                lines.push(String::new());
+                lines.push(ui_common::BOOKMARKS_HELP.to_string());
+                let bookmarks_text = lines.join("\n");
+
+                self.terminal.draw(|f| {
+                    Self::render_static_browser(
+                        f,
+                        self.content_percent,
+                        self.max_width,
+                        self.line_spacing,
+                        "chrome://bookmarks",
+                        "Bookmarks",
+                        &bookmarks_text,
+                        &[],
+                        0,
+                        0,
+                        0,
+                        None,
+                        None,
+                    );
                 })?;
             }
             BrowserState::Error { message } => {
                 self.terminal.draw(|f| {
                     Self::render_static_browser(
                         f,
+                        self.content_percent,
+                        self.max_width,
+                        self.line_spacing,
                         "about:error",
                         "Error",
                         &format!("An error occurred:\n\n{}", message),
@@ -202,66 +380,112 @@ impl UIInterface for ExpiUI {
                         0,
                         0,
                         None,
-                    )
+                        None,
+                    );
+                })?;
+            }
+            BrowserState::Confirm { message, .. } => {
+                self.terminal.draw(|f| {
+                    Self::render_static_browser(
+                        f,
+                        self.content_percent,
+                        self.max_width,
+                        self.line_spacing,
+                        "about:confirm",
+                        "Confirm",
+                        &format!("{}\n\nPress y to confirm, any other key to cancel", message),
+                        &[],
+                        0,
+                        0,
+                        0,
+                        None,
+                        None,
+                    );
+                })?;
+            }
+            BrowserState::Settings {
+                items,
+                selected,
+                status,
+            } => {
+                let content = Self::settings_content(items, *selected, status.as_deref());
+                self.terminal.draw(|f| {
+                    Self::render_static_browser(
+                        f,
+                        self.content_percent,
+                        self.max_width,
+                        self.line_spacing,
+                        "about:settings",
+                        "Settings",
+                        &content,
+                        &[],
+                        0,
+                        0,
+                        0,
+                        None,
+                        None,
+                    );
+                })?;
+            }
+            BrowserState::Info { items }
+            | BrowserState::Stats { items }
+            | BrowserState::Extraction { items } => {
+                let (url, title) = match state {
+                    BrowserState::Info { .. } => ("about:info", "About bbow"),
+                    BrowserState::Stats { .. } => ("about:stats", "Session stats"),
+                    _ => ("about:extraction", "Extraction"),
+                };
+                let content = Self::info_content(title, items);
+                self.terminal.draw(|f| {
+                    Self::render_static_browser(
+                        f,
+                        self.content_percent,
+                        self.max_width,
+                        self.line_spacing,
+                        url,
+                        title,
+                        &content,
+                        &[],
+                        0,
+                        0,
+                        0,
+                        None,
+                        None,
+                    );
                 })?;
             }
+            BrowserState::Compare {
+                url,
+                title,
+                left,
+                right,
+                focus,
+            } => {
+                let (positions, line_spacing) = (self.pane_scroll.positions(), self.line_spacing);
+                let mut max_scroll = [0; 2];
+                self.terminal.draw(|f| {
+                    max_scroll = Self::render_compare(
+                        f,
+                        url,
+                        title,
+                        [left, right],
+                        *focus,
+                        positions,
+                        line_spacing,
+                    );
+                })?;
+                self.pane_scroll.set_max(max_scroll);
+            }
         }
         Ok(())
     }
 
-    fn get_user_input(&mut self, state: &BrowserState) -> Result<UserAction> {
-        loop {
-            if let Event::Key(key) = event::read()? {
-                match state {
-                    BrowserState::URLInput { .. } => match key.code {
-                        KeyCode::Esc => return Ok(UserAction::CancelInput),
-                        KeyCode::Enter => {
-                            // Get the current input from state
-                            if let BrowserState::URLInput { input } = state {
-                                return Ok(UserAction::ConfirmInput(input.clone()));
-                            }
-                        }
-                        KeyCode::Backspace => return Ok(UserAction::Backspace),
-                        KeyCode::Char(c) => return Ok(UserAction::InputChar(c)),
-                        _ => continue,
-                    },
-                    BrowserState::History { .. } => return Ok(UserAction::GoBack),
-                    BrowserState::URLSuggestions { .. } => match key.code {
-                        KeyCode::Esc => return Ok(UserAction::CancelInput),
-                        KeyCode::Char('q') => return Ok(UserAction::Quit),
-                        KeyCode::Up => return Ok(UserAction::SelectPrevSuggestion),
-                        KeyCode::Down => return Ok(UserAction::SelectNextSuggestion),
-                        KeyCode::Enter => return Ok(UserAction::ConfirmSuggestion),
-                        _ => continue,
-                    },
-                    BrowserState::Error { .. } => return Ok(UserAction::DismissError),
-                    _ => match key.code {
-                        KeyCode::Char('q') => return Ok(UserAction::Quit),
-                        KeyCode::Char('b') => return Ok(UserAction::GoBack),
-                        KeyCode::Char('f') => return Ok(UserAction::GoForward),
-                        KeyCode::Char('h') => return Ok(UserAction::ShowHistory),
-                        KeyCode::Char('g') => return Ok(UserAction::EnterUrl),
-                        KeyCode::Char('r') => return Ok(UserAction::Refresh),
-                        KeyCode::Up if key.modifiers.contains(KeyModifiers::SHIFT) => {
-                            return Ok(UserAction::SelectPrevLink)
-                        }
-                        KeyCode::Down if key.modifiers.contains(KeyModifiers::SHIFT) => {
-                            return Ok(UserAction::SelectNextLink)
-                        }
-                        KeyCode::Up => return Ok(UserAction::ScrollUp),
-                        KeyCode::Down => return Ok(UserAction::ScrollDown),
-                        KeyCode::Enter => return Ok(UserAction::FollowSelectedLink),
-                        KeyCode::Char(c) if c.is_ascii_digit() => {
-                            let digit = c.to_digit(10).unwrap() as usize;
-                            if digit > 0 {
-                                return Ok(UserAction::FollowLink(digit));
-                            }
-                        }
-                        _ => continue,
-                    },
-                }
-            }
-        }
+    fn get_user_input(
+        &mut self,
+        state: &BrowserState,
+        timeout: Option<Duration>,
+    ) -> Result<UserAction> {
+        self.input.next_action(state, timeout)
     }
 
     fn reset_scroll(&mut self) {
@@ -270,14 +494,73 @@ impl UIInterface for ExpiUI {
         self.links_scroll = 0;
     }
 
+    fn scroll_position(&self) -> u16 {
+        self.scroll_position
+    }
+
+    fn summary_width(&self) -> usize {
+        self.summary_width
+    }
+
+    fn set_scroll_position(&mut self, position: u16) {
+        self.scroll_position = position;
+    }
+
+    fn max_scroll(&self) -> u16 {
+        self.max_scroll
+    }
+
+    fn scroll_to_text(&mut self, summary: &str, text: &str) -> bool {
+        match ui_common::find_text_line(summary, self.summary_width, self.line_spacing, text) {
+            Some(line) => {
+                self.scroll_position = line;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn scroll_pane(&mut self, pane: usize, lines: i32) {
+        self.pane_scroll.scroll(pane, lines);
+    }
+
+    fn reset_pane_scroll(&mut self) {
+        self.pane_scroll.reset();
+    }
+
+    fn set_split_ratio(&mut self, content_percent: u16) {
+        self.content_percent = content_percent;
+    }
+
+    fn set_max_width(&mut self, columns: u16) {
+        self.max_width = Some(columns);
+    }
+
+    fn set_line_spacing(&mut self, enabled: bool) {
+        self.line_spacing = enabled;
+    }
+
+    fn set_summary_preview(&mut self, chars: Option<usize>) {
+        self.summary_preview = chars;
+    }
+
+    fn set_hyperlinks(&mut self, enabled: bool) {
+        self.hyperlinks = enabled;
+    }
+
+    fn set_emoji(&mut self, enabled: bool) {
+        self.emoji = enabled;
+    }
+
     fn scroll_up(&mut self) {
-        self.scroll_position = self.scroll_position.saturating_sub(1);
+        let step = self.scroll_accel.step(false);
+        self.scroll_position = self.scroll_position.saturating_sub(step);
     }
 
     fn scroll_down(&mut self) {
-        if self.scroll_position < self.max_scroll {
-            self.scroll_position += 1;
-        }
+        let step = self.scroll_accel.step(true);
+        self.scroll_position =
+            ui_common::scroll_down_clamped(self.scroll_position, step, self.max_scroll);
     }
 
     fn select_prev_link(&mut self, links_len: usize) {
@@ -297,6 +580,11 @@ impl UIInterface for ExpiUI {
     fn get_selected_link(&self) -> usize {
         self.selected_link
     }
+
+    fn select_link(&mut self, index: usize) {
+        self.selected_link = index;
+        self.update_links_scroll();
+    }
 }
 
 #[derive(Debug)]
@@ -305,21 +593,35 @@ struct PageStats {
     compressed_size: usize,
     compression_ratio: f32,
     link_count: usize,
+    /// Links to the page's own host and to other hosts
+    internal_links: usize,
+    external_links: usize,
+    /// "showing 30 of 142" when `--max-links` hid some links
+    links_note: Option<String>,
+    response: Option<String>,
+    /// One-off feedback shown in the status bar instead of "Ready"
+    notice: Option<String>,
+    /// Show the summary's markdown as it is instead of rendered
+    raw: bool,
 }
 
 impl ExpiUI {
     #[allow(clippy::too_many_arguments)]
     fn render_static_browser(
         f: &mut Frame,
+        content_percent: u16,
+        max_width: Option<u16>,
+        line_spacing: bool,
         url: &str,
         title: &str,
         content: &str,
-        links: &[Link],
+        links: &[LinkLine],
         scroll_pos: u16,
         selected_link: usize,
         links_scroll: usize,
         stats: Option<&PageStats>,
-    ) {
+        link_find: Option<&LinkFind>,
+    ) -> Vec<Hyperlink> {
         let area = f.size();
 
         // Traditional browser layout: Title bar, Address bar, Content, Status bar
@@ -333,17 +635,38 @@ impl ExpiUI {
             ])
             .split(area);
 
-        // Title bar (like window title)
+        // Title bar (like window title), shortened so the app name stays visible
+        let suffix = " - BBOW Browser";
+        let title_width = (area.width as usize).saturating_sub(suffix.len());
         f.render_widget(
-            Paragraph::new(format!("{} - BBOW Browser", title))
+            Paragraph::new(format!(
+                "{}{}",
+                truncate_at_word_boundary(title, title_width),
+                suffix
+            ))
                 .style(Style::default().fg(TEXT_PRIMARY).bg(STATUS_BAR))
                 .alignment(Alignment::Left),
             main_chunks[0],
         );
 
-        // Address bar
+        // Address bar, with the response status when a page is loaded; a URL
+        // too long for the bar keeps its start and end
+        let response = stats.and_then(|s| s.response.as_deref());
+        let url_width = (main_chunks[1].width as usize)
+            .saturating_sub(2 + "Address: ".len())
+            .saturating_sub(response.map_or(0, |r| display_width(r) + 5));
+        let mut address = vec![Span::raw(format!(
+            "Address: {}",
+            ui_common::truncate_middle(url, url_width)
+        ))];
+        if let Some(response) = response {
+            address.push(Span::styled(
+                format!("   [{}]", response),
+                Style::default().fg(TEXT_SECONDARY),
+            ));
+        }
         f.render_widget(
-            Paragraph::new(format!("Address: {}", url))
+            Paragraph::new(Line::from(address))
                 .style(Style::default().fg(TEXT_PRIMARY))
                 .block(
                     Block::default()
@@ -354,17 +677,20 @@ impl ExpiUI {
             main_chunks[1],
         );
 
-        // Content area split between main content and sidebar
-        let content_chunks = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([
-                Constraint::Percentage(65), // Main content
-                Constraint::Percentage(35), // Sidebar (links + stats)
-            ])
-            .split(main_chunks[2]);
+        // Content area split between main content and sidebar (links + stats)
+        let content_chunks = ui_common::split_content(main_chunks[2], content_percent);
 
         // Main content area
-        Self::render_main_content(f, content_chunks[0], content, scroll_pos);
+        let raw = stats.is_some_and(|stats| stats.raw);
+        Self::render_main_content(
+            f,
+            content_chunks[0],
+            content,
+            scroll_pos,
+            max_width,
+            line_spacing,
+            raw,
+        );
 
         // Sidebar split between links and stats
         let sidebar_chunks = Layout::default()
@@ -375,23 +701,171 @@ impl ExpiUI {
             ])
             .split(content_chunks[1]);
 
-        Self::render_links_panel(f, sidebar_chunks[0], links, selected_link, links_scroll);
+        let hyperlinks = Self::render_links_panel(
+            f,
+            sidebar_chunks[0],
+            links,
+            selected_link,
+            links_scroll,
+            link_find,
+            stats.and_then(|s| s.links_note.as_deref()),
+        );
         Self::render_stats_panel(f, sidebar_chunks[1], stats);
 
         // Status bar
-        Self::render_status_bar(f, main_chunks[3], content, links);
+        let notice = stats.and_then(|s| s.notice.as_deref());
+        let link_count = stats.map_or(links.len(), |s| s.link_count);
+        Self::render_status_bar(f, main_chunks[3], content, link_count, notice);
+        hyperlinks
     }
 
-    fn render_main_content(f: &mut Frame, area: Rect, content: &str, scroll_pos: u16) {
-        let width = area.width.saturating_sub(4) as usize;
+    /// Same window chrome as the browser, with the two summaries as side-by-side
+    /// content areas; returns their max scroll
+    fn render_compare(
+        f: &mut Frame,
+        url: &str,
+        title: &str,
+        panes: [&ComparePane; 2],
+        focus: usize,
+        positions: [u16; 2],
+        line_spacing: bool,
+    ) -> [u16; 2] {
+        let area = f.size();
+
+        let main_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1),  // Title bar
+                Constraint::Length(3),  // Address bar
+                Constraint::Min(10),    // Content areas
+                Constraint::Length(3),  // Status bar
+            ])
+            .split(area);
+
+        let suffix = " - Compare - BBOW Browser";
+        let title_width = (area.width as usize).saturating_sub(suffix.len());
+        f.render_widget(
+            Paragraph::new(format!(
+                "{}{}",
+                truncate_at_word_boundary(title, title_width),
+                suffix
+            ))
+                .style(Style::default().fg(TEXT_PRIMARY).bg(STATUS_BAR))
+                .alignment(Alignment::Left),
+            main_chunks[0],
+        );
+
+        let url_width = (main_chunks[1].width as usize).saturating_sub(2 + "Address: ".len());
+        f.render_widget(
+            Paragraph::new(format!("Address: {}", ui_common::truncate_middle(url, url_width)))
+                .style(Style::default().fg(TEXT_PRIMARY))
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_style(Style::default().fg(BORDER_GRAY))
+                        .style(Style::default().bg(ADDRESS_BAR))
+                ),
+            main_chunks[1],
+        );
+
+        let style = ui_common::CompareStyle {
+            text: Style::default().fg(TEXT_PRIMARY),
+            block: |label: &str, focused: bool| {
+                let border = if focused { LINK_BLUE } else { BORDER_GRAY };
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(border))
+                    .title(label.to_string())
+                    .title_style(Style::default().fg(TEXT_SECONDARY))
+            },
+            markdown: Self::style_markdown_element,
+        };
+        let max_scroll = ui_common::render_compare_panes(
+            f,
+            main_chunks[2],
+            panes,
+            focus,
+            positions,
+            line_spacing,
+            &style,
+        );
+
+        let words = panes.map(|pane| pane.summary.split_whitespace().count());
+        f.render_widget(
+            Paragraph::new(Line::from(vec![
+                Span::styled("Comparing", Style::default().fg(SUCCESS_GREEN)),
+                Span::raw("  |  "),
+                Span::styled(
+                    format!("A: {} words, B: {} words", words[0], words[1]),
+                    Style::default().fg(TEXT_SECONDARY),
+                ),
+                Span::raw("  |  "),
+                Span::styled(
+                    "Tab:Switch pane Esc:Back",
+                    Style::default().fg(TEXT_SECONDARY),
+                ),
+            ]))
+                .style(Style::default().fg(TEXT_SECONDARY).bg(STATUS_BAR))
+                .block(
+                    Block::default()
+                        .borders(Borders::TOP)
+                        .border_style(Style::default().fg(BORDER_GRAY))
+                ),
+            main_chunks[3],
+        );
+
+        max_scroll
+    }
+
+    fn settings_content(items: &[SettingsItem], selected: usize, status: Option<&str>) -> String {
+        let mut content = String::from("## Settings\n\n");
+        for (i, item) in items.iter().enumerate() {
+            if i == selected {
+                content.push_str(&format!("- **{}: {}**\n", item.label, item.value));
+            } else {
+                content.push_str(&format!("- {}: {}\n", item.label, item.value));
+            }
+        }
+        content.push_str("\n*Up/Down select, Left/Right or Enter change, w save, Esc close*");
+        if let Some(status) = status {
+            content.push_str(&format!("\n\n{}", status));
+        }
+        content
+    }
+
+    fn info_content(title: &str, items: &[SettingsItem]) -> String {
+        let mut content = format!("## {}\n\n", title);
+        for item in items {
+            content.push_str(&format!("- **{}:** {}\n", item.label, item.value));
+        }
+        content.push_str("\n*Press any key to close*");
+        content
+    }
+
+    fn render_main_content(
+        f: &mut Frame,
+        area: Rect,
+        content: &str,
+        scroll_pos: u16,
+        max_width: Option<u16>,
+        line_spacing: bool,
+        raw: bool,
+    ) {
+        let (width, indent) =
+            ui_common::text_width(area.width.saturating_sub(4) as usize, max_width);
         let visible_height = area.height.saturating_sub(2) as usize;
 
-        let visible_lines = ui_common::get_visible_markdown_lines(
-            content,
-            width,
-            scroll_pos,
-            visible_height,
-            Self::style_markdown_element,
+        let visible_lines = ui_common::indent_lines(
+            ui_common::get_visible_markdown_lines(
+                content,
+                width,
+                line_spacing,
+                scroll_pos,
+                visible_height,
+                raw,
+                Self::style_markdown_element,
+            ),
+            indent,
         );
 
         f.render_widget(
@@ -412,10 +886,23 @@ impl ExpiUI {
     fn render_links_panel(
         f: &mut Frame,
         area: Rect,
-        links: &[Link],
+        links: &[LinkLine],
         selected_link: usize,
         links_scroll: usize,
-    ) {
+        link_find: Option<&LinkFind>,
+        links_note: Option<&str>,
+    ) -> Vec<Hyperlink> {
+        let title = match link_find {
+            Some(find) => match find.count_label(links.len()) {
+                Some(count) => format!("Find: {}_ ({})", find.query, count),
+                None => format!("Find: {}_", find.query),
+            },
+            None => match links_note {
+                Some(note) => format!("Links ({})", note),
+                None => "Links".to_string(),
+            },
+        };
+
         if links.is_empty() {
             f.render_widget(
                 Paragraph::new("No links found")
@@ -425,12 +912,12 @@ impl ExpiUI {
                         Block::default()
                             .borders(Borders::ALL)
                             .border_style(Style::default().fg(BORDER_GRAY))
-                            .title("Links")
+                            .title(title)
                             .title_style(Style::default().fg(TEXT_SECONDARY)),
                     ),
                 area,
             );
-            return;
+            return Vec::new();
         }
 
         let visible_height = area.height.saturating_sub(2) as usize;
@@ -449,11 +936,13 @@ impl ExpiUI {
                         .fg(BACKGROUND)
                         .bg(LINK_BLUE)
                         .add_modifier(Modifier::BOLD)
+                } else if link.header {
+                    Style::default().fg(TEXT_PRIMARY).add_modifier(Modifier::BOLD)
                 } else {
                     Style::default().fg(LINK_BLUE).add_modifier(Modifier::UNDERLINED)
                 };
 
-                let content = format!("[{}] {}", link.index, link.text);
+                let content = link.label();
                 let wrapped_content = fill(&content, area.width.saturating_sub(6) as usize);
                 ListItem::new(wrapped_content).style(style)
             })
@@ -465,11 +954,18 @@ impl ExpiUI {
                     Block::default()
                         .borders(Borders::ALL)
                         .border_style(Style::default().fg(BORDER_GRAY))
-                        .title("Links")
+                        .title(title)
                         .title_style(Style::default().fg(TEXT_SECONDARY)),
                 ),
             area,
         );
+
+        hyperlink::list_links(
+            Block::default().borders(Borders::ALL).inner(area),
+            &links[start_index..end_index],
+            area.width.saturating_sub(6) as usize,
+            LinkLine::label,
+        )
     }
 
     fn render_stats_panel(f: &mut Frame, area: Rect, stats: Option<&PageStats>) {
@@ -503,6 +999,13 @@ impl ExpiUI {
                         Style::default().fg(TEXT_PRIMARY),
                     ),
                 ]),
+                Line::from(vec![
+                    Span::styled("Internal/External: ", Style::default().fg(TEXT_SECONDARY)),
+                    Span::styled(
+                        format!("{} / {}", stats.internal_links, stats.external_links),
+                        Style::default().fg(TEXT_PRIMARY),
+                    ),
+                ]),
             ]
         } else {
             vec![
@@ -526,13 +1029,22 @@ impl ExpiUI {
         );
     }
 
-    fn render_status_bar(f: &mut Frame, area: Rect, content: &str, links: &[Link]) {
+    fn render_status_bar(
+        f: &mut Frame,
+        area: Rect,
+        content: &str,
+        link_count: usize,
+        notice: Option<&str>,
+    ) {
         let word_count = content.split_whitespace().count();
         let char_count = content.len();
         
         let status_text = vec![
             Line::from(vec![
-                Span::styled("Ready", Style::default().fg(SUCCESS_GREEN)),
+                Span::styled(
+                    notice.unwrap_or("Ready").to_string(),
+                    Style::default().fg(SUCCESS_GREEN),
+                ),
                 Span::raw("  |  "),
                 Span::styled(
                     format!("{} words, {} chars", word_count, char_count),
@@ -540,7 +1052,7 @@ impl ExpiUI {
                 ),
                 Span::raw("  |  "),
                 Span::styled(
-                    format!("{} links", links.len()),
+                    format!("{} links", link_count),
                     Style::default().fg(TEXT_SECONDARY),
                 ),
                 Span::raw("  |  "),
@@ -585,6 +1097,17 @@ impl ExpiUI {
                 .bg(ADDRESS_BAR),
             MarkdownElement::Normal(_) => Style::default().fg(TEXT_PRIMARY),
             MarkdownElement::Empty => Style::default(),
+            MarkdownElement::TaskMarker(true) => Style::default().fg(SUCCESS_GREEN),
+            MarkdownElement::TaskMarker(false) => Style::default().fg(TEXT_SECONDARY),
+            MarkdownElement::DefinitionTerm => Style::default()
+                .fg(LINK_BLUE)
+                .add_modifier(Modifier::BOLD),
+            MarkdownElement::DiffAdded => Style::default()
+                .fg(SUCCESS_GREEN)
+                .add_modifier(Modifier::UNDERLINED),
+            MarkdownElement::DiffRemoved => Style::default()
+                .fg(TEXT_SECONDARY)
+                .add_modifier(Modifier::CROSSED_OUT),
         }
     }
 
@@ -597,23 +1120,29 @@ impl ExpiUI {
             ui_common::update_links_scroll(self.selected_link, self.links_scroll, visible_height);
     }
 
-    fn update_max_scroll(&mut self, content: &str) {
+    fn update_max_scroll(&mut self, content: &str, raw: bool) {
         let terminal_size = self
             .terminal
             .size()
             .unwrap_or(ratatui::layout::Rect::new(0, 0, 80, 24));
 
         // Calculate content area dimensions
-        let content_width = terminal_size.width * 65 / 100; // 65% for main content
+        let content_width = ui_common::content_width(terminal_size.width, self.content_percent);
         let width = content_width.saturating_sub(4) as usize;
         let content_height = terminal_size.height.saturating_sub(1 + 3 + 3); // title + address + status
         let visible_height = content_height.saturating_sub(2) as usize;
 
+        let (width, _) = ui_common::text_width(width, self.max_width);
+        self.summary_width = width;
+
         self.max_scroll = ui_common::calculate_max_scroll_for_markdown(
             content,
             width,
+            self.line_spacing,
             visible_height,
+            raw,
             Self::style_markdown_element,
         );
+        self.scroll_position = self.scroll_position.min(self.max_scroll);
     }
 }
\ No newline at end of file
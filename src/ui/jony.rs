@@ -1,12 +1,21 @@
 // Jony Ive-inspired UI for BBOW
 // Embodying principles of simplicity, elegance, and focus on content
 
-use super::{BrowserState, HistoryEntry, UIInterface, UserAction};
-use crate::common::{markdown::MarkdownElement, ui as ui_common};
-use crate::links::Link;
+use super::{
+    BookmarkEntry, BrowserState, ComparePane, HistoryEntry, LinkFind, SettingsItem, TextInput,
+    UIInterface, UserAction,
+};
+use crate::common::{
+    diff,
+    hyperlink::{self, Hyperlink},
+    keys,
+    markdown::MarkdownElement,
+    text::truncate_at_word_boundary,
+    ui::{self as ui_common, LinkLine},
+};
 use anyhow::Result;
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyModifiers},
+    event::{DisableMouseCapture, EnableMouseCapture},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
@@ -15,18 +24,39 @@ use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Margin, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Clear, Gauge, List, ListItem, Paragraph, Wrap},
+    widgets::{Block, Borders, Clear, Gauge, List, ListItem, ListState, Padding, Paragraph, Wrap},
     Frame, Terminal,
 };
 use std::io::{self, Stdout};
+use std::time::Duration;
 use textwrap::fill;
 
+/// Share of the page width given to the summary unless `--split-ratio` is set
+const CONTENT_PERCENT: u16 = 75;
+
 pub struct JonyUI {
     terminal: Terminal<CrosstermBackend<Stdout>>,
     scroll_position: u16,
     selected_link: usize,
     links_scroll: usize,
     max_scroll: u16,
+    /// Summary wrap width from the last render, for locating text in it
+    summary_width: usize,
+    scroll_accel: ui_common::ScrollAccel,
+    pane_scroll: ui_common::PaneScroll,
+    /// Share of the page width given to the summary
+    content_percent: u16,
+    /// Widest the summary text wraps, centred in its pane when narrower
+    max_width: Option<u16>,
+    /// Blank line after every block of a summary
+    line_spacing: bool,
+    /// Characters of a summary shown until it is expanded
+    summary_preview: Option<usize>,
+    /// Make links clickable with OSC 8 escape sequences
+    hyperlinks: bool,
+    /// Show emoji in the chrome rather than their ASCII stand-ins
+    emoji: bool,
+    input: keys::InputReader,
 }
 
 // Jony Ive color palette - optimized for dark terminals
@@ -51,6 +81,16 @@ impl UIInterface for JonyUI {
             selected_link: 0,
             links_scroll: 0,
             max_scroll: 0,
+            summary_width: 0,
+            scroll_accel: ui_common::ScrollAccel::default(),
+            pane_scroll: ui_common::PaneScroll::default(),
+            content_percent: CONTENT_PERCENT,
+            max_width: None,
+            line_spacing: false,
+            summary_preview: None,
+            hyperlinks: false,
+            emoji: true,
+            input: keys::InputReader::default(),
         })
     }
 
@@ -65,42 +105,74 @@ impl UIInterface for JonyUI {
         Ok(())
     }
 
+    fn clear(&mut self) -> Result<()> {
+        self.terminal.clear()?;
+        Ok(())
+    }
+
     fn render(&mut self, state: &BrowserState) -> Result<()> {
         match state {
             BrowserState::Loading {
                 url,
                 progress,
                 stage,
+                thinking,
             } => {
-                let (url, progress, stage) = (url.clone(), *progress, stage.clone());
-                self.terminal
-                    .draw(|f| Self::render_loading(f, &url, progress, &stage))?;
+                self.terminal.draw(|f| {
+                    Self::render_loading(f, url, *progress, stage.label(), thinking.as_deref())
+                })?;
             }
             BrowserState::Page {
                 url,
                 title,
                 summary,
                 links,
+                meta,
+                link_find,
+                link_groups,
+                notice,
+                diff_from,
+                raw,
+                expanded,
             } => {
-                let (url, title, summary, links) =
-                    (url.clone(), title.clone(), summary.clone(), links.clone());
+                let links_note = meta.links_note(links.len());
+                let summary = ui_common::shown_summary(summary, *expanded, self.summary_preview);
+                let (url, title, summary, links, link_find, notice) = (
+                    url.clone(),
+                    title.clone(),
+                    diff::summary_with_changes(&summary, diff_from.as_deref().filter(|_| !*raw)),
+                    ui_common::link_lines(links, link_groups.as_deref()),
+                    link_find.clone(),
+                    notice.clone(),
+                );
+                let status = meta.header_label(self.emoji);
+                let (content_percent, max_width, line_spacing) =
+                    (self.content_percent, self.max_width, self.line_spacing);
                 let (scroll_pos, selected_link, links_scroll) =
                     (self.scroll_position, self.selected_link, self.links_scroll);
 
-                self.terminal.draw(|f| {
+                hyperlink::draw(&mut self.terminal, self.hyperlinks, |f| {
                     Self::render_page(
                         f,
+                        content_percent,
+                        max_width,
+                        line_spacing,
                         &url,
+                        status.as_deref(),
                         &title,
                         &summary,
+                        *raw,
                         &links,
                         scroll_pos,
                         selected_link,
                         links_scroll,
-                    );
+                        link_find.as_ref(),
+                        links_note.as_deref(),
+                        notice.as_deref(),
+                    )
                 })?;
 
-                self.update_max_scroll(&summary);
+                self.update_max_scroll(&summary, *raw);
                 self.update_links_scroll_with_height(
                     self.terminal.size()?.height.saturating_sub(10) as usize,
                 );
@@ -113,9 +185,23 @@ impl UIInterface for JonyUI {
                 self.terminal
                     .draw(|f| Self::render_history(f, &entries, current_index))?;
             }
+            BrowserState::Bookmarks {
+                entries,
+                filter,
+                selected,
+            } => {
+                self.terminal
+                    .draw(|f| Self::render_bookmarks(f, entries, filter, *selected))?;
+            }
             BrowserState::URLInput { input } => {
                 let input = input.clone();
-                self.terminal.draw(|f| Self::render_url_input(f, &input))?;
+                self.terminal
+                    .draw(|f| Self::render_input(f, None, &input))?;
+            }
+            BrowserState::Prompt { title, input, .. } => {
+                let (title, input) = (title.clone(), input.clone());
+                self.terminal
+                    .draw(|f| Self::render_input(f, Some(&title), &input))?;
             }
             BrowserState::URLSuggestions {
                 original_url,
@@ -143,58 +229,66 @@ impl UIInterface for JonyUI {
                 let message = message.clone();
                 self.terminal.draw(|f| Self::render_error(f, &message))?;
             }
+            BrowserState::Confirm { message, .. } => {
+                let message = message.clone();
+                self.terminal.draw(|f| Self::render_confirm(f, &message))?;
+            }
+            BrowserState::Settings {
+                items,
+                selected,
+                status,
+            } => {
+                let (items, selected, status) = (items.clone(), *selected, status.clone());
+                self.terminal
+                    .draw(|f| Self::render_settings(f, &items, selected, status.as_deref()))?;
+            }
+            BrowserState::Info { items } => {
+                let items = items.clone();
+                self.terminal
+                    .draw(|f| Self::render_info(f, "About bbow", &items))?;
+            }
+            BrowserState::Stats { items } => {
+                let items = items.clone();
+                self.terminal
+                    .draw(|f| Self::render_info(f, "Session", &items))?;
+            }
+            BrowserState::Extraction { items } => {
+                let items = items.clone();
+                self.terminal
+                    .draw(|f| Self::render_info(f, "Extraction", &items))?;
+            }
+            BrowserState::Compare {
+                url,
+                title,
+                left,
+                right,
+                focus,
+            } => {
+                let (positions, line_spacing) = (self.pane_scroll.positions(), self.line_spacing);
+                let mut max_scroll = [0; 2];
+                self.terminal.draw(|f| {
+                    max_scroll = Self::render_compare(
+                        f,
+                        url,
+                        title,
+                        [left, right],
+                        *focus,
+                        positions,
+                        line_spacing,
+                    );
+                })?;
+                self.pane_scroll.set_max(max_scroll);
+            }
         }
         Ok(())
     }
 
-    fn get_user_input(&mut self, state: &BrowserState) -> Result<UserAction> {
-        loop {
-            if let Event::Key(key) = event::read()? {
-                match state {
-                    BrowserState::URLInput { input } => match key.code {
-                        KeyCode::Esc => return Ok(UserAction::CancelInput),
-                        KeyCode::Enter => return Ok(UserAction::ConfirmInput(input.clone())),
-                        KeyCode::Backspace => return Ok(UserAction::Backspace),
-                        KeyCode::Char(c) => return Ok(UserAction::InputChar(c)),
-                        _ => continue,
-                    },
-                    BrowserState::History { .. } => return Ok(UserAction::GoBack),
-                    BrowserState::URLSuggestions { .. } => match key.code {
-                        KeyCode::Esc => return Ok(UserAction::CancelInput),
-                        KeyCode::Char('q') => return Ok(UserAction::Quit),
-                        KeyCode::Up => return Ok(UserAction::SelectPrevSuggestion),
-                        KeyCode::Down => return Ok(UserAction::SelectNextSuggestion),
-                        KeyCode::Enter => return Ok(UserAction::ConfirmSuggestion),
-                        _ => continue,
-                    },
-                    BrowserState::Error { .. } => return Ok(UserAction::DismissError),
-                    _ => match key.code {
-                        KeyCode::Char('q') => return Ok(UserAction::Quit),
-                        KeyCode::Char('b') => return Ok(UserAction::GoBack),
-                        KeyCode::Char('f') => return Ok(UserAction::GoForward),
-                        KeyCode::Char('h') => return Ok(UserAction::ShowHistory),
-                        KeyCode::Char('g') => return Ok(UserAction::EnterUrl),
-                        KeyCode::Char('r') => return Ok(UserAction::Refresh),
-                        KeyCode::Up if key.modifiers.contains(KeyModifiers::SHIFT) => {
-                            return Ok(UserAction::SelectPrevLink)
-                        }
-                        KeyCode::Down if key.modifiers.contains(KeyModifiers::SHIFT) => {
-                            return Ok(UserAction::SelectNextLink)
-                        }
-                        KeyCode::Up => return Ok(UserAction::ScrollUp),
-                        KeyCode::Down => return Ok(UserAction::ScrollDown),
-                        KeyCode::Enter => return Ok(UserAction::FollowSelectedLink),
-                        KeyCode::Char(c) if c.is_ascii_digit() => {
-                            let digit = c.to_digit(10).unwrap() as usize;
-                            if digit > 0 {
-                                return Ok(UserAction::FollowLink(digit));
-                            }
-                        }
-                        _ => continue,
-                    },
-                }
-            }
-        }
+    fn get_user_input(
+        &mut self,
+        state: &BrowserState,
+        timeout: Option<Duration>,
+    ) -> Result<UserAction> {
+        self.input.next_action(state, timeout)
     }
 
     fn reset_scroll(&mut self) {
@@ -203,14 +297,73 @@ impl UIInterface for JonyUI {
         self.links_scroll = 0;
     }
 
+    fn scroll_position(&self) -> u16 {
+        self.scroll_position
+    }
+
+    fn summary_width(&self) -> usize {
+        self.summary_width
+    }
+
+    fn set_scroll_position(&mut self, position: u16) {
+        self.scroll_position = position;
+    }
+
+    fn max_scroll(&self) -> u16 {
+        self.max_scroll
+    }
+
+    fn scroll_to_text(&mut self, summary: &str, text: &str) -> bool {
+        match ui_common::find_text_line(summary, self.summary_width, self.line_spacing, text) {
+            Some(line) => {
+                self.scroll_position = line;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn scroll_pane(&mut self, pane: usize, lines: i32) {
+        self.pane_scroll.scroll(pane, lines);
+    }
+
+    fn reset_pane_scroll(&mut self) {
+        self.pane_scroll.reset();
+    }
+
+    fn set_split_ratio(&mut self, content_percent: u16) {
+        self.content_percent = content_percent;
+    }
+
+    fn set_max_width(&mut self, columns: u16) {
+        self.max_width = Some(columns);
+    }
+
+    fn set_line_spacing(&mut self, enabled: bool) {
+        self.line_spacing = enabled;
+    }
+
+    fn set_summary_preview(&mut self, chars: Option<usize>) {
+        self.summary_preview = chars;
+    }
+
+    fn set_hyperlinks(&mut self, enabled: bool) {
+        self.hyperlinks = enabled;
+    }
+
+    fn set_emoji(&mut self, enabled: bool) {
+        self.emoji = enabled;
+    }
+
     fn scroll_up(&mut self) {
-        self.scroll_position = self.scroll_position.saturating_sub(1);
+        let step = self.scroll_accel.step(false);
+        self.scroll_position = self.scroll_position.saturating_sub(step);
     }
 
     fn scroll_down(&mut self) {
-        if self.scroll_position < self.max_scroll {
-            self.scroll_position += 1;
-        }
+        let step = self.scroll_accel.step(true);
+        self.scroll_position =
+            ui_common::scroll_down_clamped(self.scroll_position, step, self.max_scroll);
     }
 
     fn select_prev_link(&mut self, links_len: usize) {
@@ -230,10 +383,21 @@ impl UIInterface for JonyUI {
     fn get_selected_link(&self) -> usize {
         self.selected_link
     }
+
+    fn select_link(&mut self, index: usize) {
+        self.selected_link = index;
+        self.update_links_scroll();
+    }
 }
 
 impl JonyUI {
-    fn render_loading(f: &mut Frame, url: &str, progress: u16, stage: &str) {
+    fn render_loading(
+        f: &mut Frame,
+        url: &str,
+        progress: u16,
+        stage: &str,
+        thinking: Option<&str>,
+    ) {
         let area = f.size();
 
         // Center loading screen with generous margins - Jony Ive's love of whitespace
@@ -241,7 +405,7 @@ impl JonyUI {
             .direction(Direction::Vertical)
             .constraints([
                 Constraint::Length(area.height / 3), // Top spacer
-                Constraint::Length(12),              // Content area
+                Constraint::Length(14),              // Content area
                 Constraint::Min(0),                  // Bottom spacer
             ])
             .split(area);
@@ -267,6 +431,8 @@ impl JonyUI {
                 Constraint::Length(2), // Progress
                 Constraint::Length(1), // Spacer
                 Constraint::Length(1), // Status
+                Constraint::Length(1), // Spacer
+                Constraint::Length(1), // Thinking
             ])
             .split(center_area);
 
@@ -304,19 +470,36 @@ impl JonyUI {
                 .alignment(Alignment::Center),
             sections[6],
         );
+
+        if let Some(thinking) = thinking {
+            f.render_widget(
+                Paragraph::new(thinking)
+                    .style(Style::default().fg(ACCENT))
+                    .alignment(Alignment::Center),
+                sections[8],
+            );
+        }
     }
 
     #[allow(clippy::too_many_arguments)]
     fn render_page(
         f: &mut Frame,
+        content_percent: u16,
+        max_width: Option<u16>,
+        line_spacing: bool,
         url: &str,
+        status: Option<&str>,
         title: &str,
         summary: &str,
-        links: &[Link],
+        raw: bool,
+        links: &[LinkLine],
         scroll_pos: u16,
         selected_link: usize,
         links_scroll: usize,
-    ) {
+        link_find: Option<&LinkFind>,
+        links_note: Option<&str>,
+        notice: Option<&str>,
+    ) -> Vec<Hyperlink> {
         let area = f.size();
 
         // Main layout with generous margins
@@ -331,13 +514,10 @@ impl JonyUI {
             .split(area);
 
         // Header layout
-        Self::render_header(f, main_chunks[0], url, title);
+        Self::render_header(f, main_chunks[0], url, status, title);
 
-        // Content layout - 75/25 split for content/links
-        let content_chunks = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([Constraint::Percentage(75), Constraint::Percentage(25)])
-            .split(main_chunks[1]);
+        // Content layout - summary on the left, links on the right
+        let content_chunks = ui_common::split_content(main_chunks[1], content_percent);
 
         // Add subtle divider between content and links
         let content_with_margin = content_chunks[0].inner(&Margin {
@@ -350,44 +530,143 @@ impl JonyUI {
             vertical: 0,
         });
 
-        Self::render_summary(f, content_with_margin, summary, scroll_pos);
-        Self::render_links(f, links_with_margin, links, selected_link, links_scroll);
-        Self::render_footer(f, main_chunks[2]);
+        Self::render_summary(
+            f,
+            content_with_margin,
+            summary,
+            scroll_pos,
+            max_width,
+            line_spacing,
+            raw,
+        );
+        Self::render_footer(f, main_chunks[2], notice);
+        Self::render_links(
+            f,
+            links_with_margin,
+            links,
+            selected_link,
+            links_scroll,
+            link_find,
+            links_note,
+        )
+    }
+
+    /// Draw both compare panes and return their max scroll
+    fn render_compare(
+        f: &mut Frame,
+        url: &str,
+        title: &str,
+        panes: [&ComparePane; 2],
+        focus: usize,
+        positions: [u16; 2],
+        line_spacing: bool,
+    ) -> [u16; 2] {
+        let main_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .margin(1)
+            .constraints([
+                Constraint::Length(4), // Header
+                Constraint::Min(5),    // Panes
+                Constraint::Length(2), // Footer
+            ])
+            .split(f.size());
+
+        Self::render_header(f, main_chunks[0], url, None, title);
+
+        let style = ui_common::CompareStyle {
+            text: Style::default().fg(CONTENT),
+            // No borders: the focused pane's label is the only emphasis
+            block: |label: &str, focused: bool| {
+                let label_style = if focused {
+                    Style::default().fg(EMPHASIS).add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(SUBTLE)
+                };
+                Block::default()
+                    .title(Span::styled(format!(" {}", label), label_style))
+                    .padding(Padding::new(1, 1, 1, 0))
+            },
+            markdown: Self::style_markdown_element,
+        };
+        let max_scroll = ui_common::render_compare_panes(
+            f,
+            main_chunks[1],
+            panes,
+            focus,
+            positions,
+            line_spacing,
+            &style,
+        );
+
+        f.render_widget(
+            Paragraph::new(Line::from(vec![
+                Span::styled("tab", Style::default().fg(ACCENT)),
+                Span::raw(" switch  "),
+                Span::styled("↑↓", Style::default().fg(ACCENT)),
+                Span::raw(" scroll  "),
+                Span::styled("esc", Style::default().fg(ACCENT)),
+                Span::raw(" back"),
+            ]))
+            .style(Style::default().fg(SUBTLE))
+            .alignment(Alignment::Center),
+            main_chunks[2],
+        );
+
+        max_scroll
     }
 
-    fn render_header(f: &mut Frame, area: Rect, url: &str, title: &str) {
+    fn render_header(f: &mut Frame, area: Rect, url: &str, status: Option<&str>, title: &str) {
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([Constraint::Length(2), Constraint::Length(2)])
             .split(area);
 
+        // One line each: long titles end in an ellipsis, long URLs lose their middle
+        let title = truncate_at_word_boundary(title, area.width as usize);
+        let url = ui_common::truncate_middle(url, ui_common::url_width(area.width, status));
+
         // Clean title without borders - focus on typography
         f.render_widget(
-            Paragraph::new(title)
-                .style(Style::default().fg(EMPHASIS).add_modifier(Modifier::BOLD))
-                .wrap(Wrap { trim: true }),
+            Paragraph::new(title).style(Style::default().fg(EMPHASIS).add_modifier(Modifier::BOLD)),
             chunks[0],
         );
 
-        // URL with subtle color
-        f.render_widget(
-            Paragraph::new(url)
-                .style(Style::default().fg(SECONDARY))
-                .wrap(Wrap { trim: true }),
-            chunks[1],
-        );
+        // URL with subtle color, response details even quieter
+        let mut url_line = vec![Span::styled(url, Style::default().fg(SECONDARY))];
+        if let Some(status) = status {
+            url_line.push(Span::styled(
+                format!("  {}", status),
+                Style::default().fg(SUBTLE),
+            ));
+        }
+
+        f.render_widget(Paragraph::new(Line::from(url_line)), chunks[1]);
     }
 
-    fn render_summary(f: &mut Frame, area: Rect, summary: &str, scroll_pos: u16) {
-        let width = area.width.saturating_sub(2) as usize;
+    fn render_summary(
+        f: &mut Frame,
+        area: Rect,
+        summary: &str,
+        scroll_pos: u16,
+        max_width: Option<u16>,
+        line_spacing: bool,
+        raw: bool,
+    ) {
+        let (width, indent) =
+            ui_common::text_width(area.width.saturating_sub(2) as usize, max_width);
         let visible_height = area.height as usize;
 
-        let visible_lines = ui_common::get_visible_markdown_lines(
-            summary,
-            width,
-            scroll_pos,
-            visible_height,
-            Self::style_markdown_element,
+        let visible_lines = ui_common::indent_lines(
+            ui_common::get_visible_markdown_lines(
+                summary,
+                width,
+                line_spacing,
+                scroll_pos,
+                visible_height,
+                raw,
+                Self::style_markdown_element,
+            ),
+            indent,
         );
 
         // If no content, render empty
@@ -408,7 +687,9 @@ impl JonyUI {
         let max_scroll = ui_common::calculate_max_scroll_for_markdown(
             summary,
             width,
+            line_spacing,
             visible_height,
+            raw,
             Self::style_markdown_element,
         );
 
@@ -452,16 +733,29 @@ impl JonyUI {
             MarkdownElement::Code(_) => Style::default().fg(EMPHASIS).bg(DIVIDER),
             MarkdownElement::Normal(_) => Style::default().fg(CONTENT),
             MarkdownElement::Empty => Style::default(),
+            MarkdownElement::TaskMarker(true) => Style::default().fg(ACCENT),
+            MarkdownElement::TaskMarker(false) => Style::default().fg(SECONDARY),
+            MarkdownElement::DefinitionTerm => {
+                Style::default().fg(EMPHASIS).add_modifier(Modifier::BOLD)
+            }
+            MarkdownElement::DiffAdded => Style::default()
+                .fg(ACCENT)
+                .add_modifier(Modifier::UNDERLINED),
+            MarkdownElement::DiffRemoved => Style::default()
+                .fg(SUBTLE)
+                .add_modifier(Modifier::CROSSED_OUT),
         }
     }
 
     fn render_links(
         f: &mut Frame,
         area: Rect,
-        links: &[Link],
+        links: &[LinkLine],
         selected_link: usize,
         links_scroll: usize,
-    ) {
+        link_find: Option<&LinkFind>,
+        links_note: Option<&str>,
+    ) -> Vec<Hyperlink> {
         if links.is_empty() {
             f.render_widget(
                 Paragraph::new("No links available")
@@ -469,7 +763,7 @@ impl JonyUI {
                     .alignment(Alignment::Center),
                 area,
             );
-            return;
+            return Vec::new();
         }
 
         // Create vertical divider
@@ -493,13 +787,46 @@ impl JonyUI {
         }
 
         // Links area with padding
-        let links_area = Rect {
+        let mut links_area = Rect {
             x: area.x + 2,
             y: area.y,
             width: area.width.saturating_sub(2),
             height: area.height,
         };
 
+        // Quiet search line above the list while finding a link
+        if let Some(find) = link_find {
+            let mut line = vec![
+                Span::styled("find ", Style::default().fg(SUBTLE)),
+                Span::styled(format!("{}▏", find.query), Style::default().fg(ACCENT)),
+            ];
+            if let Some(count) = find.count_label(links.len()) {
+                line.push(Span::styled(
+                    format!("  {}", count),
+                    Style::default().fg(SUBTLE),
+                ));
+            }
+            f.render_widget(
+                Paragraph::new(Line::from(line)),
+                Rect {
+                    height: 1,
+                    ..links_area
+                },
+            );
+            links_area.y += 2;
+            links_area.height = links_area.height.saturating_sub(2);
+        } else if let Some(note) = links_note {
+            f.render_widget(
+                Paragraph::new(note).style(Style::default().fg(SUBTLE)),
+                Rect {
+                    height: 1,
+                    ..links_area
+                },
+            );
+            links_area.y += 2;
+            links_area.height = links_area.height.saturating_sub(2);
+        }
+
         let visible_height = links_area.height as usize;
         let start_index = links_scroll;
         let end_index = (start_index + visible_height).min(links.len());
@@ -519,6 +846,8 @@ impl JonyUI {
 
                 let style = if is_selected {
                     Style::default().fg(ACCENT).add_modifier(Modifier::BOLD)
+                } else if link.header {
+                    Style::default().fg(EMPHASIS).add_modifier(Modifier::BOLD)
                 } else {
                     Style::default().fg(SECONDARY)
                 };
@@ -529,9 +858,27 @@ impl JonyUI {
             .collect();
 
         f.render_widget(List::new(items), links_area);
+
+        // Selected or not, the text starts two columns in
+        hyperlink::list_links(
+            links_area,
+            &links[start_index..end_index],
+            links_area.width.saturating_sub(2) as usize,
+            |link| format!("  {}", link.text),
+        )
     }
 
-    fn render_footer(f: &mut Frame, area: Rect) {
+    fn render_footer(f: &mut Frame, area: Rect, notice: Option<&str>) {
+        if let Some(notice) = notice {
+            f.render_widget(
+                Paragraph::new(notice.to_string())
+                    .style(Style::default().fg(ACCENT))
+                    .alignment(Alignment::Center),
+                area,
+            );
+            return;
+        }
+
         // Minimal footer with essential controls only
         let help_text = Line::from(vec![
             Span::styled("↑↓", Style::default().fg(ACCENT)),
@@ -582,7 +929,7 @@ impl JonyUI {
             content_area[0],
         );
 
-        let items: Vec<ListItem> = entries
+        let mut items: Vec<ListItem> = entries
             .iter()
             .enumerate()
             .map(|(i, entry)| {
@@ -594,12 +941,18 @@ impl JonyUI {
                     Style::default().fg(CONTENT)
                 };
 
-                let content = format!("{}{}", marker, entry.title);
+                let content = format!("{}{}  {}", marker, entry.title, entry.age_label());
                 let wrapped_content =
                     fill(&content, content_area[1].width.saturating_sub(4) as usize);
                 ListItem::new(wrapped_content).style(style)
             })
             .collect();
+        if items.is_empty() {
+            items.push(
+                ListItem::new(format!("  {}", ui_common::EMPTY_HISTORY))
+                    .style(Style::default().fg(SECONDARY)),
+            );
+        }
 
         f.render_widget(List::new(items), content_area[1]);
 
@@ -611,7 +964,201 @@ impl JonyUI {
         );
     }
 
-    fn render_url_input(f: &mut Frame, input: &str) {
+    fn render_bookmarks(f: &mut Frame, entries: &[BookmarkEntry], filter: &str, selected: usize) {
+        let area = f.size();
+
+        // Same centered column as the history view
+        let main_area = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Length(area.width / 8),
+                Constraint::Min(0),
+                Constraint::Length(area.width / 8),
+            ])
+            .split(area);
+
+        let content_area = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // Title and filter
+                Constraint::Min(5),    // Content
+                Constraint::Length(2), // Footer
+            ])
+            .split(main_area[1]);
+
+        let title = vec![
+            Line::from(Span::styled(
+                "Bookmarks",
+                Style::default().fg(EMPHASIS).add_modifier(Modifier::BOLD),
+            )),
+            Line::from(Span::styled(
+                format!("{}_", filter),
+                Style::default().fg(ACCENT),
+            )),
+        ];
+        f.render_widget(
+            Paragraph::new(title).alignment(Alignment::Center),
+            content_area[0],
+        );
+
+        let mut items: Vec<ListItem> = entries
+            .iter()
+            .enumerate()
+            .map(|(i, entry)| {
+                let is_selected = i == selected;
+                let marker = if is_selected { "▶ " } else { "  " };
+                let style = if is_selected {
+                    Style::default().fg(ACCENT).add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(CONTENT)
+                };
+
+                let content = format!("{}{}  {}", marker, entry.title, entry.tags_label());
+                let wrapped_content =
+                    fill(&content, content_area[1].width.saturating_sub(4) as usize);
+                ListItem::new(wrapped_content).style(style)
+            })
+            .collect();
+        let mut state = ListState::default();
+        if items.is_empty() {
+            items.push(
+                ListItem::new(format!("  {}", ui_common::empty_bookmarks(filter)))
+                    .style(Style::default().fg(SECONDARY)),
+            );
+        } else {
+            state.select(Some(selected));
+        }
+
+        f.render_stateful_widget(List::new(items), content_area[1], &mut state);
+
+        f.render_widget(
+            Paragraph::new(ui_common::BOOKMARKS_HELP)
+                .style(Style::default().fg(SUBTLE))
+                .alignment(Alignment::Center),
+            content_area[2],
+        );
+    }
+
+    fn render_settings(
+        f: &mut Frame,
+        items: &[SettingsItem],
+        selected: usize,
+        status: Option<&str>,
+    ) {
+        let area = f.size();
+
+        // Same centered column as the history view
+        let main_area = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Length(area.width / 8),
+                Constraint::Min(0),
+                Constraint::Length(area.width / 8),
+            ])
+            .split(area);
+
+        let content_area = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // Title
+                Constraint::Min(5),    // Content
+                Constraint::Length(2), // Footer
+            ])
+            .split(main_area[1]);
+
+        f.render_widget(
+            Paragraph::new("Settings")
+                .style(Style::default().fg(EMPHASIS).add_modifier(Modifier::BOLD))
+                .alignment(Alignment::Center),
+            content_area[0],
+        );
+
+        let list_items: Vec<ListItem> = items
+            .iter()
+            .enumerate()
+            .map(|(i, item)| {
+                let is_selected = i == selected;
+                let label_style = if is_selected {
+                    Style::default().fg(ACCENT).add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(CONTENT)
+                };
+                ListItem::new(Line::from(vec![
+                    Span::styled(if is_selected { "▶ " } else { "  " }, label_style),
+                    Span::styled(format!("{:<32} ", item.label), label_style),
+                    Span::styled(item.value.clone(), Style::default().fg(SECONDARY)),
+                ]))
+            })
+            .collect();
+
+        f.render_widget(List::new(list_items), content_area[1]);
+
+        f.render_widget(
+            Paragraph::new(
+                status
+                    .unwrap_or("↑↓ select  ←→ change  w save  esc close")
+                    .to_string(),
+            )
+            .style(Style::default().fg(SUBTLE))
+            .alignment(Alignment::Center),
+            content_area[2],
+        );
+    }
+
+    fn render_info(f: &mut Frame, title: &str, items: &[SettingsItem]) {
+        let area = f.size();
+
+        // Same centered column as the settings view
+        let main_area = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Length(area.width / 8),
+                Constraint::Min(0),
+                Constraint::Length(area.width / 8),
+            ])
+            .split(area);
+
+        let content_area = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // Title
+                Constraint::Min(5),    // Content
+                Constraint::Length(2), // Footer
+            ])
+            .split(main_area[1]);
+
+        f.render_widget(
+            Paragraph::new(title)
+                .style(Style::default().fg(EMPHASIS).add_modifier(Modifier::BOLD))
+                .alignment(Alignment::Center),
+            content_area[0],
+        );
+
+        let list_items: Vec<ListItem> = items
+            .iter()
+            .map(|item| {
+                ListItem::new(Line::from(vec![
+                    Span::styled(
+                        format!("  {:<20} ", item.label),
+                        Style::default().fg(CONTENT),
+                    ),
+                    Span::styled(item.value.clone(), Style::default().fg(SECONDARY)),
+                ]))
+            })
+            .collect();
+
+        f.render_widget(List::new(list_items), content_area[1]);
+
+        f.render_widget(
+            Paragraph::new("any key to close")
+                .style(Style::default().fg(SUBTLE))
+                .alignment(Alignment::Center),
+            content_area[2],
+        );
+    }
+
+    /// The input field, with a quiet label above the text when it is not for a URL
+    fn render_input(f: &mut Frame, label: Option<&str>, input: &TextInput) {
         let area = f.size();
 
         // Elegant centered input
@@ -632,14 +1179,24 @@ impl JonyUI {
             popup_area,
         );
 
-        let input_area = popup_area.inner(&Margin {
+        let mut input_area = popup_area.inner(&Margin {
             horizontal: 1,
             vertical: 1,
         });
-        f.render_widget(
-            Paragraph::new(input).style(Style::default().fg(CONTENT)),
-            input_area,
+        if let Some(label) = label {
+            f.render_widget(
+                Paragraph::new(label.to_string()).style(Style::default().fg(SECONDARY)),
+                input_area,
+            );
+            input_area.y += 1;
+            input_area.height = input_area.height.saturating_sub(1);
+        }
+        let spans = ui_common::input_spans(
+            input,
+            input_area.width as usize,
+            Style::default().fg(CONTENT),
         );
+        f.render_widget(Paragraph::new(Line::from(spans)), input_area);
     }
 
     fn render_url_suggestions(
@@ -744,6 +1301,48 @@ impl JonyUI {
         );
     }
 
+    fn render_confirm(f: &mut Frame, message: &str) {
+        let area = f.size();
+        let popup_area = Rect {
+            x: area.width / 4,
+            y: area.height / 2 - 3,
+            width: area.width / 2,
+            height: 6,
+        };
+
+        f.render_widget(Clear, popup_area);
+        f.render_widget(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(DIVIDER)),
+            popup_area,
+        );
+
+        let inner = popup_area.inner(&Margin {
+            horizontal: 2,
+            vertical: 1,
+        });
+        let text = vec![
+            Line::from(Span::styled(
+                message.to_string(),
+                Style::default().fg(EMPHASIS).add_modifier(Modifier::BOLD),
+            )),
+            Line::from(""),
+            Line::from(vec![
+                Span::styled("y", Style::default().fg(ACCENT)),
+                Span::styled(" confirm  ", Style::default().fg(SUBTLE)),
+                Span::styled("any key", Style::default().fg(ACCENT)),
+                Span::styled(" cancel", Style::default().fg(SUBTLE)),
+            ]),
+        ];
+        f.render_widget(
+            Paragraph::new(text)
+                .alignment(Alignment::Center)
+                .wrap(Wrap { trim: true }),
+            inner,
+        );
+    }
+
     fn update_links_scroll(&mut self) {
         self.update_links_scroll_with_height(10);
     }
@@ -753,23 +1352,30 @@ impl JonyUI {
             ui_common::update_links_scroll(self.selected_link, self.links_scroll, visible_height);
     }
 
-    fn update_max_scroll(&mut self, summary: &str) {
+    fn update_max_scroll(&mut self, summary: &str, raw: bool) {
         let terminal_size = self
             .terminal
             .size()
             .unwrap_or(ratatui::layout::Rect::new(0, 0, 80, 24));
 
         // Match render_summary calculations exactly
-        let content_width = terminal_size.width.saturating_sub(2) * 75 / 100; // 75% for content area
+        let content_width =
+            ui_common::content_width(terminal_size.width.saturating_sub(2), self.content_percent);
         let width = content_width.saturating_sub(2) as usize; // same as area.width.saturating_sub(2)
         let content_height = terminal_size.height.saturating_sub(1 + 4 + 2); // margin + header + footer
         let visible_height = content_height as usize; // same as area.height
 
+        let (width, _) = ui_common::text_width(width, self.max_width);
+        self.summary_width = width;
+
         self.max_scroll = ui_common::calculate_max_scroll_for_markdown(
             summary,
             width,
+            self.line_spacing,
             visible_height,
+            raw,
             Self::style_markdown_element,
         );
+        self.scroll_position = self.scroll_position.min(self.max_scroll);
     }
 }
@@ -0,0 +1,1238 @@
+// Light UI for BBOW
+// A clean, bordered layout with a palette tuned for light terminal backgrounds:
+// dark text, saturated accents and soft selection highlights
+
+use super::palette::Palette;
+use super::{
+    BookmarkEntry, BrowserState, ComparePane, HistoryEntry, LinkFind, SettingsItem, TextInput,
+    UIInterface, UserAction,
+};
+use crate::common::{
+    diff,
+    hyperlink::{self, Hyperlink},
+    keys,
+    markdown::MarkdownElement,
+    text::truncate_at_word_boundary,
+    ui::{self as ui_common, LinkLine},
+};
+use anyhow::Result;
+use crossterm::{
+    event::{DisableMouseCapture, EnableMouseCapture},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::{
+    backend::CrosstermBackend,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{
+        block::Title, Block, Borders, Clear, Gauge, List, ListItem, ListState, Paragraph,
+        Scrollbar, ScrollbarOrientation, ScrollbarState, Wrap,
+    },
+    Frame, Terminal,
+};
+use std::io::{self, Stdout};
+use std::time::Duration;
+use textwrap::fill;
+
+/// Share of the page width given to the summary unless `--split-ratio` is set
+const CONTENT_PERCENT: u16 = 75;
+
+pub struct LightUI {
+    terminal: Terminal<CrosstermBackend<Stdout>>,
+    scroll_position: u16,
+    selected_link: usize,
+    links_scroll: usize,
+    max_scroll: u16,
+    /// Summary wrap width from the last render, for locating text in it
+    summary_width: usize,
+    scroll_accel: ui_common::ScrollAccel,
+    pane_scroll: ui_common::PaneScroll,
+    /// Share of the page width given to the summary
+    content_percent: u16,
+    /// Widest the summary text wraps, centred in its pane when narrower
+    max_width: Option<u16>,
+    /// Blank line after every block of a summary
+    line_spacing: bool,
+    /// Characters of a summary shown until it is expanded
+    summary_preview: Option<usize>,
+    /// Make links clickable with OSC 8 escape sequences
+    hyperlinks: bool,
+    /// Show emoji in the chrome rather than their ASCII stand-ins
+    emoji: bool,
+    input: keys::InputReader,
+    palette: Palette,
+}
+
+impl UIInterface for LightUI {
+    fn new() -> Result<Self> {
+        enable_raw_mode()?;
+        let mut stdout = io::stdout();
+        execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+        let backend = CrosstermBackend::new(stdout);
+        let terminal = Terminal::new(backend)?;
+
+        Ok(Self {
+            terminal,
+            scroll_position: 0,
+            selected_link: 0,
+            links_scroll: 0,
+            max_scroll: 0,
+            summary_width: 0,
+            scroll_accel: ui_common::ScrollAccel::default(),
+            pane_scroll: ui_common::PaneScroll::default(),
+            content_percent: CONTENT_PERCENT,
+            max_width: None,
+            line_spacing: false,
+            summary_preview: None,
+            hyperlinks: false,
+            emoji: true,
+            input: keys::InputReader::default(),
+            palette: Palette::light(),
+        })
+    }
+
+    fn cleanup(&mut self) -> Result<()> {
+        disable_raw_mode()?;
+        execute!(
+            self.terminal.backend_mut(),
+            LeaveAlternateScreen,
+            DisableMouseCapture
+        )?;
+        self.terminal.show_cursor()?;
+        Ok(())
+    }
+
+    fn clear(&mut self) -> Result<()> {
+        self.terminal.clear()?;
+        Ok(())
+    }
+
+    fn render(&mut self, state: &BrowserState) -> Result<()> {
+        let palette = self.palette;
+        let p = &palette;
+
+        match state {
+            BrowserState::Loading {
+                url,
+                progress,
+                stage,
+                thinking,
+            } => {
+                self.terminal.draw(|f| {
+                    Self::render_loading(f, p, url, *progress, stage.label(), thinking.as_deref())
+                })?;
+            }
+            BrowserState::Page {
+                url,
+                title,
+                summary,
+                links,
+                meta,
+                link_find,
+                link_groups,
+                notice,
+                diff_from,
+                raw,
+                expanded,
+            } => {
+                let links_note = meta.links_note(links.len());
+                let summary = ui_common::shown_summary(summary, *expanded, self.summary_preview);
+                let (url, title, summary, links, link_find, notice) = (
+                    url.clone(),
+                    title.clone(),
+                    diff::summary_with_changes(&summary, diff_from.as_deref().filter(|_| !*raw)),
+                    ui_common::link_lines(links, link_groups.as_deref()),
+                    link_find.clone(),
+                    notice.clone(),
+                );
+                let status = meta.header_label(self.emoji);
+                let (content_percent, max_width, line_spacing) =
+                    (self.content_percent, self.max_width, self.line_spacing);
+                let (scroll_pos, selected_link, links_scroll) =
+                    (self.scroll_position, self.selected_link, self.links_scroll);
+
+                hyperlink::draw(&mut self.terminal, self.hyperlinks, |f| {
+                    Self::render_page(
+                        f,
+                        content_percent,
+                        max_width,
+                        line_spacing,
+                        p,
+                        &url,
+                        status.as_deref(),
+                        &title,
+                        &summary,
+                        *raw,
+                        &links,
+                        scroll_pos,
+                        selected_link,
+                        links_scroll,
+                        link_find.as_ref(),
+                        links_note.as_deref(),
+                        notice.as_deref(),
+                    )
+                })?;
+
+                self.update_max_scroll(&summary, *raw);
+                self.update_links_scroll_with_height(
+                    self.terminal.size()?.height.saturating_sub(6) as usize,
+                );
+            }
+            BrowserState::History {
+                entries,
+                current_index,
+            } => {
+                let (entries, current_index) = (entries.clone(), *current_index);
+                self.terminal
+                    .draw(|f| Self::render_history(f, p, &entries, current_index))?;
+            }
+            BrowserState::Bookmarks {
+                entries,
+                filter,
+                selected,
+            } => {
+                self.terminal
+                    .draw(|f| Self::render_bookmarks(f, p, entries, filter, *selected))?;
+            }
+            BrowserState::URLInput { input } => {
+                let input = input.clone();
+                self.terminal
+                    .draw(|f| Self::render_input(f, p, " Enter URL ", &input))?;
+            }
+            BrowserState::Prompt { title, input, .. } => {
+                let (title, input) = (format!(" {} ", title), input.clone());
+                self.terminal
+                    .draw(|f| Self::render_input(f, p, &title, &input))?;
+            }
+            BrowserState::URLSuggestions {
+                original_url,
+                error_message,
+                suggestions,
+                selected_index,
+            } => {
+                let (original_url, error_message, suggestions, selected_index) = (
+                    original_url.clone(),
+                    error_message.clone(),
+                    suggestions.clone(),
+                    *selected_index,
+                );
+                self.terminal.draw(|f| {
+                    Self::render_url_suggestions(
+                        f,
+                        p,
+                        &original_url,
+                        &error_message,
+                        &suggestions,
+                        selected_index,
+                    );
+                })?;
+            }
+            BrowserState::Error { message } => {
+                let message = message.clone();
+                self.terminal.draw(|f| Self::render_error(f, p, &message))?;
+            }
+            BrowserState::Confirm { message, .. } => {
+                let message = message.clone();
+                self.terminal
+                    .draw(|f| Self::render_confirm(f, p, &message))?;
+            }
+            BrowserState::Settings {
+                items,
+                selected,
+                status,
+            } => {
+                let (items, selected, status) = (items.clone(), *selected, status.clone());
+                self.terminal
+                    .draw(|f| Self::render_settings(f, p, &items, selected, status.as_deref()))?;
+            }
+            BrowserState::Info { items } => {
+                let items = items.clone();
+                self.terminal
+                    .draw(|f| Self::render_info(f, p, " About bbow ", &items))?;
+            }
+            BrowserState::Stats { items } => {
+                let items = items.clone();
+                self.terminal
+                    .draw(|f| Self::render_info(f, p, " Session stats ", &items))?;
+            }
+            BrowserState::Extraction { items } => {
+                let items = items.clone();
+                self.terminal
+                    .draw(|f| Self::render_info(f, p, " Extraction ", &items))?;
+            }
+            BrowserState::Compare {
+                url,
+                title,
+                left,
+                right,
+                focus,
+            } => {
+                let (positions, line_spacing) = (self.pane_scroll.positions(), self.line_spacing);
+                let mut max_scroll = [0; 2];
+                self.terminal.draw(|f| {
+                    max_scroll = Self::render_compare(
+                        f,
+                        p,
+                        url,
+                        title,
+                        [left, right],
+                        *focus,
+                        positions,
+                        line_spacing,
+                    );
+                })?;
+                self.pane_scroll.set_max(max_scroll);
+            }
+        }
+        Ok(())
+    }
+
+    fn get_user_input(
+        &mut self,
+        state: &BrowserState,
+        timeout: Option<Duration>,
+    ) -> Result<UserAction> {
+        self.input.next_action(state, timeout)
+    }
+
+    fn reset_scroll(&mut self) {
+        self.scroll_position = 0;
+        self.selected_link = 0;
+        self.links_scroll = 0;
+    }
+
+    fn scroll_position(&self) -> u16 {
+        self.scroll_position
+    }
+
+    fn summary_width(&self) -> usize {
+        self.summary_width
+    }
+
+    fn set_scroll_position(&mut self, position: u16) {
+        self.scroll_position = position;
+    }
+
+    fn max_scroll(&self) -> u16 {
+        self.max_scroll
+    }
+
+    fn scroll_to_text(&mut self, summary: &str, text: &str) -> bool {
+        match ui_common::find_text_line(summary, self.summary_width, self.line_spacing, text) {
+            Some(line) => {
+                self.scroll_position = line;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn scroll_pane(&mut self, pane: usize, lines: i32) {
+        self.pane_scroll.scroll(pane, lines);
+    }
+
+    fn reset_pane_scroll(&mut self) {
+        self.pane_scroll.reset();
+    }
+
+    fn set_split_ratio(&mut self, content_percent: u16) {
+        self.content_percent = content_percent;
+    }
+
+    fn set_max_width(&mut self, columns: u16) {
+        self.max_width = Some(columns);
+    }
+
+    fn set_line_spacing(&mut self, enabled: bool) {
+        self.line_spacing = enabled;
+    }
+
+    fn set_summary_preview(&mut self, chars: Option<usize>) {
+        self.summary_preview = chars;
+    }
+
+    fn set_hyperlinks(&mut self, enabled: bool) {
+        self.hyperlinks = enabled;
+    }
+
+    fn set_emoji(&mut self, enabled: bool) {
+        self.emoji = enabled;
+    }
+
+    fn scroll_up(&mut self) {
+        let step = self.scroll_accel.step(false);
+        self.scroll_position = self.scroll_position.saturating_sub(step);
+    }
+
+    fn scroll_down(&mut self) {
+        let step = self.scroll_accel.step(true);
+        self.scroll_position =
+            ui_common::scroll_down_clamped(self.scroll_position, step, self.max_scroll);
+    }
+
+    fn select_prev_link(&mut self, links_len: usize) {
+        if links_len > 0 && self.selected_link > 0 {
+            self.selected_link -= 1;
+            self.update_links_scroll();
+        }
+    }
+
+    fn select_next_link(&mut self, links_len: usize) {
+        if links_len > 0 && self.selected_link < links_len - 1 {
+            self.selected_link += 1;
+            self.update_links_scroll();
+        }
+    }
+
+    fn get_selected_link(&self) -> usize {
+        self.selected_link
+    }
+
+    fn select_link(&mut self, index: usize) {
+        self.selected_link = index;
+        self.update_links_scroll();
+    }
+}
+
+impl LightUI {
+    /// Use a custom palette, e.g. one loaded with `--theme-file`
+    pub fn with_palette(palette: Palette) -> Result<Self> {
+        let mut ui = <Self as UIInterface>::new()?;
+        ui.palette = palette;
+        Ok(ui)
+    }
+
+    /// Fill the whole screen with the palette background, if it sets one
+    fn paint_background(f: &mut Frame, p: &Palette) {
+        if let Some(background) = p.background {
+            f.render_widget(
+                Block::default().style(Style::default().bg(background)),
+                f.size(),
+            );
+        }
+    }
+
+    fn block<'a>(p: &Palette, title: impl Into<Title<'a>>) -> Block<'a> {
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(p.border))
+            .title(title)
+            .title_style(Style::default().fg(p.muted))
+    }
+
+    fn render_loading(
+        f: &mut Frame,
+        p: &Palette,
+        url: &str,
+        progress: u16,
+        stage: &str,
+        thinking: Option<&str>,
+    ) {
+        Self::paint_background(f, p);
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .margin(2)
+            .constraints([
+                Constraint::Length(2),
+                Constraint::Length(4),
+                Constraint::Length(3),
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Length(1),
+            ])
+            .split(f.size());
+
+        f.render_widget(
+            Paragraph::new("BBOW")
+                .style(Style::default().fg(p.accent).add_modifier(Modifier::BOLD))
+                .alignment(Alignment::Center),
+            chunks[0],
+        );
+
+        f.render_widget(
+            Paragraph::new(fill(url, chunks[1].width.saturating_sub(4) as usize))
+                .style(Style::default().fg(p.text))
+                .wrap(Wrap { trim: true })
+                .block(Self::block(p, " Loading ")),
+            chunks[1],
+        );
+
+        f.render_widget(
+            Gauge::default()
+                .block(Self::block(p, " Progress "))
+                .gauge_style(Style::default().fg(p.accent).bg(p.selection))
+                .percent(progress)
+                .label(Span::styled(
+                    format!("{}%", progress),
+                    Style::default().fg(p.text),
+                ))
+                .use_unicode(true),
+            chunks[2],
+        );
+
+        f.render_widget(
+            Paragraph::new(stage.to_string())
+                .style(Style::default().fg(p.muted))
+                .alignment(Alignment::Center),
+            chunks[3],
+        );
+
+        if let Some(thinking) = thinking {
+            f.render_widget(
+                Paragraph::new(thinking)
+                    .style(Style::default().fg(p.accent).add_modifier(Modifier::BOLD))
+                    .alignment(Alignment::Center),
+                chunks[5],
+            );
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn render_page(
+        f: &mut Frame,
+        content_percent: u16,
+        max_width: Option<u16>,
+        line_spacing: bool,
+        p: &Palette,
+        url: &str,
+        status: Option<&str>,
+        title: &str,
+        summary: &str,
+        raw: bool,
+        links: &[LinkLine],
+        scroll_pos: u16,
+        selected_link: usize,
+        links_scroll: usize,
+        link_find: Option<&LinkFind>,
+        links_note: Option<&str>,
+        notice: Option<&str>,
+    ) -> Vec<Hyperlink> {
+        Self::paint_background(f, p);
+        let main_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // Header
+                Constraint::Min(5),    // Content
+                Constraint::Length(1), // Footer
+            ])
+            .split(f.size());
+
+        Self::render_header(f, p, main_chunks[0], url, status, title);
+
+        let content_chunks = ui_common::split_content(main_chunks[1], content_percent);
+
+        Self::render_summary(
+            f,
+            p,
+            content_chunks[0],
+            summary,
+            scroll_pos,
+            max_width,
+            line_spacing,
+            raw,
+        );
+        Self::render_footer(f, p, main_chunks[2], notice);
+        Self::render_links(
+            f,
+            p,
+            content_chunks[1],
+            links,
+            selected_link,
+            links_scroll,
+            link_find,
+            links_note,
+        )
+    }
+
+    /// Draw both compare panes and return their max scroll
+    #[allow(clippy::too_many_arguments)]
+    fn render_compare(
+        f: &mut Frame,
+        p: &Palette,
+        url: &str,
+        title: &str,
+        panes: [&ComparePane; 2],
+        focus: usize,
+        positions: [u16; 2],
+        line_spacing: bool,
+    ) -> [u16; 2] {
+        Self::paint_background(f, p);
+        let main_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // Header
+                Constraint::Min(5),    // Panes
+                Constraint::Length(1), // Footer
+            ])
+            .split(f.size());
+
+        Self::render_header(f, p, main_chunks[0], url, None, title);
+
+        let style = ui_common::CompareStyle {
+            text: Style::default().fg(p.text),
+            block: |label: &str, focused: bool| {
+                let block = Self::block(p, format!(" {} ", label));
+                if !focused {
+                    return block;
+                }
+                block
+                    .border_style(Style::default().fg(p.accent))
+                    .title_style(Style::default().fg(p.accent).add_modifier(Modifier::BOLD))
+            },
+            markdown: |element: &MarkdownElement| Self::style_markdown_element(p, element),
+        };
+        let max_scroll = ui_common::render_compare_panes(
+            f,
+            main_chunks[1],
+            panes,
+            focus,
+            positions,
+            line_spacing,
+            &style,
+        );
+
+        let key = |k: &'static str| Span::styled(k, Style::default().fg(p.accent));
+        let label = |l: &'static str| Span::styled(l, Style::default().fg(p.muted));
+        f.render_widget(
+            Paragraph::new(Line::from(vec![
+                key("Tab"),
+                label(" switch pane  "),
+                key("↑↓"),
+                label(" scroll  "),
+                key("Esc"),
+                label(" back"),
+            ])),
+            main_chunks[2],
+        );
+
+        max_scroll
+    }
+
+    fn render_header(
+        f: &mut Frame,
+        p: &Palette,
+        area: Rect,
+        url: &str,
+        status: Option<&str>,
+        title: &str,
+    ) {
+        // One line each: long titles end in an ellipsis, long URLs lose their middle
+        let title = truncate_at_word_boundary(title, area.width as usize);
+        let url = ui_common::truncate_middle(url, ui_common::url_width(area.width, status));
+
+        let mut header = vec![
+            Line::from(Span::styled(
+                title,
+                Style::default().fg(p.text).add_modifier(Modifier::BOLD),
+            )),
+            Line::from(Span::styled(url, Style::default().fg(p.link))),
+        ];
+        if let Some(status) = status {
+            header[1].spans.push(Span::styled(
+                format!("  {}", status),
+                Style::default().fg(p.muted),
+            ));
+        }
+
+        f.render_widget(
+            Paragraph::new(header).block(
+                Block::default()
+                    .borders(Borders::BOTTOM)
+                    .border_style(Style::default().fg(p.border)),
+            ),
+            area,
+        );
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn render_summary(
+        f: &mut Frame,
+        p: &Palette,
+        area: Rect,
+        summary: &str,
+        scroll_pos: u16,
+        max_width: Option<u16>,
+        line_spacing: bool,
+        raw: bool,
+    ) {
+        let (width, indent) =
+            ui_common::text_width(area.width.saturating_sub(4) as usize, max_width);
+        let visible_height = area.height.saturating_sub(2) as usize;
+
+        let visible_lines = ui_common::indent_lines(
+            ui_common::get_visible_markdown_lines(
+                summary,
+                width,
+                line_spacing,
+                scroll_pos,
+                visible_height,
+                raw,
+                |element| Self::style_markdown_element(p, element),
+            ),
+            indent,
+        );
+
+        let max_scroll = ui_common::calculate_max_scroll_for_markdown(
+            summary,
+            width,
+            line_spacing,
+            visible_height,
+            raw,
+            |element| Self::style_markdown_element(p, element),
+        );
+
+        let content_length = visible_lines.len() + max_scroll as usize;
+
+        f.render_widget(
+            Paragraph::new(visible_lines)
+                .style(Style::default().fg(p.text))
+                .wrap(Wrap { trim: false })
+                .block(Self::block(p, " Summary ")),
+            area,
+        );
+
+        if max_scroll > 0 {
+            let scrollbar = Scrollbar::default()
+                .orientation(ScrollbarOrientation::VerticalRight)
+                .begin_symbol(None)
+                .end_symbol(None)
+                .style(Style::default().fg(p.border));
+            let mut scrollbar_state = ScrollbarState::default()
+                .content_length(content_length)
+                .position(scroll_pos as usize);
+            f.render_stateful_widget(scrollbar, area, &mut scrollbar_state);
+        }
+    }
+
+    fn style_markdown_element(p: &Palette, element: &MarkdownElement) -> Style {
+        match element {
+            MarkdownElement::Header1(_) => Style::default()
+                .fg(p.header1)
+                .add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+            MarkdownElement::Header2(_) => {
+                Style::default().fg(p.header1).add_modifier(Modifier::BOLD)
+            }
+            MarkdownElement::Header3(_) => {
+                Style::default().fg(p.header3).add_modifier(Modifier::BOLD)
+            }
+            MarkdownElement::Header4(_) => {
+                Style::default().fg(p.muted).add_modifier(Modifier::BOLD)
+            }
+            MarkdownElement::Bold(_) => Style::default().fg(p.text).add_modifier(Modifier::BOLD),
+            MarkdownElement::Italic(_) => {
+                Style::default().fg(p.muted).add_modifier(Modifier::ITALIC)
+            }
+            MarkdownElement::Code(_) => Style::default().fg(p.text).bg(p.code_bg),
+            MarkdownElement::Normal(_) => Style::default().fg(p.text),
+            MarkdownElement::Empty => Style::default(),
+            MarkdownElement::TaskMarker(true) => Style::default().fg(p.accent),
+            MarkdownElement::TaskMarker(false) => Style::default().fg(p.muted),
+            MarkdownElement::DefinitionTerm => {
+                Style::default().fg(p.header3).add_modifier(Modifier::BOLD)
+            }
+            MarkdownElement::DiffAdded => Style::default()
+                .fg(p.accent)
+                .add_modifier(Modifier::UNDERLINED),
+            MarkdownElement::DiffRemoved => Style::default()
+                .fg(p.error)
+                .add_modifier(Modifier::CROSSED_OUT),
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn render_links(
+        f: &mut Frame,
+        p: &Palette,
+        area: Rect,
+        links: &[LinkLine],
+        selected_link: usize,
+        links_scroll: usize,
+        link_find: Option<&LinkFind>,
+        links_note: Option<&str>,
+    ) -> Vec<Hyperlink> {
+        let title = match link_find {
+            Some(find) => match find.count_label(links.len()) {
+                Some(count) => format!(" Find: {}▏ {} ", find.query, count),
+                None => format!(" Find: {}▏ ", find.query),
+            },
+            None => match links_note {
+                Some(note) => format!(" Links ({}) ", note),
+                None => " Links ".to_string(),
+            },
+        };
+
+        if links.is_empty() {
+            f.render_widget(
+                Paragraph::new("No links found")
+                    .style(Style::default().fg(p.muted))
+                    .block(Self::block(p, title.as_str())),
+                area,
+            );
+            return Vec::new();
+        }
+
+        let visible_height = area.height.saturating_sub(2) as usize;
+        let start_index = links_scroll;
+        let end_index = (start_index + visible_height).min(links.len());
+
+        let items: Vec<ListItem> = links[start_index..end_index]
+            .iter()
+            .enumerate()
+            .map(|(i, link)| {
+                let absolute_index = start_index + i;
+                let style = if absolute_index == selected_link {
+                    Style::default()
+                        .fg(p.text)
+                        .bg(p.selection)
+                        .add_modifier(Modifier::BOLD)
+                } else if link.header {
+                    Style::default().fg(p.text).add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(p.link)
+                };
+
+                let content = link.label();
+                let wrapped_content = fill(&content, area.width.saturating_sub(6) as usize);
+                ListItem::new(wrapped_content).style(style)
+            })
+            .collect();
+
+        f.render_widget(List::new(items).block(Self::block(p, title.as_str())), area);
+
+        if links.len() > visible_height {
+            let scrollbar = Scrollbar::default()
+                .orientation(ScrollbarOrientation::VerticalRight)
+                .begin_symbol(None)
+                .end_symbol(None)
+                .style(Style::default().fg(p.border));
+            let mut scrollbar_state = ScrollbarState::default()
+                .content_length(links.len())
+                .position(links_scroll);
+            f.render_stateful_widget(scrollbar, area, &mut scrollbar_state);
+        }
+
+        hyperlink::list_links(
+            Self::block(p, title.as_str()).inner(area),
+            &links[start_index..end_index],
+            area.width.saturating_sub(6) as usize,
+            LinkLine::label,
+        )
+    }
+
+    fn render_footer(f: &mut Frame, p: &Palette, area: Rect, notice: Option<&str>) {
+        if let Some(notice) = notice {
+            f.render_widget(
+                Paragraph::new(notice.to_string()).style(Style::default().fg(p.accent)),
+                area,
+            );
+            return;
+        }
+
+        let key = |k: &'static str| Span::styled(k, Style::default().fg(p.accent));
+        let label = |l: &'static str| Span::styled(l, Style::default().fg(p.muted));
+
+        let help_text = Line::from(vec![
+            key("↑↓"),
+            label(" scroll  "),
+            key("Shift+↑↓"),
+            label(" select  "),
+            key("Enter"),
+            label(" follow  "),
+            key("b"),
+            label(" back  "),
+            key("g"),
+            label(" url  "),
+            key("h"),
+            label(" history  "),
+            key("r"),
+            label(" refresh  "),
+            key("'"),
+            label(" find  "),
+            key("q"),
+            label(" quit"),
+        ]);
+
+        f.render_widget(Paragraph::new(help_text), area);
+    }
+
+    fn render_history(
+        f: &mut Frame,
+        p: &Palette,
+        entries: &[HistoryEntry],
+        current_index: Option<usize>,
+    ) {
+        Self::paint_background(f, p);
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(3), Constraint::Length(1)])
+            .split(f.size());
+
+        let mut items: Vec<ListItem> = entries
+            .iter()
+            .enumerate()
+            .map(|(i, entry)| {
+                let is_current = Some(i) == current_index;
+                let marker = if is_current { "▶ " } else { "  " };
+                let style = if is_current {
+                    Style::default().fg(p.accent).add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(p.text)
+                };
+
+                let content = format!(
+                    "{}{} - {} · {}",
+                    marker,
+                    entry.title,
+                    entry.url,
+                    entry.age_label()
+                );
+                let wrapped_content = fill(&content, chunks[0].width.saturating_sub(4) as usize);
+                ListItem::new(wrapped_content).style(style)
+            })
+            .collect();
+        if items.is_empty() {
+            items.push(ListItem::new(ui_common::EMPTY_HISTORY).style(Style::default().fg(p.muted)));
+        }
+
+        f.render_widget(
+            List::new(items).block(Self::block(p, " History ")),
+            chunks[0],
+        );
+
+        f.render_widget(
+            Paragraph::new("Press any key to return")
+                .style(Style::default().fg(p.muted))
+                .alignment(Alignment::Center),
+            chunks[1],
+        );
+    }
+
+    fn render_bookmarks(
+        f: &mut Frame,
+        p: &Palette,
+        entries: &[BookmarkEntry],
+        filter: &str,
+        selected: usize,
+    ) {
+        Self::paint_background(f, p);
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(3), Constraint::Length(1)])
+            .split(f.size());
+
+        let mut items: Vec<ListItem> = entries
+            .iter()
+            .enumerate()
+            .map(|(i, entry)| {
+                let is_selected = i == selected;
+                let marker = if is_selected { "▶ " } else { "  " };
+                let style = if is_selected {
+                    Style::default().fg(p.accent).add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(p.text)
+                };
+
+                let mut content = format!("{}{} - {}", marker, entry.title, entry.url);
+                if !entry.tags.is_empty() {
+                    content.push_str(&format!(" · {}", entry.tags_label()));
+                }
+                let wrapped_content = fill(&content, chunks[0].width.saturating_sub(4) as usize);
+                ListItem::new(wrapped_content).style(style)
+            })
+            .collect();
+        let mut state = ListState::default();
+        if items.is_empty() {
+            items.push(
+                ListItem::new(ui_common::empty_bookmarks(filter))
+                    .style(Style::default().fg(p.muted)),
+            );
+        } else {
+            state.select(Some(selected));
+        }
+
+        let title = format!(" Bookmarks · Filter: {}_ ", filter);
+        f.render_stateful_widget(
+            List::new(items).block(Self::block(p, title.as_str())),
+            chunks[0],
+            &mut state,
+        );
+
+        f.render_widget(
+            Paragraph::new(ui_common::BOOKMARKS_HELP)
+                .style(Style::default().fg(p.muted))
+                .alignment(Alignment::Center),
+            chunks[1],
+        );
+    }
+
+    fn render_input(f: &mut Frame, p: &Palette, title: &str, input: &TextInput) {
+        Self::paint_background(f, p);
+        let area = f.size();
+        let popup_area = Rect {
+            x: area.width / 4,
+            y: area.height / 2 - 2,
+            width: area.width / 2,
+            height: 3,
+        };
+
+        let spans = ui_common::input_spans(
+            input,
+            popup_area.width.saturating_sub(2) as usize,
+            Style::default().fg(p.text),
+        );
+
+        f.render_widget(Clear, popup_area);
+        f.render_widget(
+            Paragraph::new(Line::from(spans)).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(p.accent))
+                    .title(title.to_string())
+                    .title_style(Style::default().fg(p.accent)),
+            ),
+            popup_area,
+        );
+    }
+
+    fn render_url_suggestions(
+        f: &mut Frame,
+        p: &Palette,
+        original_url: &str,
+        error_message: &str,
+        suggestions: &[String],
+        selected_index: usize,
+    ) {
+        Self::paint_background(f, p);
+        let area = f.size();
+        let popup_area = Rect {
+            x: area.width / 8,
+            y: area.height / 4,
+            width: area.width * 3 / 4,
+            height: area.height / 2,
+        };
+
+        f.render_widget(Clear, popup_area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Min(3),
+                Constraint::Length(1),
+            ])
+            .split(popup_area);
+
+        f.render_widget(
+            Paragraph::new(format!("Failed to load: {}", error_message))
+                .style(Style::default().fg(p.error))
+                .wrap(Wrap { trim: true })
+                .block(Self::block(p, " Error ")),
+            chunks[0],
+        );
+
+        f.render_widget(
+            Paragraph::new(original_url.to_string())
+                .style(Style::default().fg(p.text))
+                .wrap(Wrap { trim: true })
+                .block(Self::block(p, " Requested URL ")),
+            chunks[1],
+        );
+
+        let suggestion_items: Vec<ListItem> = suggestions
+            .iter()
+            .enumerate()
+            .map(|(i, suggestion)| {
+                let style = if i == selected_index {
+                    Style::default()
+                        .fg(p.text)
+                        .bg(p.selection)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(p.accent)
+                };
+                ListItem::new(suggestion.clone()).style(style)
+            })
+            .collect();
+
+        f.render_widget(
+            List::new(suggestion_items).block(Self::block(p, " Suggestions ")),
+            chunks[2],
+        );
+
+        f.render_widget(
+            Paragraph::new("↑↓ select • Enter confirm • Esc cancel • q quit")
+                .style(Style::default().fg(p.muted))
+                .alignment(Alignment::Center),
+            chunks[3],
+        );
+    }
+
+    fn render_error(f: &mut Frame, p: &Palette, message: &str) {
+        Self::paint_background(f, p);
+        let area = f.size();
+        let popup_area = Rect {
+            x: area.width / 8,
+            y: area.height / 2 - 3,
+            width: area.width * 3 / 4,
+            height: 6,
+        };
+
+        f.render_widget(Clear, popup_area);
+        f.render_widget(
+            Paragraph::new(format!("{}\n\nPress any key to dismiss", message))
+                .style(Style::default().fg(p.error))
+                .wrap(Wrap { trim: true })
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_style(Style::default().fg(p.error))
+                        .title(" Error ")
+                        .title_style(Style::default().fg(p.error)),
+                ),
+            popup_area,
+        );
+    }
+
+    fn render_confirm(f: &mut Frame, p: &Palette, message: &str) {
+        Self::paint_background(f, p);
+        let area = f.size();
+        let popup_area = Rect {
+            x: area.width / 4,
+            y: area.height / 2 - 3,
+            width: area.width / 2,
+            height: 6,
+        };
+
+        f.render_widget(Clear, popup_area);
+        f.render_widget(
+            Paragraph::new(format!(
+                "{}\n\nPress y to confirm, any other key to cancel",
+                message
+            ))
+            .style(Style::default().fg(p.text))
+            .wrap(Wrap { trim: true })
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(p.warning))
+                    .title(" Confirm ")
+                    .title_style(Style::default().fg(p.warning)),
+            ),
+            popup_area,
+        );
+    }
+
+    fn render_settings(
+        f: &mut Frame,
+        p: &Palette,
+        items: &[SettingsItem],
+        selected: usize,
+        status: Option<&str>,
+    ) {
+        Self::paint_background(f, p);
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(3), Constraint::Length(1)])
+            .split(f.size());
+
+        let list_items: Vec<ListItem> = items
+            .iter()
+            .enumerate()
+            .map(|(i, item)| {
+                let line = Line::from(vec![
+                    Span::styled(format!("{:<32} ", item.label), Style::default().fg(p.text)),
+                    Span::styled(item.value.clone(), Style::default().fg(p.link)),
+                ]);
+                let style = if i == selected {
+                    Style::default()
+                        .bg(p.selection)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default()
+                };
+                ListItem::new(line).style(style)
+            })
+            .collect();
+
+        f.render_widget(
+            List::new(list_items).block(Self::block(p, " Settings ")),
+            chunks[0],
+        );
+
+        let help = status.unwrap_or("↑↓ select  ←→/Enter change  w save  Esc close");
+        f.render_widget(
+            Paragraph::new(help.to_string())
+                .style(Style::default().fg(p.muted))
+                .alignment(Alignment::Center),
+            chunks[1],
+        );
+    }
+
+    fn render_info(f: &mut Frame, p: &Palette, title: &str, items: &[SettingsItem]) {
+        Self::paint_background(f, p);
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(3), Constraint::Length(1)])
+            .split(f.size());
+
+        let list_items: Vec<ListItem> = items
+            .iter()
+            .map(|item| {
+                ListItem::new(Line::from(vec![
+                    Span::styled(format!("{:<20} ", item.label), Style::default().fg(p.text)),
+                    Span::styled(item.value.clone(), Style::default().fg(p.link)),
+                ]))
+            })
+            .collect();
+
+        f.render_widget(
+            List::new(list_items).block(Self::block(p, title)),
+            chunks[0],
+        );
+
+        f.render_widget(
+            Paragraph::new("any key to close")
+                .style(Style::default().fg(p.muted))
+                .alignment(Alignment::Center),
+            chunks[1],
+        );
+    }
+
+    fn update_links_scroll(&mut self) {
+        self.update_links_scroll_with_height(10);
+    }
+
+    fn update_links_scroll_with_height(&mut self, visible_height: usize) {
+        self.links_scroll =
+            ui_common::update_links_scroll(self.selected_link, self.links_scroll, visible_height);
+    }
+
+    fn update_max_scroll(&mut self, summary: &str, raw: bool) {
+        let terminal_size = self
+            .terminal
+            .size()
+            .unwrap_or(ratatui::layout::Rect::new(0, 0, 80, 24));
+
+        // Match render_summary calculations exactly
+        let content_width = ui_common::content_width(terminal_size.width, self.content_percent);
+        let width = content_width.saturating_sub(4) as usize; // same as area.width.saturating_sub(4)
+        let content_height = terminal_size.height.saturating_sub(3 + 1); // header + footer
+        let visible_height = content_height.saturating_sub(2) as usize; // borders
+
+        let (width, _) = ui_common::text_width(width, self.max_width);
+        self.summary_width = width;
+
+        self.max_scroll = ui_common::calculate_max_scroll_for_markdown(
+            summary,
+            width,
+            self.line_spacing,
+            visible_height,
+            raw,
+            |element| Self::style_markdown_element(&self.palette, element),
+        );
+        self.scroll_position = self.scroll_position.min(self.max_scroll);
+    }
+}
@@ -1,13 +1,25 @@
 // UI module - contains all UI implementations and shared types
 // This package provides a clean separation between UI logic and business logic
 
+use crate::common::emoji;
+use crate::common::text::{relative_age, relative_time};
 use crate::links::Link;
 use anyhow::Result;
+use chrono::{DateTime, Utc};
+use crossterm::{
+    event::EnableMouseCapture,
+    execute,
+    terminal::{enable_raw_mode, EnterAlternateScreen},
+};
+use std::io;
+use std::time::Duration;
 
 // Re-export UI implementations
 pub mod default;
 pub mod expi;
 pub mod jony;
+pub mod light;
+pub mod palette;
 pub mod robocop;
 
 // Shared UI types and traits
@@ -15,6 +27,289 @@ pub mod robocop;
 pub struct HistoryEntry {
     pub url: String,
     pub title: String,
+    /// When the page was last opened
+    pub visited_at: DateTime<Utc>,
+}
+
+impl HistoryEntry {
+    /// How long ago the page was visited, e.g. "2h ago"
+    pub fn age_label(&self) -> String {
+        relative_time(self.visited_at, Utc::now())
+    }
+}
+
+/// One row of the bookmarks view
+#[derive(Debug, Clone)]
+pub struct BookmarkEntry {
+    pub url: String,
+    pub title: String,
+    pub tags: Vec<String>,
+}
+
+impl BookmarkEntry {
+    /// Tags as typed to filter by them, e.g. "#rust #async"
+    pub fn tags_label(&self) -> String {
+        self.tags
+            .iter()
+            .map(|tag| format!("#{}", tag))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// Response details and content hints for the page being shown
+#[derive(Debug, Clone, Default)]
+pub struct PageMeta {
+    pub status: Option<u16>,
+    pub content_type: Option<String>,
+    /// The page looks paywalled, so the summary may only cover a teaser
+    pub paywalled: bool,
+    /// When the page says it was published, shown as a freshness badge
+    pub published: Option<DateTime<Utc>>,
+    /// Links found on the page when `--max-links` kept only some of them
+    pub links_found: Option<usize>,
+    /// Tokens the summary took, when the API reports them
+    pub tokens: Option<u64>,
+    /// Estimated cost of those tokens in US dollars, when the model has a price
+    pub cost_usd: Option<f64>,
+    /// The summary covers only the start of the page (quick look mode)
+    pub quick_look: bool,
+    /// The summary is a bullet outline (outline mode)
+    pub outline: bool,
+    /// The page is a JSON response, explained rather than summarized
+    pub json: bool,
+    /// Preferred address the page declares for itself, when it has one
+    pub canonical: Option<String>,
+    /// Address of the page's AMP variant, when it has one
+    pub amp: Option<String>,
+}
+
+impl PageMeta {
+    /// Compact header label such as "200 · text/html · 3 days ago · 812 tokens ~$0.0005
+    /// · ⚡ quick look · outline · ⚠ possible paywall", or `None` when nothing is known;
+    /// without `emoji`, their ASCII stand-ins
+    pub fn header_label(&self, emoji: bool) -> Option<String> {
+        let mime = self
+            .content_type
+            .as_deref()
+            .and_then(|ct| ct.split(';').next())
+            .map(str::trim)
+            .filter(|mime| !mime.is_empty());
+
+        let mut parts = Vec::new();
+        if let Some(status) = self.status {
+            parts.push(status.to_string());
+        }
+        if let Some(mime) = mime {
+            parts.push(mime.to_string());
+        }
+        if let Some(published) = self.published {
+            parts.push(relative_age(published, Utc::now()));
+        }
+        if let Some(tokens) = self.tokens {
+            parts.push(match self.cost_usd {
+                Some(cost) => format!("{} tokens ~${:.4}", tokens, cost),
+                None => format!("{} tokens", tokens),
+            });
+        }
+        if self.quick_look {
+            parts.push(emoji::chrome("⚡ quick look", emoji).into_owned());
+        }
+        if self.outline {
+            parts.push("outline".to_string());
+        }
+        if self.paywalled {
+            parts.push(emoji::chrome("⚠ possible paywall", emoji).into_owned());
+        }
+
+        if parts.is_empty() {
+            None
+        } else {
+            Some(parts.join(" · "))
+        }
+    }
+
+    /// "showing 30 of 142" when `--max-links` hid some of the page's links
+    pub fn links_note(&self, shown: usize) -> Option<String> {
+        self.links_found
+            .filter(|&found| found > shown)
+            .map(|found| format!("showing {} of {}", shown, found))
+    }
+}
+
+/// Find-as-you-type state for the links panel
+#[derive(Debug, Clone, Default)]
+pub struct LinkFind {
+    pub query: String,
+    /// Number of links matching `query`
+    pub matches: usize,
+}
+
+impl LinkFind {
+    /// Match count such as "3/42", or `None` while the query is empty
+    pub fn count_label(&self, total: usize) -> Option<String> {
+        if self.query.is_empty() {
+            None
+        } else {
+            Some(format!("{}/{}", self.matches, total))
+        }
+    }
+}
+
+/// Where a cursor key moves the cursor of a text input
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CursorMove {
+    Left,
+    Right,
+    Home,
+    End,
+}
+
+/// Text being typed, with a cursor that can move within it
+#[derive(Debug, Clone, Default)]
+pub struct TextInput {
+    pub text: String,
+    /// Cursor position in characters, `text.chars().count()` at the end
+    pub cursor: usize,
+}
+
+impl TextInput {
+    /// Input holding `text` with the cursor after its last character
+    pub fn new(text: &str) -> Self {
+        Self {
+            text: text.to_string(),
+            cursor: text.chars().count(),
+        }
+    }
+
+    pub fn insert(&mut self, c: char) {
+        let index = self.byte_index(self.cursor);
+        self.text.insert(index, c);
+        self.cursor += 1;
+    }
+
+    /// Delete the character before the cursor
+    pub fn backspace(&mut self) {
+        if self.cursor > 0 {
+            self.cursor -= 1;
+            let index = self.byte_index(self.cursor);
+            self.text.remove(index);
+        }
+    }
+
+    /// Delete the character under the cursor
+    pub fn delete(&mut self) {
+        if self.cursor < self.text.chars().count() {
+            let index = self.byte_index(self.cursor);
+            self.text.remove(index);
+        }
+    }
+
+    pub fn move_cursor(&mut self, movement: CursorMove) {
+        let len = self.text.chars().count();
+        self.cursor = match movement {
+            CursorMove::Left => self.cursor.saturating_sub(1),
+            CursorMove::Right => (self.cursor + 1).min(len),
+            CursorMove::Home => 0,
+            CursorMove::End => len,
+        };
+    }
+
+    /// The text with `caret` inserted at the cursor, for plain-text displays
+    pub fn with_caret(&self, caret: char) -> String {
+        let mut text = self.text.clone();
+        text.insert(self.byte_index(self.cursor), caret);
+        text
+    }
+
+    fn byte_index(&self, cursor: usize) -> usize {
+        self.text
+            .char_indices()
+            .nth(cursor)
+            .map_or(self.text.len(), |(index, _)| index)
+    }
+}
+
+/// A row of the links panel while links are grouped by host
+#[derive(Debug, Clone)]
+pub enum LinkRow {
+    /// Host header; the links of `expanded` hosts follow it
+    Host {
+        host: String,
+        count: usize,
+        expanded: bool,
+    },
+    Link(Link),
+}
+
+/// One side of the compare view: a summary and the options that produced it
+#[derive(Debug, Clone)]
+pub struct ComparePane {
+    pub label: String,
+    pub summary: String,
+}
+
+/// One row of the settings or info screen
+#[derive(Debug, Clone)]
+pub struct SettingsItem {
+    pub label: String,
+    pub value: String,
+}
+
+/// Phase of loading a page or summary. Themes turn it into text with
+/// `label`, or with wording of their own.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LoadingStage {
+    Starting,
+    ReadingStdin,
+    FetchingPage,
+    ExtractingText,
+    ProcessingLinks,
+    Summarizing,
+    Regenerating,
+    /// The first of the two summaries of the compare view
+    SummarizingA,
+    SummarizingB,
+    FetchingSitemap,
+    Complete,
+}
+
+impl LoadingStage {
+    /// Plain English label such as "Fetching HTML content..."
+    pub fn label(&self) -> &'static str {
+        match self {
+            LoadingStage::Starting => "Starting...",
+            LoadingStage::ReadingStdin => "Reading standard input...",
+            LoadingStage::FetchingPage => "Fetching HTML content...",
+            LoadingStage::ExtractingText => "Extracting text content...",
+            LoadingStage::ProcessingLinks => "Processing page structure...",
+            LoadingStage::Summarizing => "Generating AI summary...",
+            LoadingStage::Regenerating => "Regenerating AI summary...",
+            LoadingStage::SummarizingA => "Generating summary A...",
+            LoadingStage::SummarizingB => "Generating summary B...",
+            LoadingStage::FetchingSitemap => "Fetching sitemap...",
+            LoadingStage::Complete => "Complete!",
+        }
+    }
+}
+
+/// What to do with the text typed into a prompt
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PromptAction {
+    /// Regenerate the summary with the text as an extra instruction
+    RegenerateWith,
+    /// Bookmark the current page with the text as its tags
+    Bookmark,
+}
+
+/// What to do when the user answers yes to a confirmation prompt
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfirmAction {
+    Quit,
+    /// Hand the URL to the system's browser
+    OpenInBrowser(String),
+    /// Load the URL again, stopping at its first redirect
+    LoadWithoutRedirects(String),
 }
 
 #[derive(Debug)]
@@ -22,16 +317,40 @@ pub enum BrowserState {
     Loading {
         url: String,
         progress: u16,
-        stage: String,
+        stage: LoadingStage,
+        /// Animated label such as "⠹ Summarizing with gpt-4.1-mini…" while the
+        /// LLM is the current wait, shown apart from the network progress
+        thinking: Option<String>,
     },
     Page {
         url: String,
         title: String,
         summary: String,
         links: Vec<Link>,
+        meta: PageMeta,
+        /// Active find-as-you-type over link texts, if any
+        link_find: Option<LinkFind>,
+        /// Links grouped by host, shown in the panel instead of `links` when set
+        link_groups: Option<Vec<LinkRow>>,
+        /// One-off feedback (e.g. where an export was saved), shown in place
+        /// of the key hints until the next key press
+        notice: Option<String>,
+        /// Summary before the last regenerate; while set, the words changed
+        /// since it are highlighted
+        diff_from: Option<String>,
+        /// Show the summary's markdown as it is, unstyled, instead of rendered
+        raw: bool,
+        /// Show all of a summary cut short by `--summary-preview`
+        expanded: bool,
     },
     URLInput {
-        input: String,
+        input: TextInput,
+    },
+    /// A line of text asked for by an action other than navigating
+    Prompt {
+        title: String,
+        input: TextInput,
+        on_submit: PromptAction,
     },
     URLSuggestions {
         original_url: String,
@@ -43,9 +362,46 @@ pub enum BrowserState {
         entries: Vec<HistoryEntry>,
         current_index: Option<usize>,
     },
+    /// Saved bookmarks, narrowed to those tagged with every word of `filter`
+    Bookmarks {
+        entries: Vec<BookmarkEntry>,
+        filter: String,
+        selected: usize,
+    },
     Error {
         message: String,
     },
+    Confirm {
+        message: String,
+        on_yes: ConfirmAction,
+    },
+    Settings {
+        items: Vec<SettingsItem>,
+        selected: usize,
+        /// Feedback such as the result of saving
+        status: Option<String>,
+    },
+    /// Version and runtime details worth quoting in a bug report
+    Info {
+        items: Vec<SettingsItem>,
+    },
+    /// What this session has done so far: pages, links, bytes and tokens
+    Stats {
+        items: Vec<SettingsItem>,
+    },
+    /// How the page's main text was found, shown on load with `--debug-extract`
+    Extraction {
+        items: Vec<SettingsItem>,
+    },
+    /// Two summaries of the same page side by side, each scrolled on its own
+    Compare {
+        url: String,
+        title: String,
+        left: ComparePane,
+        right: ComparePane,
+        /// Pane scrolled by the arrow keys: 0 for left, 1 for right
+        focus: usize,
+    },
 }
 
 #[derive(Debug)]
@@ -53,23 +409,105 @@ pub enum UserAction {
     Quit,
     FollowLink(usize),
     FollowSelectedLink,
+    /// Show the selected link's page until the next key, without adding it to history
+    PeekLink,
     GoBack,
     GoForward,
+    /// Navigate to the page most recently dropped from the forward history
+    ReopenLast,
     ShowHistory,
+    /// Bookmark the current page, asking for its tags
+    AddBookmark,
+    ShowBookmarks,
+    SelectPrevBookmark,
+    SelectNextBookmark,
+    OpenBookmark,
+    RemoveBookmark,
     EnterUrl,
+    /// Open the URL input holding the current URL, to edit it
+    EditUrl,
     ConfirmInput(String),
     CancelInput,
     Refresh,
+    Regenerate,
+    /// Ask for an instruction, then regenerate the summary following it
+    RegenerateWithInstruction,
+    /// The text typed into a prompt was submitted
+    SubmitPrompt(String),
+    ShowSitemap,
+    /// Crawl the site from the current page, listing pages as they are found
+    StartCrawl,
+    /// Stop the crawl shown on the page, keeping what it found
+    StopCrawl,
+    ExportPage,
+    /// Open the page's extracted text in the user's editor
+    OpenInEditor,
+    CopyMarkdownLink,
+    /// Copy every link of the page as a markdown list
+    YankLinks,
+    ToggleLinkGroups,
+    CompareSummaries,
+    /// Show or hide what changed in the last regenerated summary
+    ToggleDiff,
+    /// Switch between summarizing the whole page and only its start
+    ToggleQuickLook,
+    /// Switch between prose summaries and bullet outlines
+    ToggleOutline,
+    /// Switch between the rendered summary and its raw markdown
+    ToggleRawSummary,
+    /// Show the whole summary, or its `--summary-preview` again
+    ToggleExpanded,
+    /// Load the page's canonical (or AMP) version
+    OpenCanonical,
+    /// Scroll the summary to its previous header
+    PrevSection,
+    /// Scroll the summary to its next header
+    NextSection,
+    /// Copy the summary's section at the top of the view as markdown
+    CopySection,
+    /// Copy a link to the page's anchor for the section at the top of the view
+    CopySectionLink,
+    /// Move the arrow keys to the other compare pane
+    SwitchPane,
+    /// The terminal was resized; render the current state again
+    Redraw,
+    /// No input arrived within the timeout given to `get_user_input`
+    Tick,
+    /// The mouse moved to this cell
+    Hover {
+        column: u16,
+        row: u16,
+    },
+    /// The left mouse button was pressed on this cell
+    Click {
+        column: u16,
+        row: u16,
+    },
     ScrollUp,
     ScrollDown,
+    /// Several queued scroll keys merged into one: lines down, negative for up
+    ScrollBy(i32),
     SelectPrevLink,
     SelectNextLink,
     InputChar(char),
     Backspace,
+    /// Delete the character under the cursor of the URL input
+    DeleteChar,
+    MoveCursor(CursorMove),
     SelectPrevSuggestion,
     SelectNextSuggestion,
     ConfirmSuggestion,
     DismissError,
+    Confirm,
+    FindLink,
+    OpenSettings,
+    ShowInfo,
+    ShowStats,
+    SelectPrevSetting,
+    SelectNextSetting,
+    NextSettingValue,
+    PrevSettingValue,
+    SaveSettings,
 }
 
 /// Trait that all UI implementations must implement
@@ -80,16 +518,71 @@ pub trait UIInterface {
         Self: Sized;
 
     fn cleanup(&mut self) -> Result<()>;
+    /// Hand the terminal back to the shell, e.g. while an editor runs
+    fn suspend(&mut self) -> Result<()> {
+        self.cleanup()
+    }
+    /// Take the terminal again after `suspend`; the next render redraws it all
+    fn resume(&mut self) -> Result<()> {
+        enable_raw_mode()?;
+        execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture)?;
+        self.clear()
+    }
     fn render(&mut self, state: &BrowserState) -> Result<()>;
-    fn get_user_input(&mut self, state: &BrowserState) -> Result<UserAction>;
+    /// Forget what is on screen so the next render redraws every cell
+    fn clear(&mut self) -> Result<()>;
+    /// Give `content_percent` of the page width to the summary, the rest to links
+    fn set_split_ratio(&mut self, content_percent: u16);
+    /// Wrap summary text at most `columns` wide, centred in a wider pane
+    fn set_max_width(&mut self, columns: u16);
+    /// Space out summaries with a blank line after every block
+    fn set_line_spacing(&mut self, enabled: bool);
+    /// Show summaries longer than `chars` characters cut short until expanded
+    fn set_summary_preview(&mut self, chars: Option<usize>);
+    /// Emit OSC 8 sequences so links and URLs can be clicked open
+    fn set_hyperlinks(&mut self, enabled: bool);
+    /// Show emoji in the chrome, or their ASCII stand-ins when `false`
+    fn set_emoji(&mut self, enabled: bool);
+    /// Wait for the user's next action in `state`; after `timeout` without
+    /// one, `UserAction::Tick`. `None` waits for input however long it takes.
+    fn get_user_input(
+        &mut self,
+        state: &BrowserState,
+        timeout: Option<Duration>,
+    ) -> Result<UserAction>;
 
     // Scroll management
     fn scroll_up(&mut self);
     fn scroll_down(&mut self);
     fn reset_scroll(&mut self);
+    /// Lines the summary is scrolled down, to come back to after a peek
+    fn scroll_position(&self) -> u16;
+    fn set_scroll_position(&mut self, position: u16);
+    /// Furthest the summary scrolls, as of the last render
+    fn max_scroll(&self) -> u16;
+    /// Columns the summary was last wrapped at
+    fn summary_width(&self) -> usize;
+    /// Scroll the summary so the first line containing `text` (ignoring case)
+    /// is at the top; `false` when no line contains it
+    fn scroll_to_text(&mut self, summary: &str, text: &str) -> bool;
+    /// Scroll compare pane `pane` (0 left, 1 right) by `lines`, negative for up
+    fn scroll_pane(&mut self, pane: usize, lines: i32);
+    fn reset_pane_scroll(&mut self);
 
     // Link selection
     fn select_prev_link(&mut self, total_links: usize);
     fn select_next_link(&mut self, total_links: usize);
     fn get_selected_link(&self) -> usize;
+    fn select_link(&mut self, index: usize);
+
+    // Mouse
+    /// URL of the link drawn at `column`, `row` in the last frame, if any
+    fn link_at(&self, _column: u16, _row: u16) -> Option<String> {
+        None
+    }
+    /// Point out the link at `column`, `row` (the mouse) in the status bar;
+    /// `true` when the next render shows something different
+    fn hover(&mut self, _column: u16, _row: u16) -> bool {
+        false
+    }
 }
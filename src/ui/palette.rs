@@ -0,0 +1,130 @@
+// Color palette for the palette-driven light theme
+// The built-in light palette can be overridden role by role from a TOML or JSON file
+
+use anyhow::{anyhow, Context, Result};
+use ratatui::style::Color;
+use serde::Deserialize;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Palette {
+    /// Body text
+    pub text: Color,
+    /// Secondary text, hints and block titles
+    pub muted: Color,
+    /// Highlights such as the logo, progress and key hints
+    pub accent: Color,
+    /// Top-level summary headings
+    pub header1: Color,
+    /// Minor summary headings
+    pub header3: Color,
+    /// Links, the current URL and setting values
+    pub link: Color,
+    pub border: Color,
+    /// Background of the selected link or row
+    pub selection: Color,
+    /// Background of inline code
+    pub code_bg: Color,
+    pub error: Color,
+    /// Confirmation prompts
+    pub warning: Color,
+    /// Screen background; `None` leaves the terminal's own background
+    pub background: Option<Color>,
+}
+
+impl Palette {
+    /// Dark text and saturated accents for light terminal backgrounds
+    pub fn light() -> Self {
+        Self {
+            text: Color::Rgb(28, 28, 30),
+            muted: Color::Rgb(99, 99, 102),
+            accent: Color::Rgb(0, 88, 208),
+            header1: Color::Rgb(0, 88, 208),
+            header3: Color::Rgb(0, 110, 60),
+            link: Color::Rgb(0, 88, 208),
+            border: Color::Rgb(174, 174, 178),
+            selection: Color::Rgb(210, 228, 255),
+            code_bg: Color::Rgb(232, 232, 236),
+            error: Color::Rgb(192, 28, 40),
+            warning: Color::Rgb(176, 96, 0),
+            background: None,
+        }
+    }
+
+    /// Load a palette file on top of the light palette. Files ending in
+    /// `.json` are read as JSON, anything else as TOML.
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read theme file {}", path.display()))?;
+
+        let file: PaletteFile = if path.extension().is_some_and(|ext| ext == "json") {
+            serde_json::from_str(&contents)
+                .with_context(|| format!("Invalid theme file {}", path.display()))?
+        } else {
+            toml::from_str(&contents)
+                .with_context(|| format!("Invalid theme file {}", path.display()))?
+        };
+
+        file.apply(Self::light())
+    }
+}
+
+/// Color roles as written in a theme file, each an "#rrggbb" string
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct PaletteFile {
+    text: Option<String>,
+    muted: Option<String>,
+    accent: Option<String>,
+    header1: Option<String>,
+    header3: Option<String>,
+    link: Option<String>,
+    border: Option<String>,
+    selection: Option<String>,
+    code_bg: Option<String>,
+    error: Option<String>,
+    warning: Option<String>,
+    background: Option<String>,
+}
+
+impl PaletteFile {
+    fn apply(self, mut palette: Palette) -> Result<Palette> {
+        let roles = [
+            (self.text, &mut palette.text),
+            (self.muted, &mut palette.muted),
+            (self.accent, &mut palette.accent),
+            (self.header1, &mut palette.header1),
+            (self.header3, &mut palette.header3),
+            (self.link, &mut palette.link),
+            (self.border, &mut palette.border),
+            (self.selection, &mut palette.selection),
+            (self.code_bg, &mut palette.code_bg),
+            (self.error, &mut palette.error),
+            (self.warning, &mut palette.warning),
+        ];
+        for (value, slot) in roles {
+            if let Some(value) = value {
+                *slot = parse_hex_color(&value)?;
+            }
+        }
+
+        if let Some(background) = self.background {
+            palette.background = Some(parse_hex_color(&background)?);
+        }
+        Ok(palette)
+    }
+}
+
+fn parse_hex_color(value: &str) -> Result<Color> {
+    let hex = value.trim().trim_start_matches('#');
+    // Checked before slicing, which would panic inside a multi-byte character
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(anyhow!("Invalid color '{}', expected #rrggbb", value));
+    }
+
+    let channel = |range: std::ops::Range<usize>| {
+        u8::from_str_radix(&hex[range], 16)
+            .map_err(|_| anyhow!("Invalid color '{}', expected #rrggbb", value))
+    };
+    Ok(Color::Rgb(channel(0..2)?, channel(2..4)?, channel(4..6)?))
+}
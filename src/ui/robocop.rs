@@ -2,12 +2,21 @@
 // Capturing the 1987 cyberpunk aesthetic: corporate chrome, digital amber displays,
 // and the cold efficiency of OCP's dystopian future
 
-use super::{BrowserState, HistoryEntry, UIInterface, UserAction};
-use crate::common::{markdown::MarkdownElement, ui as ui_common};
-use crate::links::Link;
+use super::{
+    BookmarkEntry, BrowserState, ComparePane, HistoryEntry, LinkFind, LoadingStage, SettingsItem,
+    TextInput, UIInterface, UserAction,
+};
+use crate::common::{
+    diff, emoji,
+    hyperlink::{self, Hyperlink},
+    keys,
+    markdown::MarkdownElement,
+    text::truncate_at_word_boundary,
+    ui::{self as ui_common, LinkLine},
+};
 use anyhow::Result;
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyModifiers},
+    event::{DisableMouseCapture, EnableMouseCapture},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
@@ -16,18 +25,39 @@ use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Margin, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Clear, Gauge, List, ListItem, Paragraph, Wrap},
+    widgets::{Block, Borders, Clear, Gauge, List, ListItem, ListState, Paragraph, Wrap},
     Frame, Terminal,
 };
 use std::io::{self, Stdout};
+use std::time::Duration;
 use textwrap::fill;
 
+/// Share of the page width given to the summary unless `--split-ratio` is set
+const CONTENT_PERCENT: u16 = 70;
+
 pub struct RobocopUI {
     terminal: Terminal<CrosstermBackend<Stdout>>,
     scroll_position: u16,
     selected_link: usize,
     links_scroll: usize,
     max_scroll: u16,
+    /// Summary wrap width from the last render, for locating text in it
+    summary_width: usize,
+    scroll_accel: ui_common::ScrollAccel,
+    pane_scroll: ui_common::PaneScroll,
+    /// Share of the page width given to the summary
+    content_percent: u16,
+    /// Widest the summary text wraps, centred in its pane when narrower
+    max_width: Option<u16>,
+    /// Blank line after every block of a summary
+    line_spacing: bool,
+    /// Characters of a summary shown until it is expanded
+    summary_preview: Option<usize>,
+    /// Make links clickable with OSC 8 escape sequences
+    hyperlinks: bool,
+    /// Show emoji in the chrome rather than their ASCII stand-ins
+    emoji: bool,
+    input: keys::InputReader,
 }
 
 // RoboCop 1987 color palette - Corporate dystopian future
@@ -54,6 +84,16 @@ impl UIInterface for RobocopUI {
             selected_link: 0,
             links_scroll: 0,
             max_scroll: 0,
+            summary_width: 0,
+            scroll_accel: ui_common::ScrollAccel::default(),
+            pane_scroll: ui_common::PaneScroll::default(),
+            content_percent: CONTENT_PERCENT,
+            max_width: None,
+            line_spacing: false,
+            summary_preview: None,
+            hyperlinks: false,
+            emoji: true,
+            input: keys::InputReader::default(),
         })
     }
 
@@ -68,42 +108,74 @@ impl UIInterface for RobocopUI {
         Ok(())
     }
 
+    fn clear(&mut self) -> Result<()> {
+        self.terminal.clear()?;
+        Ok(())
+    }
+
     fn render(&mut self, state: &BrowserState) -> Result<()> {
         match state {
             BrowserState::Loading {
                 url,
                 progress,
                 stage,
+                thinking,
             } => {
-                let (url, progress, stage) = (url.clone(), *progress, stage.clone());
-                self.terminal
-                    .draw(|f| Self::render_loading(f, &url, progress, &stage))?;
+                self.terminal.draw(|f| {
+                    Self::render_loading(f, url, *progress, *stage, thinking.as_deref())
+                })?;
             }
             BrowserState::Page {
                 url,
                 title,
                 summary,
                 links,
+                meta,
+                link_find,
+                link_groups,
+                notice,
+                diff_from,
+                raw,
+                expanded,
             } => {
-                let (url, title, summary, links) =
-                    (url.clone(), title.clone(), summary.clone(), links.clone());
+                let links_note = meta.links_note(links.len());
+                let summary = ui_common::shown_summary(summary, *expanded, self.summary_preview);
+                let (url, title, summary, links, link_find, notice) = (
+                    url.clone(),
+                    title.clone(),
+                    diff::summary_with_changes(&summary, diff_from.as_deref().filter(|_| !*raw)),
+                    ui_common::link_lines(links, link_groups.as_deref()),
+                    link_find.clone(),
+                    notice.clone(),
+                );
+                let status = meta.header_label(self.emoji);
+                let (content_percent, max_width, line_spacing) =
+                    (self.content_percent, self.max_width, self.line_spacing);
                 let (scroll_pos, selected_link, links_scroll) =
                     (self.scroll_position, self.selected_link, self.links_scroll);
 
-                self.terminal.draw(|f| {
+                hyperlink::draw(&mut self.terminal, self.hyperlinks, |f| {
                     Self::render_page(
                         f,
+                        content_percent,
+                        max_width,
+                        line_spacing,
                         &url,
+                        status.as_deref(),
                         &title,
                         &summary,
+                        *raw,
                         &links,
                         scroll_pos,
                         selected_link,
                         links_scroll,
-                    );
+                        link_find.as_ref(),
+                        links_note.as_deref(),
+                        notice.as_deref(),
+                    )
                 })?;
 
-                self.update_max_scroll(&summary);
+                self.update_max_scroll(&summary, *raw);
                 self.update_links_scroll_with_height(
                     self.terminal.size()?.height.saturating_sub(12) as usize,
                 );
@@ -116,9 +188,24 @@ impl UIInterface for RobocopUI {
                 self.terminal
                     .draw(|f| Self::render_history(f, &entries, current_index))?;
             }
+            BrowserState::Bookmarks {
+                entries,
+                filter,
+                selected,
+            } => {
+                self.terminal
+                    .draw(|f| Self::render_bookmarks(f, entries, filter, *selected))?;
+            }
             BrowserState::URLInput { input } => {
                 let input = input.clone();
-                self.terminal.draw(|f| Self::render_url_input(f, &input))?;
+                self.terminal.draw(|f| {
+                    Self::render_input(f, "NETWORK INPUT", "ENTER NETWORK ADDRESS:", &input)
+                })?;
+            }
+            BrowserState::Prompt { title, input, .. } => {
+                let (label, input) = (format!("{}:", title.to_uppercase()), input.clone());
+                self.terminal
+                    .draw(|f| Self::render_input(f, "OPERATOR INPUT", &label, &input))?;
             }
             BrowserState::URLSuggestions {
                 original_url,
@@ -143,61 +230,71 @@ impl UIInterface for RobocopUI {
                 })?;
             }
             BrowserState::Error { message } => {
+                let (message, emoji) = (message.clone(), self.emoji);
+                self.terminal
+                    .draw(|f| Self::render_error(f, &message, emoji))?;
+            }
+            BrowserState::Confirm { message, .. } => {
                 let message = message.clone();
-                self.terminal.draw(|f| Self::render_error(f, &message))?;
+                self.terminal.draw(|f| Self::render_confirm(f, &message))?;
+            }
+            BrowserState::Settings {
+                items,
+                selected,
+                status,
+            } => {
+                let (items, selected, status) = (items.clone(), *selected, status.clone());
+                self.terminal.draw(|f| {
+                    Self::render_settings(f, &items, selected, status.as_deref())
+                })?;
+            }
+            BrowserState::Info { items } => {
+                let items = items.clone();
+                self.terminal
+                    .draw(|f| Self::render_info(f, "SYSTEM IDENTIFICATION", &items))?;
+            }
+            BrowserState::Stats { items } => {
+                let items = items.clone();
+                self.terminal
+                    .draw(|f| Self::render_info(f, "SESSION TELEMETRY", &items))?;
+            }
+            BrowserState::Extraction { items } => {
+                let items = items.clone();
+                self.terminal
+                    .draw(|f| Self::render_info(f, "EXTRACTION DIAGNOSTICS", &items))?;
+            }
+            BrowserState::Compare {
+                url,
+                title,
+                left,
+                right,
+                focus,
+            } => {
+                let (positions, line_spacing) = (self.pane_scroll.positions(), self.line_spacing);
+                let mut max_scroll = [0; 2];
+                self.terminal.draw(|f| {
+                    max_scroll = Self::render_compare(
+                        f,
+                        url,
+                        title,
+                        [left, right],
+                        *focus,
+                        positions,
+                        line_spacing,
+                    );
+                })?;
+                self.pane_scroll.set_max(max_scroll);
             }
         }
         Ok(())
     }
 
-    fn get_user_input(&mut self, state: &BrowserState) -> Result<UserAction> {
-        loop {
-            if let Event::Key(key) = event::read()? {
-                match state {
-                    BrowserState::URLInput { input } => match key.code {
-                        KeyCode::Esc => return Ok(UserAction::CancelInput),
-                        KeyCode::Enter => return Ok(UserAction::ConfirmInput(input.clone())),
-                        KeyCode::Backspace => return Ok(UserAction::Backspace),
-                        KeyCode::Char(c) => return Ok(UserAction::InputChar(c)),
-                        _ => continue,
-                    },
-                    BrowserState::History { .. } => return Ok(UserAction::GoBack),
-                    BrowserState::URLSuggestions { .. } => match key.code {
-                        KeyCode::Esc => return Ok(UserAction::CancelInput),
-                        KeyCode::Char('q') => return Ok(UserAction::Quit),
-                        KeyCode::Up => return Ok(UserAction::SelectPrevSuggestion),
-                        KeyCode::Down => return Ok(UserAction::SelectNextSuggestion),
-                        KeyCode::Enter => return Ok(UserAction::ConfirmSuggestion),
-                        _ => continue,
-                    },
-                    BrowserState::Error { .. } => return Ok(UserAction::DismissError),
-                    _ => match key.code {
-                        KeyCode::Char('q') => return Ok(UserAction::Quit),
-                        KeyCode::Char('b') => return Ok(UserAction::GoBack),
-                        KeyCode::Char('f') => return Ok(UserAction::GoForward),
-                        KeyCode::Char('h') => return Ok(UserAction::ShowHistory),
-                        KeyCode::Char('g') => return Ok(UserAction::EnterUrl),
-                        KeyCode::Char('r') => return Ok(UserAction::Refresh),
-                        KeyCode::Up if key.modifiers.contains(KeyModifiers::SHIFT) => {
-                            return Ok(UserAction::SelectPrevLink)
-                        }
-                        KeyCode::Down if key.modifiers.contains(KeyModifiers::SHIFT) => {
-                            return Ok(UserAction::SelectNextLink)
-                        }
-                        KeyCode::Up => return Ok(UserAction::ScrollUp),
-                        KeyCode::Down => return Ok(UserAction::ScrollDown),
-                        KeyCode::Enter => return Ok(UserAction::FollowSelectedLink),
-                        KeyCode::Char(c) if c.is_ascii_digit() => {
-                            let digit = c.to_digit(10).unwrap() as usize;
-                            if digit > 0 {
-                                return Ok(UserAction::FollowLink(digit));
-                            }
-                        }
-                        _ => continue,
-                    },
-                }
-            }
-        }
+    fn get_user_input(
+        &mut self,
+        state: &BrowserState,
+        timeout: Option<Duration>,
+    ) -> Result<UserAction> {
+        self.input.next_action(state, timeout)
     }
 
     fn reset_scroll(&mut self) {
@@ -206,14 +303,73 @@ impl UIInterface for RobocopUI {
         self.links_scroll = 0;
     }
 
+    fn scroll_position(&self) -> u16 {
+        self.scroll_position
+    }
+
+    fn summary_width(&self) -> usize {
+        self.summary_width
+    }
+
+    fn set_scroll_position(&mut self, position: u16) {
+        self.scroll_position = position;
+    }
+
+    fn max_scroll(&self) -> u16 {
+        self.max_scroll
+    }
+
+    fn scroll_to_text(&mut self, summary: &str, text: &str) -> bool {
+        match ui_common::find_text_line(summary, self.summary_width, self.line_spacing, text) {
+            Some(line) => {
+                self.scroll_position = line;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn scroll_pane(&mut self, pane: usize, lines: i32) {
+        self.pane_scroll.scroll(pane, lines);
+    }
+
+    fn reset_pane_scroll(&mut self) {
+        self.pane_scroll.reset();
+    }
+
+    fn set_split_ratio(&mut self, content_percent: u16) {
+        self.content_percent = content_percent;
+    }
+
+    fn set_max_width(&mut self, columns: u16) {
+        self.max_width = Some(columns);
+    }
+
+    fn set_line_spacing(&mut self, enabled: bool) {
+        self.line_spacing = enabled;
+    }
+
+    fn set_summary_preview(&mut self, chars: Option<usize>) {
+        self.summary_preview = chars;
+    }
+
+    fn set_hyperlinks(&mut self, enabled: bool) {
+        self.hyperlinks = enabled;
+    }
+
+    fn set_emoji(&mut self, enabled: bool) {
+        self.emoji = enabled;
+    }
+
     fn scroll_up(&mut self) {
-        self.scroll_position = self.scroll_position.saturating_sub(1);
+        let step = self.scroll_accel.step(false);
+        self.scroll_position = self.scroll_position.saturating_sub(step);
     }
 
     fn scroll_down(&mut self) {
-        if self.scroll_position < self.max_scroll {
-            self.scroll_position += 1;
-        }
+        let step = self.scroll_accel.step(true);
+        self.scroll_position =
+            ui_common::scroll_down_clamped(self.scroll_position, step, self.max_scroll);
     }
 
     fn select_prev_link(&mut self, links_len: usize) {
@@ -233,10 +389,38 @@ impl UIInterface for RobocopUI {
     fn get_selected_link(&self) -> usize {
         self.selected_link
     }
+
+    fn select_link(&mut self, index: usize) {
+        self.selected_link = index;
+        self.update_links_scroll();
+    }
 }
 
 impl RobocopUI {
-    fn render_loading(f: &mut Frame, url: &str, progress: u16, stage: &str) {
+    /// Loading phases in the OCP console's own words
+    fn stage_label(stage: LoadingStage) -> &'static str {
+        match stage {
+            LoadingStage::Starting => "INITIALIZING",
+            LoadingStage::ReadingStdin => "READING LOCAL INPUT",
+            LoadingStage::FetchingPage => "ACQUIRING TARGET DATA",
+            LoadingStage::ExtractingText => "EXTRACTING INTELLIGENCE",
+            LoadingStage::ProcessingLinks => "MAPPING NETWORK LINKS",
+            LoadingStage::Summarizing => "COMPILING BRIEFING",
+            LoadingStage::Regenerating => "RECOMPILING BRIEFING",
+            LoadingStage::SummarizingA => "COMPILING BRIEFING A",
+            LoadingStage::SummarizingB => "COMPILING BRIEFING B",
+            LoadingStage::FetchingSitemap => "SCANNING SITE DIRECTORY",
+            LoadingStage::Complete => "OPERATION COMPLETE",
+        }
+    }
+
+    fn render_loading(
+        f: &mut Frame,
+        url: &str,
+        progress: u16,
+        stage: LoadingStage,
+        thinking: Option<&str>,
+    ) {
         let area = f.size();
 
         // Corporate-style header bar
@@ -267,6 +451,7 @@ impl RobocopUI {
                 Constraint::Length(3),  // Progress bar
                 Constraint::Length(1),  // Spacer
                 Constraint::Length(2),  // Current operation
+                Constraint::Length(1),  // Neural processor
                 Constraint::Min(0),     // Bottom spacer
             ])
             .split(Rect {
@@ -323,24 +508,41 @@ impl RobocopUI {
 
         // Current operation in monospace corporate style
         f.render_widget(
-            Paragraph::new(format!("OPERATION: {}", stage.to_uppercase()))
+            Paragraph::new(format!("OPERATION: {}", Self::stage_label(stage)))
                 .style(Style::default().fg(DATA_WHITE))
                 .alignment(Alignment::Center),
             main_chunks[7],
         );
+
+        if let Some(thinking) = thinking {
+            f.render_widget(
+                Paragraph::new(format!("NEURAL PROCESSOR: {}", thinking.to_uppercase()))
+                    .style(Style::default().fg(PRIMARY_AMBER).add_modifier(Modifier::BOLD))
+                    .alignment(Alignment::Center),
+                main_chunks[8],
+            );
+        }
     }
 
     #[allow(clippy::too_many_arguments)]
     fn render_page(
         f: &mut Frame,
+        content_percent: u16,
+        max_width: Option<u16>,
+        line_spacing: bool,
         url: &str,
+        status: Option<&str>,
         title: &str,
         summary: &str,
-        links: &[Link],
+        raw: bool,
+        links: &[LinkLine],
         scroll_pos: u16,
         selected_link: usize,
         links_scroll: usize,
-    ) {
+        link_find: Option<&LinkFind>,
+        links_note: Option<&str>,
+        notice: Option<&str>,
+    ) -> Vec<Hyperlink> {
         let area = f.size();
 
         // Corporate header bar
@@ -374,30 +576,138 @@ impl RobocopUI {
                 height: area.height - 1,
             });
 
-        Self::render_header(f, main_chunks[1], url, title);
+        Self::render_header(f, main_chunks[1], url, status, title);
 
         // Content layout - corporate split screen
-        let content_chunks = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([Constraint::Percentage(70), Constraint::Percentage(30)])
-            .split(main_chunks[2]);
+        let content_chunks = ui_common::split_content(main_chunks[2], content_percent);
 
-        Self::render_summary(f, content_chunks[0], summary, scroll_pos);
-        Self::render_links(f, content_chunks[1], links, selected_link, links_scroll);
-        Self::render_status_bar(f, main_chunks[3]);
+        Self::render_summary(
+            f,
+            content_chunks[0],
+            summary,
+            scroll_pos,
+            max_width,
+            line_spacing,
+            raw,
+        );
+        Self::render_status_bar(f, main_chunks[3], notice);
+        Self::render_links(
+            f,
+            content_chunks[1],
+            links,
+            selected_link,
+            links_scroll,
+            link_find,
+            links_note,
+        )
+    }
+
+    /// Draw both compare panes and return their max scroll
+    fn render_compare(
+        f: &mut Frame,
+        url: &str,
+        title: &str,
+        panes: [&ComparePane; 2],
+        focus: usize,
+        positions: [u16; 2],
+        line_spacing: bool,
+    ) -> [u16; 2] {
+        let area = f.size();
+
+        f.render_widget(
+            Paragraph::new("═══════════ OCP COMPARATIVE ANALYSIS ═══════════")
+                .style(Style::default().fg(CHROME_BLUE).bg(CONSOLE_BLACK))
+                .alignment(Alignment::Center),
+            Rect {
+                x: 0,
+                y: 0,
+                width: area.width,
+                height: 1,
+            },
+        );
+
+        let main_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1),  // Spacer
+                Constraint::Length(6),  // Header info
+                Constraint::Min(10),    // Analysis panes
+                Constraint::Length(3),  // Status bar
+            ])
+            .split(Rect {
+                x: 0,
+                y: 1,
+                width: area.width,
+                height: area.height - 1,
+            });
+
+        Self::render_header(f, main_chunks[1], url, None, title);
+
+        let style = ui_common::CompareStyle {
+            text: Style::default().fg(DATA_WHITE),
+            block: |label: &str, focused: bool| {
+                let (border, title_color) = if focused {
+                    (PRIMARY_AMBER, PRIMARY_AMBER)
+                } else {
+                    (DARK_CHROME, STEEL_GRAY)
+                };
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(border))
+                    .title(label.to_uppercase())
+                    .title_style(Style::default().fg(title_color))
+            },
+            markdown: Self::style_markdown_element,
+        };
+        let max_scroll = ui_common::render_compare_panes(
+            f,
+            main_chunks[2],
+            panes,
+            focus,
+            positions,
+            line_spacing,
+            &style,
+        );
+
+        let key = Style::default().fg(PRIMARY_AMBER).add_modifier(Modifier::BOLD);
+        f.render_widget(
+            Paragraph::new(Line::from(vec![
+                Span::styled("COMMANDS: ", Style::default().fg(STEEL_GRAY)),
+                Span::styled("TAB", key),
+                Span::styled(" SWITCH UNIT  ", Style::default().fg(DATA_WHITE)),
+                Span::styled("↑↓", key),
+                Span::styled(" SCROLL  ", Style::default().fg(DATA_WHITE)),
+                Span::styled("ESC", key),
+                Span::styled(" RETURN", Style::default().fg(DATA_WHITE)),
+            ]))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(DARK_CHROME))
+                    .title("SYSTEM COMMANDS")
+                    .title_style(Style::default().fg(STEEL_GRAY)),
+            ),
+            main_chunks[3],
+        );
+
+        max_scroll
     }
 
-    fn render_header(f: &mut Frame, area: Rect, url: &str, title: &str) {
+    fn render_header(f: &mut Frame, area: Rect, url: &str, status: Option<&str>, title: &str) {
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([Constraint::Length(3), Constraint::Length(3)])
             .split(area);
 
+        // Single-line displays: long titles end in an ellipsis, long URLs lose their middle
+        let inner_width = area.width.saturating_sub(2) as usize;
+        let title = truncate_at_word_boundary(title, inner_width);
+        let url = ui_common::truncate_middle(url, inner_width);
+
         // Title in corporate amber display style
         f.render_widget(
             Paragraph::new(title)
                 .style(Style::default().fg(PRIMARY_AMBER).add_modifier(Modifier::BOLD))
-                .wrap(Wrap { trim: true })
                 .block(
                     Block::default()
                         .borders(Borders::ALL)
@@ -408,32 +718,49 @@ impl RobocopUI {
             chunks[0],
         );
 
-        // URL in system green
+        // URL in system green, response code in the block title
+        let address_title = match status {
+            Some(status) => format!("NETWORK ADDRESS // {}", status.to_uppercase()),
+            None => "NETWORK ADDRESS".to_string(),
+        };
         f.render_widget(
             Paragraph::new(url)
                 .style(Style::default().fg(SYSTEM_GREEN))
-                .wrap(Wrap { trim: true })
                 .block(
                     Block::default()
                         .borders(Borders::ALL)
                         .border_style(Style::default().fg(DARK_CHROME))
-                        .title("NETWORK ADDRESS")
+                        .title(address_title)
                         .title_style(Style::default().fg(STEEL_GRAY)),
                 ),
             chunks[1],
         );
     }
 
-    fn render_summary(f: &mut Frame, area: Rect, summary: &str, scroll_pos: u16) {
-        let width = area.width.saturating_sub(4) as usize;
+    fn render_summary(
+        f: &mut Frame,
+        area: Rect,
+        summary: &str,
+        scroll_pos: u16,
+        max_width: Option<u16>,
+        line_spacing: bool,
+        raw: bool,
+    ) {
+        let (width, indent) =
+            ui_common::text_width(area.width.saturating_sub(4) as usize, max_width);
         let visible_height = area.height.saturating_sub(2) as usize;
 
-        let visible_lines = ui_common::get_visible_markdown_lines(
-            summary,
-            width,
-            scroll_pos,
-            visible_height,
-            Self::style_markdown_element,
+        let visible_lines = ui_common::indent_lines(
+            ui_common::get_visible_markdown_lines(
+                summary,
+                width,
+                line_spacing,
+                scroll_pos,
+                visible_height,
+                raw,
+                Self::style_markdown_element,
+            ),
+            indent,
         );
 
         if visible_lines.is_empty() {
@@ -472,7 +799,9 @@ impl RobocopUI {
         let max_scroll = ui_common::calculate_max_scroll_for_markdown(
             summary,
             width,
+            line_spacing,
             visible_height,
+            raw,
             Self::style_markdown_element,
         );
 
@@ -521,16 +850,40 @@ impl RobocopUI {
                 .bg(CONSOLE_BLACK),
             MarkdownElement::Normal(_) => Style::default().fg(DATA_WHITE),
             MarkdownElement::Empty => Style::default(),
+            MarkdownElement::TaskMarker(true) => Style::default().fg(SYSTEM_GREEN),
+            MarkdownElement::TaskMarker(false) => Style::default().fg(PRIMARY_AMBER),
+            MarkdownElement::DefinitionTerm => Style::default()
+                .fg(PRIMARY_AMBER)
+                .add_modifier(Modifier::BOLD),
+            MarkdownElement::DiffAdded => Style::default()
+                .fg(SYSTEM_GREEN)
+                .add_modifier(Modifier::UNDERLINED),
+            MarkdownElement::DiffRemoved => Style::default()
+                .fg(WARNING_RED)
+                .add_modifier(Modifier::CROSSED_OUT),
         }
     }
 
     fn render_links(
         f: &mut Frame,
         area: Rect,
-        links: &[Link],
+        links: &[LinkLine],
         selected_link: usize,
         links_scroll: usize,
-    ) {
+        link_find: Option<&LinkFind>,
+        links_note: Option<&str>,
+    ) -> Vec<Hyperlink> {
+        let title = match link_find {
+            Some(find) => match find.count_label(links.len()) {
+                Some(count) => format!("LINK SEARCH: {}_ [{}]", find.query, count),
+                None => format!("LINK SEARCH: {}_", find.query),
+            },
+            None => match links_note {
+                Some(note) => format!("NAVIGATION LINKS [{}]", note.to_uppercase()),
+                None => "NAVIGATION LINKS".to_string(),
+            },
+        };
+
         if links.is_empty() {
             f.render_widget(
                 Paragraph::new("[ NO LINKS DETECTED ]")
@@ -540,12 +893,12 @@ impl RobocopUI {
                         Block::default()
                             .borders(Borders::ALL)
                             .border_style(Style::default().fg(DARK_CHROME))
-                            .title("NAVIGATION LINKS")
+                            .title(title)
                             .title_style(Style::default().fg(STEEL_GRAY)),
                     ),
                 area,
             );
-            return;
+            return Vec::new();
         }
 
         let visible_height = area.height.saturating_sub(2) as usize;
@@ -560,13 +913,15 @@ impl RobocopUI {
                 let is_selected = absolute_index == selected_link;
 
                 let marker = if is_selected { "►" } else { " " };
-                let content = format!("{} [{}] {}", marker, link.index, link.text);
+                let content = format!("{} {}", marker, link.label());
 
                 let style = if is_selected {
                     Style::default()
                         .fg(CONSOLE_BLACK)
                         .bg(PRIMARY_AMBER)
                         .add_modifier(Modifier::BOLD)
+                } else if link.header {
+                    Style::default().fg(PRIMARY_AMBER).add_modifier(Modifier::BOLD)
                 } else {
                     Style::default().fg(SYSTEM_GREEN)
                 };
@@ -582,7 +937,7 @@ impl RobocopUI {
                     Block::default()
                         .borders(Borders::ALL)
                         .border_style(Style::default().fg(DARK_CHROME))
-                        .title("NAVIGATION LINKS")
+                        .title(title)
                         .title_style(Style::default().fg(STEEL_GRAY)),
                 )
                 .highlight_style(
@@ -593,9 +948,32 @@ impl RobocopUI {
                 ),
             area,
         );
+
+        hyperlink::list_links(
+            Block::default().borders(Borders::ALL).inner(area),
+            &links[start_index..end_index],
+            area.width.saturating_sub(6) as usize,
+            |link| format!("  {}", link.label()),
+        )
     }
 
-    fn render_status_bar(f: &mut Frame, area: Rect) {
+    fn render_status_bar(f: &mut Frame, area: Rect, notice: Option<&str>) {
+        if let Some(notice) = notice {
+            f.render_widget(
+                Paragraph::new(notice.to_uppercase())
+                    .style(Style::default().fg(SYSTEM_GREEN))
+                    .block(
+                        Block::default()
+                            .borders(Borders::ALL)
+                            .border_style(Style::default().fg(DARK_CHROME))
+                            .title("SYSTEM MESSAGE")
+                            .title_style(Style::default().fg(STEEL_GRAY)),
+                    ),
+                area,
+            );
+            return;
+        }
+
         // Corporate command interface
         let command_line = vec![
             Line::from(vec![
@@ -649,7 +1027,7 @@ impl RobocopUI {
             height: area.height - 3,
         };
 
-        let items: Vec<ListItem> = entries
+        let mut items: Vec<ListItem> = entries
             .iter()
             .enumerate()
             .map(|(i, entry)| {
@@ -661,11 +1039,23 @@ impl RobocopUI {
                     Style::default().fg(DATA_WHITE)
                 };
 
-                let content = format!("{} {} - {}", marker, entry.title, entry.url);
+                let content = format!(
+                    "{} [{}] {} - {}",
+                    marker,
+                    entry.age_label(),
+                    entry.title,
+                    entry.url
+                );
                 let wrapped_content = fill(&content, content_area.width.saturating_sub(4) as usize);
                 ListItem::new(wrapped_content).style(style)
             })
             .collect();
+        if items.is_empty() {
+            items.push(
+                ListItem::new("  NO ACCESS RECORDS ON FILE. PRESS ANY KEY TO ENTER AN ADDRESS.")
+                    .style(Style::default().fg(STEEL_GRAY)),
+            );
+        }
 
         f.render_widget(
             List::new(items)
@@ -700,7 +1090,239 @@ impl RobocopUI {
         );
     }
 
-    fn render_url_input(f: &mut Frame, input: &str) {
+    fn render_bookmarks(f: &mut Frame, entries: &[BookmarkEntry], filter: &str, selected: usize) {
+        let area = f.size();
+
+        let header_area = Rect {
+            x: 0,
+            y: 0,
+            width: area.width,
+            height: 1,
+        };
+
+        f.render_widget(
+            Paragraph::new("═══════════ TARGET DOSSIER INDEX ═══════════")
+                .style(Style::default().fg(CHROME_BLUE).bg(CONSOLE_BLACK))
+                .alignment(Alignment::Center),
+            header_area,
+        );
+
+        let content_area = Rect {
+            x: 0,
+            y: 1,
+            width: area.width,
+            height: area.height.saturating_sub(3),
+        };
+
+        let mut items: Vec<ListItem> = entries
+            .iter()
+            .enumerate()
+            .map(|(i, entry)| {
+                let is_selected = i == selected;
+                let marker = if is_selected { "►" } else { " " };
+                let style = if is_selected {
+                    Style::default().fg(PRIMARY_AMBER).add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(DATA_WHITE)
+                };
+
+                let content = format!(
+                    "{} {} - {} {}",
+                    marker,
+                    entry.title,
+                    entry.url,
+                    entry.tags_label().to_uppercase()
+                );
+                let wrapped_content = fill(&content, content_area.width.saturating_sub(4) as usize);
+                ListItem::new(wrapped_content).style(style)
+            })
+            .collect();
+        let mut state = ListState::default();
+        if items.is_empty() {
+            let message = if filter.trim().is_empty() {
+                "  NO DOSSIERS ON FILE. PRESS A ON A PAGE TO FILE ONE."
+            } else {
+                "  NO DOSSIER MATCHES ALL CLASSIFICATIONS."
+            };
+            items.push(ListItem::new(message).style(Style::default().fg(STEEL_GRAY)));
+        } else {
+            state.select(Some(selected));
+        }
+
+        let title = format!("CLASSIFICATION FILTER: {}_", filter.to_uppercase());
+        f.render_stateful_widget(
+            List::new(items)
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_style(Style::default().fg(DARK_CHROME))
+                        .title(title)
+                        .title_style(Style::default().fg(STEEL_GRAY)),
+                )
+                .style(Style::default().fg(DATA_WHITE)),
+            content_area,
+            &mut state,
+        );
+
+        let footer_area = Rect {
+            x: 0,
+            y: area.height.saturating_sub(2),
+            width: area.width,
+            height: 2.min(area.height),
+        };
+
+        f.render_widget(
+            Paragraph::new(ui_common::BOOKMARKS_HELP.to_uppercase())
+                .style(Style::default().fg(SYSTEM_GREEN))
+                .alignment(Alignment::Center)
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_style(Style::default().fg(DARK_CHROME)),
+                ),
+            footer_area,
+        );
+    }
+
+    fn render_settings(
+        f: &mut Frame,
+        items: &[SettingsItem],
+        selected: usize,
+        status: Option<&str>,
+    ) {
+        let area = f.size();
+
+        f.render_widget(
+            Paragraph::new("═══════════ SYSTEM PARAMETERS ═══════════")
+                .style(Style::default().fg(CHROME_BLUE).bg(CONSOLE_BLACK))
+                .alignment(Alignment::Center),
+            Rect {
+                x: 0,
+                y: 0,
+                width: area.width,
+                height: 1,
+            },
+        );
+
+        let list_items: Vec<ListItem> = items
+            .iter()
+            .enumerate()
+            .map(|(i, item)| {
+                let is_selected = i == selected;
+                let marker = if is_selected { "►" } else { " " };
+                let style = if is_selected {
+                    Style::default().fg(PRIMARY_AMBER).add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(DATA_WHITE)
+                };
+                ListItem::new(Line::from(vec![
+                    Span::styled(format!("{} {:<32} ", marker, item.label.to_uppercase()), style),
+                    Span::styled(item.value.to_uppercase(), Style::default().fg(SYSTEM_GREEN)),
+                ]))
+            })
+            .collect();
+
+        f.render_widget(
+            List::new(list_items).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(DARK_CHROME))
+                    .title("CONFIGURATION MATRIX")
+                    .title_style(Style::default().fg(STEEL_GRAY)),
+            ),
+            Rect {
+                x: 0,
+                y: 1,
+                width: area.width,
+                height: area.height.saturating_sub(4),
+            },
+        );
+
+        let footer = status
+            .map(str::to_uppercase)
+            .unwrap_or_else(|| "[↑↓] SELECT  [←→] ADJUST  [W] COMMIT  [ESC] EXIT".to_string());
+        f.render_widget(
+            Paragraph::new(footer)
+                .style(Style::default().fg(SYSTEM_GREEN))
+                .alignment(Alignment::Center)
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_style(Style::default().fg(DARK_CHROME)),
+                ),
+            Rect {
+                x: 0,
+                y: area.height.saturating_sub(3),
+                width: area.width,
+                height: 3,
+            },
+        );
+    }
+
+    fn render_info(f: &mut Frame, title: &str, items: &[SettingsItem]) {
+        let area = f.size();
+
+        f.render_widget(
+            Paragraph::new("═══════════ UNIT DIAGNOSTICS ═══════════")
+                .style(Style::default().fg(CHROME_BLUE).bg(CONSOLE_BLACK))
+                .alignment(Alignment::Center),
+            Rect {
+                x: 0,
+                y: 0,
+                width: area.width,
+                height: 1,
+            },
+        );
+
+        // Values keep their case: paths must stay copyable
+        let list_items: Vec<ListItem> = items
+            .iter()
+            .map(|item| {
+                ListItem::new(Line::from(vec![
+                    Span::styled(
+                        format!("  {:<20} ", item.label.to_uppercase()),
+                        Style::default().fg(DATA_WHITE),
+                    ),
+                    Span::styled(item.value.clone(), Style::default().fg(SYSTEM_GREEN)),
+                ]))
+            })
+            .collect();
+
+        f.render_widget(
+            List::new(list_items).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(DARK_CHROME))
+                    .title(title)
+                    .title_style(Style::default().fg(STEEL_GRAY)),
+            ),
+            Rect {
+                x: 0,
+                y: 1,
+                width: area.width,
+                height: area.height.saturating_sub(4),
+            },
+        );
+
+        f.render_widget(
+            Paragraph::new("[ANY KEY] EXIT")
+                .style(Style::default().fg(SYSTEM_GREEN))
+                .alignment(Alignment::Center)
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_style(Style::default().fg(DARK_CHROME)),
+                ),
+            Rect {
+                x: 0,
+                y: area.height.saturating_sub(3),
+                width: area.width,
+                height: 3,
+            },
+        );
+    }
+
+    fn render_input(f: &mut Frame, title: &str, label: &str, input: &TextInput) {
         let area = f.size();
         let popup_area = Rect {
             x: area.width / 6,
@@ -709,17 +1331,25 @@ impl RobocopUI {
             height: 6,
         };
 
+        let style = Style::default().fg(DATA_WHITE);
+        let width = popup_area.width.saturating_sub(2) as usize;
+        let lines = vec![
+            Line::from(label.to_string()),
+            Line::from(""),
+            Line::from(ui_common::input_spans(input, width, style)),
+        ];
+
         f.render_widget(Clear, popup_area);
 
         f.render_widget(
-            Paragraph::new(format!("ENTER NETWORK ADDRESS:\n\n{}", input))
-                .style(Style::default().fg(DATA_WHITE))
+            Paragraph::new(lines)
+                .style(style)
                 .alignment(Alignment::Left)
                 .block(
                     Block::default()
                         .borders(Borders::ALL)
                         .border_style(Style::default().fg(PRIMARY_AMBER))
-                        .title("NETWORK INPUT")
+                        .title(title.to_string())
                         .title_style(Style::default().fg(PRIMARY_AMBER)),
                 ),
             popup_area,
@@ -841,7 +1471,7 @@ impl RobocopUI {
         );
     }
 
-    fn render_error(f: &mut Frame, message: &str) {
+    fn render_error(f: &mut Frame, message: &str, emoji: bool) {
         let area = f.size();
         let popup_area = Rect {
             x: area.width / 8,
@@ -868,7 +1498,7 @@ impl RobocopUI {
                 Block::default()
                     .borders(Borders::ALL)
                     .border_style(Style::default().fg(WARNING_RED))
-                    .title("⚠ SYSTEM ALERT ⚠")
+                    .title(emoji::chrome("⚠ SYSTEM ALERT ⚠", emoji).into_owned())
                     .title_style(
                         Style::default()
                             .fg(WARNING_RED)
@@ -879,6 +1509,40 @@ impl RobocopUI {
         );
     }
 
+    fn render_confirm(f: &mut Frame, message: &str) {
+        let area = f.size();
+        let popup_area = Rect {
+            x: area.width / 6,
+            y: area.height / 2 - 4,
+            width: area.width * 2 / 3,
+            height: 8,
+        };
+
+        f.render_widget(Clear, popup_area);
+
+        f.render_widget(
+            Paragraph::new(format!(
+                "{}\n\n[Y] AUTHORIZE    [ANY KEY] ABORT",
+                message.to_uppercase()
+            ))
+            .style(
+                Style::default()
+                    .fg(PRIMARY_AMBER)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true })
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(PRIMARY_AMBER))
+                    .title("AUTHORIZATION REQUIRED")
+                    .title_style(Style::default().fg(PRIMARY_AMBER)),
+            ),
+            popup_area,
+        );
+    }
+
     fn update_links_scroll(&mut self) {
         self.update_links_scroll_with_height(10);
     }
@@ -888,23 +1552,29 @@ impl RobocopUI {
             ui_common::update_links_scroll(self.selected_link, self.links_scroll, visible_height);
     }
 
-    fn update_max_scroll(&mut self, summary: &str) {
+    fn update_max_scroll(&mut self, summary: &str, raw: bool) {
         let terminal_size = self
             .terminal
             .size()
             .unwrap_or(ratatui::layout::Rect::new(0, 0, 80, 24));
 
         // Match render_summary calculations exactly
-        let content_width = terminal_size.width * 70 / 100; // 70% for content area
+        let content_width = ui_common::content_width(terminal_size.width, self.content_percent);
         let width = content_width.saturating_sub(4) as usize; // same as area.width.saturating_sub(4)
         let content_height = terminal_size.height.saturating_sub(1 + 4 + 3); // header + info + status
         let visible_height = content_height.saturating_sub(2) as usize; // borders
 
+        let (width, _) = ui_common::text_width(width, self.max_width);
+        self.summary_width = width;
+
         self.max_scroll = ui_common::calculate_max_scroll_for_markdown(
             summary,
             width,
+            self.line_spacing,
             visible_height,
+            raw,
             Self::style_markdown_element,
         );
+        self.scroll_position = self.scroll_position.min(self.max_scroll);
     }
 }
\ No newline at end of file